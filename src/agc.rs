@@ -11,38 +11,71 @@ use futuresdr::runtime::Result;
 use futuresdr::runtime::StreamIo;
 use futuresdr::runtime::StreamIoBuilder;
 use futuresdr::runtime::WorkIo;
+use num_traits::ToPrimitive;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
 
-/* #[cfg(feature = "telemetry")]
-use {
-    std::sync::LazyLock, telemetry::opentelemetry::global,
-    telemetry::opentelemetry::metrics::Gauge, telemetry::opentelemetry::KeyValue,
-};
-
-#[cfg(feature = "telemetry")]
-static AGC_GAUGE: LazyLock<Gauge<f64>> = LazyLock::new(|| {
-    global::meter("AGC_METER")
-        .f64_gauge("agc_gauge")
-        .with_description("Gauge to measure AGC parameters")
-        .with_unit("dB")
-        .init()
-}); */
+use crate::serde_pmt::config::{get_config, set_config, set_config_field, Configurable};
+use crate::serde_pmt::to_pmt;
+
+/// Tolerance on the output power, relative to the reference power, to auto lock the gain.
+const AUTO_LOCK_TOLERANCE: f32 = 0.05;
 
 /// Automatic Gain Control Block
+///
+/// The power of the input is estimated as |x|² averaged over a window of samples,
+/// so that real and complex samples are handled alike. The gain follows the one
+/// bringing this power to the reference power, with separate rates when reducing the
+/// gain (attack) or increasing it (decay). [`AgcMode`] selects other gain control loops.
+///
+/// # Inputs
+///
+/// `in`: Input stream of items, implementing [`ComplexFloat`]
+///
+/// # Outputs
+///
+/// - `out`: Leveled output items of same type as `in` stream.
+/// - `gain`: Gain applied to each item, as `f32`, when enabled with [`AgcBuilder::gain_output`].
+///
+/// # Messages
+///
+/// - output `state`: the [`AgcState`] every `report_interval` seconds of samples, when set.
+/// - input `get_state`: returns the current [`AgcState`].
+/// - output `hw_gain`: gain commands for the front-end in dB, as [`Pmt::F64`], with
+///   [`AgcBuilder::hardware_gain`]. They suit the `gain` handler of FutureSDR's Seify source.
+///
+/// The states are [`Pmt::MapStrPmt`], see [`serde_pmt`](crate::serde_pmt) to read them back.
+///
+/// # Usage
+/// ```
+/// use fsdr_blocks::agc::AgcBuilder;
+/// use futuresdr::num_complex::Complex32;
+///
+/// let agc = AgcBuilder::<Complex32>::new()
+///     .sample_rate(48_000.0)
+///     .attack(0.001)
+///     .decay(0.1)
+///     .window(64)
+///     .gain_output(true)
+///     .report_interval(0.1)
+///     .build();
+/// ```
 pub struct Agc<T> {
-    /// Minimum value that has to be reached in order for AGC to start adjusting gain.
-    squelch: f32,
-    /// maximum gain value
-    max_gain: f32,
-    /// initial gain value.
-    gain: f32,
-    /// reference value to adjust signal power to.
-    reference_power: f32,
-    /// the update rate of the loop.
-    adjustment_rate: f32,
-    /// Set when gain should not be adjusted anymore, but rather be locked to the current value
-    gain_lock: bool,
-    /// Set when gain should be automatically locked, when reference power is reached.
-    auto_lock: bool,
+    config: AgcConfig,
+    power: PowerWindow,
+    delay: PeakDelayLine<T>,
+    /// per sample rate at which the gain is reduced
+    attack_rate: f32,
+    /// per sample rate at which the gain is increased
+    decay_rate: f32,
+    gain_output: bool,
+    state: AgcState,
+    /// samples since the last `state` message
+    unreported: usize,
+    /// set once the current front-end gain is commanded
+    hardware_announced: bool,
+    /// samples until the last front-end gain step applies to the input, and the step in dB
+    hardware_pending: Option<(usize, f32)>,
     _type: std::marker::PhantomData<T>,
 }
 
@@ -56,17 +89,30 @@ where
     /// - `squelch`: surpress anything below this level
     /// - `max_gain`: maximum gain setting
     /// - `gain`: initial gain setting
+    /// - `adjustment_rate`: rate at which the gain is updated on each sample
     /// - `reference_power`: target power level
     /// - `gain_lock`: lock gain to fixed value
     /// - `auto_lock`: lock gain, when reference power is reached
     ///
+    /// See [`AgcBuilder`] for the averaging window and time constants in seconds.
+    ///
     /// ## Message Handler
     ///
     /// - `auto_lock`: set `auto_lock` parameter with a [`Pmt::Bool`].
     /// - `gain_lock`: set `gain_lock` parameter with a [`Pmt::Bool`].
-    /// - `max_gain`: set `max_gain` parameter with a [`Pmt::F32`].
-    /// - `adjustment_rate`: set `adjustment_rate` with a [`Pmt::F32`].
-    /// - `reference_power`: set `reference_power` with a [`Pmt::F32`].
+    /// - `max_gain`: set `max_gain` parameter with a number.
+    /// - `adjustment_rate`: set `adjustment_rate` with a number.
+    /// - `reference_power`: set `reference_power` with a number.
+    /// - `attack`, `decay`: set the time constants in seconds with a number, or [`Pmt::Null`]
+    ///   to use `adjustment_rate` instead.
+    /// - `config`: set any fields of the [`AgcConfig`] with a [`Pmt::MapStrPmt`].
+    /// - `get_config`: get the current [`AgcConfig`], including the current gain.
+    ///
+    /// See [`serde_pmt::config`](crate::serde_pmt::config) for the replies.
+    ///
+    /// [`Pmt::Bool`]: futuresdr::runtime::Pmt::Bool
+    /// [`Pmt::Null`]: futuresdr::runtime::Pmt::Null
+    /// [`Pmt::MapStrPmt`]: futuresdr::runtime::Pmt::MapStrPmt
     ///
     /// ## Stream Input
     /// - `in`: Input stream of items, implementing [`ComplexFloat`]
@@ -83,165 +129,512 @@ where
         gain_lock: bool,
         auto_lock: bool,
     ) -> Block {
-        assert!(max_gain >= 0.0);
-        assert!(squelch >= 0.0);
+        AgcBuilder::<T>::new()
+            .squelch(squelch)
+            .max_gain(max_gain)
+            .gain(gain)
+            .adjustment_rate(adjustment_rate)
+            .reference_power(reference_power)
+            .gain_lock(gain_lock)
+            .auto_lock(auto_lock)
+            .build()
+    }
+
+    fn with_config(config: AgcConfig, gain_output: bool) -> Block {
+        if let Err(e) = check(&config) {
+            panic!("invalid AGC config: {e}");
+        }
+
+        let mut agc = Agc {
+            power: PowerWindow::new(config.window),
+            delay: PeakDelayLine::new(),
+            state: AgcState {
+                gain: config.gain,
+                input_power: 0.0,
+                output_power: 0.0,
+                gain_lock: config.gain_lock,
+                squelched: true,
+            },
+            config,
+            attack_rate: 0.0,
+            decay_rate: 0.0,
+            gain_output,
+            unreported: 0,
+            hardware_announced: false,
+            hardware_pending: None,
+            _type: std::marker::PhantomData,
+        };
+        agc.update_rates();
+
+        let sio = StreamIoBuilder::new()
+            .add_input::<T>("in")
+            .add_output::<T>("out");
+        let sio = if gain_output {
+            sio.add_output::<f32>("gain")
+        } else {
+            sio
+        };
 
         Block::new(
             BlockMetaBuilder::new("AGC").build(),
-            StreamIoBuilder::new()
-                .add_input::<T>("in")
-                .add_output::<T>("out")
-                .build(),
+            sio.build(),
             MessageIoBuilder::<Self>::new()
-                .add_input("auto_lock", Self::auto_lock)
-                .add_input("gain_lock", Self::gain_lock)
-                .add_input("max_gain", Self::max_gain)
-                .add_input("adjustment_rate", Self::adjustment_rate)
-                .add_input("reference_power", Self::reference_power)
+                .add_input("auto_lock", set_config_field("auto_lock"))
+                .add_input("gain_lock", set_config_field("gain_lock"))
+                .add_input("max_gain", set_config_field("max_gain"))
+                .add_input("adjustment_rate", set_config_field("adjustment_rate"))
+                .add_input("reference_power", set_config_field("reference_power"))
+                .add_input("attack", set_config_field("attack"))
+                .add_input("decay", set_config_field("decay"))
+                .add_input("config", set_config)
+                .add_input("get_config", get_config)
+                .add_input("get_state", Self::get_state)
+                .add_output("state")
+                .add_output("hw_gain")
                 .build(),
-            Agc {
-                squelch,
-                max_gain,
-                gain,
-                reference_power,
-                adjustment_rate,
-                gain_lock,
-                auto_lock,
-                _type: std::marker::PhantomData,
-            },
+            agc,
         )
     }
 
+    fn update_rates(&mut self) {
+        let rate = |time_constant: Option<f32>| match time_constant {
+            Some(t) => time_rate(t, self.config.sample_rate),
+            None => self.config.adjustment_rate,
+        };
+        self.attack_rate = rate(self.config.attack);
+        self.decay_rate = rate(self.config.decay);
+    }
+
+    /// Number of samples between two `state` messages
+    fn report_period(&self) -> Option<usize> {
+        self.config
+            .report_interval
+            .map(|t| (t * self.config.sample_rate).round() as usize)
+    }
+
     #[message_handler]
-    async fn auto_lock(
+    async fn get_state(
         &mut self,
         _io: &mut WorkIo,
         _mio: &mut MessageIo<Self>,
         _meta: &mut BlockMeta,
-        p: Pmt,
+        _p: Pmt,
     ) -> Result<Pmt> {
-        if let Pmt::Bool(l) = p {
-            self.auto_lock = l;
-            Ok(Pmt::Ok)
-        } else {
-            Ok(Pmt::InvalidValue)
+        Ok(to_pmt(&self.state)?)
+    }
+
+    /// Level a new input sample, returning the next output sample, if any.
+    #[inline(always)]
+    fn process(&mut self, input: T) -> Option<T> {
+        let power = sample_power(input);
+        match self.config.mode {
+            AgcMode::FeedForward { lookahead } => {
+                self.delay.push(input, power);
+                if self.delay.len() < lookahead {
+                    return None;
+                }
+                self.drain()
+            }
+            _ => {
+                let average = self.power.push(power);
+                Some(self.scale(input, power, average))
+            }
         }
     }
 
-    #[message_handler]
-    async fn gain_lock(
-        &mut self,
-        _io: &mut WorkIo,
-        _mio: &mut MessageIo<Self>,
-        _meta: &mut BlockMeta,
-        p: Pmt,
-    ) -> Result<Pmt> {
-        if let Pmt::Bool(l) = p {
-            self.gain_lock = l;
-            Ok(Pmt::Ok)
-        } else {
-            Ok(Pmt::InvalidValue)
+    /// Level the next sample of the feed-forward delay line, if any.
+    fn drain(&mut self) -> Option<T> {
+        let (sample, peak) = self.delay.pop()?;
+        Some(self.scale(sample, peak, peak))
+    }
+
+    #[inline(always)]
+    fn scale(&mut self, input: T, instant_power: f32, input_power: f32) -> T {
+        let squelched = input_power <= self.config.squelch;
+        let feed_forward = matches!(self.config.mode, AgcMode::FeedForward { .. });
+        if feed_forward && !squelched && !self.config.gain_lock {
+            self.update_gain(instant_power, input_power);
         }
+        self.state = AgcState {
+            gain: self.config.gain,
+            input_power,
+            output_power: if squelched {
+                0.0
+            } else {
+                input_power * self.config.gain.powi(2)
+            },
+            gain_lock: self.config.gain_lock,
+            squelched,
+        };
+        if squelched {
+            return T::zero();
+        }
+
+        let output = input * T::from(self.config.gain).unwrap();
+        if self.config.gain_lock {
+            return output;
+        }
+        if !feed_forward {
+            self.update_gain(instant_power, input_power);
+        }
+
+        if self.config.auto_lock {
+            let output_power = input_power * self.config.gain.powi(2);
+            if (output_power / self.config.reference_power - 1.0).abs() < AUTO_LOCK_TOLERANCE {
+                self.config.gain_lock = true;
+                //debug!("Locked gain at at {}", self.config.gain)
+            }
+        }
+        output
     }
 
-    #[message_handler]
-    async fn max_gain(
-        &mut self,
-        _io: &mut WorkIo,
-        _mio: &mut MessageIo<Self>,
-        _meta: &mut BlockMeta,
-        p: Pmt,
-    ) -> Result<Pmt> {
-        if let Pmt::F32(r) = p {
-            self.max_gain = r;
-            Ok(Pmt::Ok)
-        } else {
-            Ok(Pmt::InvalidValue)
+    /// Compensate a front-end gain step, once it applies to the input.
+    #[inline(always)]
+    fn compensate_hardware(&mut self) {
+        match self.hardware_pending.as_mut() {
+            Some((0, step)) => {
+                let factor = 10f32.powf(*step / 10.0);
+                self.config.gain /= factor.sqrt();
+                self.power.scale(factor);
+                self.hardware_pending = None;
+            }
+            Some((left, _)) => *left -= 1,
+            None => {}
         }
     }
 
-    #[message_handler]
-    async fn adjustment_rate(
-        &mut self,
-        _io: &mut WorkIo,
-        _mio: &mut MessageIo<Self>,
-        _meta: &mut BlockMeta,
-        p: Pmt,
-    ) -> Result<Pmt> {
-        if let Pmt::F32(r) = p {
-            self.adjustment_rate = r;
-            Ok(Pmt::Ok)
+    /// Next front-end gain to command, if any
+    #[inline(always)]
+    fn steer_hardware(&mut self) -> Option<f32> {
+        let hardware_gain = self.config.hardware_gain.as_mut()?;
+        if self.hardware_pending.is_some() || self.state.squelched || self.config.gain_lock {
+            return None;
+        }
+        let digital = 20.0 * self.config.gain.log10();
+        let target = if digital > hardware_gain.hysteresis {
+            (hardware_gain.gain + hardware_gain.step).min(hardware_gain.max)
+        } else if digital < -hardware_gain.hysteresis {
+            (hardware_gain.gain - hardware_gain.step).max(hardware_gain.min)
         } else {
-            Ok(Pmt::InvalidValue)
+            return None;
+        };
+        if target == hardware_gain.gain {
+            return None;
         }
+        self.hardware_pending = Some((hardware_gain.latency, target - hardware_gain.gain));
+        hardware_gain.gain = target;
+        Some(target)
     }
 
-    #[message_handler]
-    async fn reference_power(
-        &mut self,
-        _io: &mut WorkIo,
-        _mio: &mut MessageIo<Self>,
-        _meta: &mut BlockMeta,
-        p: Pmt,
-    ) -> Result<Pmt> {
-        if let Pmt::F32(r) = p {
-            self.reference_power = r;
-            Ok(Pmt::Ok)
+    /// Gain bringing `power` to the reference power
+    fn target_gain(&self, power: f32) -> f32 {
+        let target = (self.config.reference_power / power).sqrt();
+        if self.config.max_gain > 0.0 {
+            target.min(self.config.max_gain)
         } else {
-            Ok(Pmt::InvalidValue)
+            target
         }
     }
 
     #[inline(always)]
-    fn scale(&mut self, input: T) -> T {
-        let output = input * T::from(self.gain).unwrap();
-        // if the input power is very low or very high compared to the reference power, we still want to reach the reference power quickly.
-        // Thus we should make the gain also dependent on the input_power in relation to the reference power.
-
-        // Gain is only exactly 1.0 when the AGC block is freshly initialized
-        // We then can set the gain to a suitable value to scale
-        // the input power to e.g. 99% of the reference power immediately.
-        // if self.gain == 1.0 {
-        //     self.gain = (self.reference_power / input.abs().to_f32().unwrap()) * 0.99;
-        // }
-
-        // The gain adjustment rate should not be fixed, but depending
-        // on the input power in relation to the reference power (Thus actually the gain).
-        // We dont want the gain to be adjusted very strongly on rather weak signals,
-        // as it might make them being flattened out after a short time.
-        // Thus, if we have a high gain factor ( a multitude of 1.0 or very close to zero) -> log10(gain).abs() is:
-        //   - getting closer to zero, we want bigger changes -> higher adjustment_rate value
-        //   - getting bigger, we want smaller changes -> smaller adjustment_rate value
-        let dynamic_adjustment_rate = self.adjustment_rate.powf(self.gain.log10().abs());
-
-        let output_power = output.to_f32().unwrap().powi(2);
-
-        if self.auto_lock && !self.gain_lock {
-            let input_power = input.to_f32().unwrap().powi(2);
-            // Two scenarios exist here:
-            if input_power > self.reference_power {
-                // 1. Input power is greater than reference power (We are scaling the signal down)
-                //    - As soon as the output power is smaller than the reference power, we lock the gain.
-                if output_power < self.reference_power {
-                    self.gain_lock = true;
-                    //debug!("Locked gain at at {}", self.gain)
+    fn update_gain(&mut self, instant_power: f32, input_power: f32) {
+        let gain = self.config.gain;
+        match self.config.mode {
+            AgcMode::Feedback => {
+                let target = self.target_gain(input_power);
+                if target.is_finite() {
+                    let rate = if target < gain {
+                        self.attack_rate
+                    } else {
+                        self.decay_rate
+                    };
+                    self.config.gain += (target - gain) * rate;
                 }
-            } else {
-                // 2. Input power is smaller than reference power (We are scaling the signal up)
-                //    - As soon as the output power is greater than the reference power, we lock the gain.
-                if output_power > self.reference_power {
-                    self.gain_lock = true;
-                    //debug!("Locked gain at at {}", self.gain)
+            }
+            AgcMode::FeedForward { .. } => {
+                let target = self.target_gain(input_power);
+                if target.is_finite() {
+                    self.config.gain = target;
+                }
+            }
+            AgcMode::DualLoop => {
+                let fast = self.target_gain(instant_power);
+                if fast < gain {
+                    self.config.gain += (fast - gain) * self.attack_rate;
+                } else {
+                    // the slow loop never raises the gain above the fast loop
+                    let slow = self.target_gain(input_power);
+                    if slow.is_finite() {
+                        self.config.gain = (gain + (slow - gain) * self.decay_rate).min(fast);
+                    }
                 }
             }
         }
+    }
+}
+
+/// Power |x|² of a real or complex sample
+#[inline(always)]
+pub(crate) fn sample_power<T: ComplexFloat>(input: T) -> f32 {
+    input.abs().to_f32().unwrap_or_default().powi(2)
+}
+
+/// Rate of a one pole filter with time constant `time_constant`, in seconds.
+fn time_rate(time_constant: f32, sample_rate: f32) -> f32 {
+    if time_constant <= 0.0 {
+        1.0
+    } else {
+        1.0 - (-1.0 / (time_constant * sample_rate)).exp()
+    }
+}
+
+/// Delay line of the feed-forward mode, keeping track of the peak power of its samples
+struct PeakDelayLine<T> {
+    samples: VecDeque<T>,
+    /// candidate peaks, as (sample number, power), with decreasing powers
+    peaks: VecDeque<(u64, f32)>,
+    pushed: u64,
+}
 
-        if !self.gain_lock {
-            // Slow down AGC adjustments 1/adjustment_rate times
-            self.gain *=
-                1.0 + (self.reference_power / output_power).log10() * dynamic_adjustment_rate;
+impl<T> PeakDelayLine<T> {
+    fn new() -> PeakDelayLine<T> {
+        PeakDelayLine {
+            samples: VecDeque::new(),
+            peaks: VecDeque::new(),
+            pushed: 0,
         }
-        output
+    }
+
+    fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    fn push(&mut self, sample: T, power: f32) {
+        while self.peaks.back().is_some_and(|(_, p)| *p <= power) {
+            self.peaks.pop_back();
+        }
+        self.peaks.push_back((self.pushed, power));
+        self.samples.push_back(sample);
+        self.pushed += 1;
+    }
+
+    /// Oldest sample, with the peak power of the samples from it to the newest one
+    fn pop(&mut self) -> Option<(T, f32)> {
+        let index = self.pushed - self.samples.len() as u64;
+        let sample = self.samples.pop_front()?;
+        let (peak_index, peak) = *self.peaks.front()?;
+        if peak_index == index {
+            self.peaks.pop_front();
+        }
+        Some((sample, peak))
+    }
+}
+
+/// Moving average of the power over a window of samples
+pub(crate) struct PowerWindow {
+    powers: Vec<f32>,
+    index: usize,
+    sum: f64,
+}
+
+impl PowerWindow {
+    pub(crate) fn new(len: usize) -> PowerWindow {
+        PowerWindow {
+            powers: vec![0.0; len],
+            index: 0,
+            sum: 0.0,
+        }
+    }
+
+    /// Add the power of a sample, returning the average power over the window.
+    #[inline(always)]
+    pub(crate) fn push(&mut self, power: f32) -> f32 {
+        self.sum += power as f64 - self.powers[self.index] as f64;
+        self.powers[self.index] = power;
+        self.index = (self.index + 1) % self.powers.len();
+        // clamp rounding errors of the running sum
+        (self.sum.max(0.0) / self.powers.len() as f64) as f32
+    }
+
+    /// Scale the powers in the window, after a change of the front-end gain.
+    fn scale(&mut self, factor: f32) {
+        self.powers.iter_mut().for_each(|p| *p *= factor);
+        self.sum *= factor as f64;
+    }
+}
+
+/// Steering of the analog gain of the front-end by an [`Agc`] block
+///
+/// The front-end gain is stepped up or down, to keep the digital gain within
+/// ±`hysteresis` dB, i.e., to bring the input close to the reference power without
+/// clipping the ADC. Each step is compensated by the digital gain once it applies to the
+/// input, so that the output level stays continuous.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HardwareGain {
+    /// current gain of the front-end, in dB
+    pub gain: f32,
+    /// minimum gain of the front-end, in dB
+    pub min: f32,
+    /// maximum gain of the front-end, in dB
+    pub max: f32,
+    /// gain change of one command, in dB
+    pub step: f32,
+    /// digital gain, in dB, above or below which the front-end gain is changed;
+    /// at least half the step, so that steps do not oscillate
+    pub hysteresis: f32,
+    /// number of samples still received with the previous front-end gain after a command
+    pub latency: usize,
+}
+
+impl HardwareGain {
+    /// Front-end gain between `min` and `max` dB, starting at `min`, changed by `step` dB
+    ///
+    /// The hysteresis defaults to `step` and the latency to 0.
+    pub fn new(min: f32, max: f32, step: f32) -> HardwareGain {
+        HardwareGain {
+            gain: min,
+            min,
+            max,
+            step,
+            hysteresis: step,
+            latency: 0,
+        }
+    }
+
+    fn check(&self) -> std::result::Result<(), String> {
+        if !(self.min <= self.gain && self.gain <= self.max) {
+            return Err("hardware gain must be between its min and max".to_string());
+        }
+        if self.step <= 0.0 {
+            return Err("hardware gain step must be positive".to_string());
+        }
+        if self.hysteresis < self.step / 2.0 {
+            return Err("hardware gain hysteresis must be at least half the step".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Gain control loop of an [`Agc`] block
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum AgcMode {
+    /// The gain follows the power averaged over the window, at the attack and decay rates.
+    #[default]
+    Feedback,
+    /// The gain is set from the peak power of the next `lookahead` samples, so that bursts
+    /// are leveled from their first sample. The stream is delayed by `lookahead - 1` samples,
+    /// and the window is not used.
+    FeedForward { lookahead: usize },
+    /// A fast loop follows the instantaneous power at the attack rate, to reduce the gain
+    /// as soon as a stronger signal starts, while a slow loop follows the power averaged over
+    /// the window at the decay rate, without exceeding the gain of the fast loop.
+    DualLoop,
+}
+
+/// Current state of the [`Agc`] block, reported on its `state` message output
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgcState {
+    /// gain applied to the last sample
+    pub gain: f32,
+    /// power of the input, averaged over the window, or its peak in feed-forward mode
+    pub input_power: f32,
+    /// power of the output, i.e., input power scaled by the gain
+    pub output_power: f32,
+    pub gain_lock: bool,
+    /// set when the input power is below the squelch level
+    pub squelched: bool,
+}
+
+/// Parameters of the [`Agc`] block, settable through its `config` message handler
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgcConfig {
+    /// Minimum power that has to be reached in order for AGC to start adjusting gain.
+    /// See [`PowerSquelch`](crate::squelch::PowerSquelch) for a squelch with hysteresis.
+    pub squelch: f32,
+    /// maximum gain value (0 for unlimited).
+    pub max_gain: f32,
+    /// current gain value.
+    pub gain: f32,
+    /// reference value to adjust signal power to.
+    pub reference_power: f32,
+    /// the update rate of the loop, used when `attack` or `decay` is not set.
+    pub adjustment_rate: f32,
+    /// time constant in seconds to reduce the gain
+    pub attack: Option<f32>,
+    /// time constant in seconds to increase the gain
+    pub decay: Option<f32>,
+    /// sample rate of the stream, for the time constants and the report interval,
+    /// which are counted in samples when it is left to 1
+    pub sample_rate: f32,
+    /// number of samples the power is averaged over
+    pub window: usize,
+    /// interval in seconds between two `state` messages, none to disable them.
+    /// It must span at least one sample.
+    pub report_interval: Option<f32>,
+    /// gain control loop, which cannot be changed once the block is created
+    pub mode: AgcMode,
+    /// steering of the front-end gain, on the `hw_gain` message output
+    pub hardware_gain: Option<HardwareGain>,
+    /// Set when gain should not be adjusted anymore, but rather be locked to the current value
+    pub gain_lock: bool,
+    /// Set when gain should be automatically locked, when reference power is reached.
+    pub auto_lock: bool,
+}
+
+fn check(config: &AgcConfig) -> std::result::Result<(), String> {
+    if config.max_gain < 0.0 {
+        return Err("max_gain must not be negative".to_string());
+    }
+    if config.squelch < 0.0 {
+        return Err("squelch must not be negative".to_string());
+    }
+    if config.window == 0 {
+        return Err("window must not be empty".to_string());
+    }
+    if config.sample_rate <= 0.0 {
+        return Err("sample_rate must be positive".to_string());
+    }
+    if config
+        .report_interval
+        .is_some_and(|t| (t * config.sample_rate).round() < 1.0)
+    {
+        return Err("report_interval must span at least one sample".to_string());
+    }
+    if config.mode == (AgcMode::FeedForward { lookahead: 0 }) {
+        return Err("lookahead must not be empty".to_string());
+    }
+    if let Some(hardware_gain) = &config.hardware_gain {
+        hardware_gain.check()?;
+    }
+    Ok(())
+}
+
+impl<T> Configurable for Agc<T>
+where
+    T: Send + Sync + ComplexFloat + 'static,
+{
+    type Config = AgcConfig;
+
+    fn config(&self) -> AgcConfig {
+        self.config.clone()
+    }
+
+    fn set_config(&mut self, config: AgcConfig) -> std::result::Result<(), String> {
+        check(&config)?;
+        if config.mode != self.config.mode {
+            return Err("mode cannot be changed".to_string());
+        }
+        if config.hardware_gain.map(|h| h.gain) != self.config.hardware_gain.map(|h| h.gain) {
+            // command the new gain, which is then expected on the input
+            self.hardware_pending = None;
+            self.hardware_announced = false;
+        }
+        if config.window != self.config.window {
+            self.power = PowerWindow::new(config.window);
+        }
+        self.config = config;
+        self.update_rates();
+        Ok(())
     }
 }
 
@@ -255,54 +648,74 @@ where
         &mut self,
         io: &mut WorkIo,
         sio: &mut StreamIo,
-        _mio: &mut MessageIo<Self>,
+        mio: &mut MessageIo<Self>,
         _meta: &mut BlockMeta,
     ) -> Result<()> {
+        let finished = sio.input(0).finished();
         let i = sio.input(0).slice::<T>();
         let o = sio.output(0).slice::<T>();
+        let g = if self.gain_output {
+            sio.output(1).slice::<f32>()
+        } else {
+            &mut []
+        };
 
-        let m = std::cmp::min(i.len(), o.len());
-        if m > 0 {
-            for (src, dst) in i.iter().zip(o.iter_mut()) {
-                let input_power = src.to_f32().unwrap().powi(2);
-                if input_power > self.squelch {
-                    *dst = self.scale(*src);
-                } else {
-                    *dst = T::from(0.0).unwrap();
+        let space = if self.gain_output {
+            std::cmp::min(o.len(), g.len())
+        } else {
+            o.len()
+        };
+        if !self.hardware_announced {
+            if let Some(hardware_gain) = &self.config.hardware_gain {
+                mio.post(1, Pmt::F64(hardware_gain.gain as f64)).await;
+            }
+            self.hardware_announced = true;
+        }
+
+        let mut consumed = 0;
+        let mut produced = 0;
+        while produced < space {
+            let output = if consumed < i.len() {
+                consumed += 1;
+                self.compensate_hardware();
+                self.process(i[consumed - 1])
+            } else if finished {
+                match self.drain() {
+                    Some(output) => Some(output),
+                    None => break,
+                }
+            } else {
+                break;
+            };
+
+            if let Some(output) = output {
+                o[produced] = output;
+                if self.gain_output {
+                    g[produced] = self.state.gain;
+                }
+                produced += 1;
+
+                if let Some(period) = self.report_period() {
+                    self.unreported += 1;
+                    if self.unreported >= period {
+                        self.unreported = 0;
+                        mio.post(0, to_pmt(&self.state)?).await;
+                    }
                 }
 
-                /* #[cfg(feature = "telemetry")]
-                if _meta.telemetry_config().active_metrics().contains("agc") {
-                    // info!("Collecting AGC telemetry data");
-                    AGC_GAUGE.record(input_power.into(), &[KeyValue::new("type", "input_power")]);
-                    let output_power = (*dst).to_f32().unwrap().powi(2);
-                    AGC_GAUGE.record(
-                        output_power.into(),
-                        &[KeyValue::new("type", "output_power")],
-                    );
-                    // We need a force_flush() here on the meter_provider to record the exact values and dont aggregate them over time.
-                    // Might have to wait for implementation here: https://github.com/open-telemetry/opentelemetry-specification/issues/617
-                    let _ = futuresdr::runtime::METER_PROVIDER.force_flush();
-                } */
+                if let Some(gain) = self.steer_hardware() {
+                    mio.post(1, Pmt::F64(gain as f64)).await;
+                }
             }
+        }
 
-            /* #[cfg(feature = "telemetry")]
-            if _meta.telemetry_config().active_metrics().contains("agc") {
-                AGC_GAUGE.record(self.squelch.into(), &[KeyValue::new("type", "squelch")]);
-                AGC_GAUGE.record(
-                    self.reference_power.into(),
-                    &[KeyValue::new("type", "reference_power")],
-                );
-                // We need a force_flush() here on the meter_provider to record the exact values and dont aggregate them over time.
-                // Might have to wait for implementation here: https://github.com/open-telemetry/opentelemetry-specification/issues/617
-                let _ = futuresdr::runtime::METER_PROVIDER.force_flush();
-            } */
-
-            sio.input(0).consume(m);
-            sio.output(0).produce(m);
+        sio.input(0).consume(consumed);
+        sio.output(0).produce(produced);
+        if self.gain_output {
+            sio.output(1).produce(produced);
         }
 
-        if sio.input(0).finished() && m == i.len() {
+        if finished && consumed == i.len() && self.delay.len() == 0 {
             io.finished = true;
         }
 
@@ -315,19 +728,8 @@ pub struct AgcBuilder<T>
 where
     T: Send + Sync + ComplexFloat + 'static,
 {
-    squelch: f32,
-    /// maximum gain value (0 for unlimited).
-    max_gain: f32,
-    /// initial gain value.
-    gain: f32,
-    /// reference value to adjust signal power to.
-    reference_power: f32,
-    /// the update rate of the loop.
-    adjustment_rate: f32,
-    /// Set when gain should not be adjusted anymore, but rather be locked to the current value
-    gain_lock: bool,
-    /// Set when gain should be automatically locked, when reference power is reached.
-    auto_lock: bool,
+    config: AgcConfig,
+    gain_output: bool,
     _type: std::marker::PhantomData<T>,
 }
 
@@ -343,68 +745,134 @@ where
     /// - `gain`: 1.0
     /// - `reference_power`: 1.0
     /// - `adjustment_rate`: 0.0001
+    /// - `attack`, `decay`: none, i.e., `adjustment_rate` is used
+    /// - `sample_rate`: 1.0
+    /// - `window`: 1
+    /// - `report_interval`: none
+    /// - `mode`: [`AgcMode::Feedback`]
+    /// - `hardware_gain`: none
+    /// - `gain_output`: false
     /// - `gain_lock`: false
     /// - `auto_lock`: false
     pub fn new() -> AgcBuilder<T> {
         AgcBuilder {
-            squelch: 0.0,
-            max_gain: 65536.0,
-            gain: 1.0,
-            reference_power: 1.0,
-            adjustment_rate: 0.0001,
-            gain_lock: false,
-            auto_lock: false,
+            config: AgcConfig {
+                squelch: 0.0,
+                max_gain: 65536.0,
+                gain: 1.0,
+                reference_power: 1.0,
+                adjustment_rate: 0.0001,
+                attack: None,
+                decay: None,
+                sample_rate: 1.0,
+                window: 1,
+                report_interval: None,
+                mode: AgcMode::Feedback,
+                hardware_gain: None,
+                gain_lock: false,
+                auto_lock: false,
+            },
+            gain_output: false,
             _type: std::marker::PhantomData,
         }
     }
 
-    /// Surpress signals below this level
+    /// Surpress signals below this power level
     pub fn squelch(mut self, squelch: f32) -> AgcBuilder<T> {
-        self.squelch = squelch;
+        self.config.squelch = squelch;
         self
     }
 
-    /// Max gain to use to bring input closer to reference level
+    /// Max gain to use to bring input closer to reference level, 0 for unlimited
     pub fn max_gain(mut self, max_gain: f32) -> AgcBuilder<T> {
-        self.max_gain = max_gain;
+        self.config.max_gain = max_gain;
+        self
+    }
+
+    /// Initial gain
+    pub fn gain(mut self, gain: f32) -> AgcBuilder<T> {
+        self.config.gain = gain;
         self
     }
 
     /// Adjustment rate, i.e., impact of current sample on gain setting
     pub fn adjustment_rate(mut self, adjustment_rate: f32) -> AgcBuilder<T> {
-        self.adjustment_rate = adjustment_rate;
+        self.config.adjustment_rate = adjustment_rate;
         self
     }
 
     /// Targeted power level
     pub fn reference_power(mut self, reference_power: f32) -> AgcBuilder<T> {
-        self.reference_power = reference_power;
+        self.config.reference_power = reference_power;
+        self
+    }
+
+    /// Time constant in seconds to reduce the gain on stronger signals
+    pub fn attack(mut self, attack: f32) -> AgcBuilder<T> {
+        self.config.attack = Some(attack);
+        self
+    }
+
+    /// Time constant in seconds to increase the gain on weaker signals
+    pub fn decay(mut self, decay: f32) -> AgcBuilder<T> {
+        self.config.decay = Some(decay);
+        self
+    }
+
+    /// Sample rate of the stream, for the time constants and the report interval.
+    ///
+    /// Without it, they are counted in samples instead of seconds.
+    pub fn sample_rate(mut self, sample_rate: f32) -> AgcBuilder<T> {
+        self.config.sample_rate = sample_rate;
+        self
+    }
+
+    /// Number of samples the power is averaged over
+    pub fn window(mut self, window: usize) -> AgcBuilder<T> {
+        self.config.window = window;
+        self
+    }
+
+    /// Gain control loop
+    pub fn mode(mut self, mode: AgcMode) -> AgcBuilder<T> {
+        self.config.mode = mode;
+        self
+    }
+
+    /// Steer the gain of the front-end on the `hw_gain` message output
+    pub fn hardware_gain(mut self, hardware_gain: HardwareGain) -> AgcBuilder<T> {
+        self.config.hardware_gain = Some(hardware_gain);
+        self
+    }
+
+    /// Interval in seconds between two `state` messages, or in samples without a
+    /// [`sample_rate`](Self::sample_rate). It must span at least one sample.
+    pub fn report_interval(mut self, report_interval: f32) -> AgcBuilder<T> {
+        self.config.report_interval = Some(report_interval);
+        self
+    }
+
+    /// Add a `gain` stream output, with the gain applied to each item
+    pub fn gain_output(mut self, gain_output: bool) -> AgcBuilder<T> {
+        self.gain_output = gain_output;
         self
     }
 
     /// Fix gain setting, disabling AGC
     pub fn gain_lock(mut self, gain_lock: bool) -> AgcBuilder<T> {
-        self.gain_lock = gain_lock;
+        self.config.gain_lock = gain_lock;
         self
     }
 
     /// Activate gain auto_locking, when the target reference power is reached
     pub fn auto_lock(mut self, auto_lock: bool) -> AgcBuilder<T> {
-        self.auto_lock = auto_lock;
+        self.config.auto_lock = auto_lock;
         self
     }
 
     /// Create [`Agc`] block
     pub fn build(self) -> Block {
-        Agc::<T>::new(
-            self.squelch,
-            self.max_gain,
-            self.gain,
-            self.adjustment_rate,
-            self.reference_power,
-            self.gain_lock,
-            self.auto_lock,
-        )
+        Agc::<T>::with_config(self.config, self.gain_output)
     }
 }
 
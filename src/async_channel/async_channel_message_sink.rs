@@ -0,0 +1,71 @@
+use async_channel::Sender;
+
+use futuresdr::macros::message_handler;
+use futuresdr::runtime::Block;
+use futuresdr::runtime::BlockMeta;
+use futuresdr::runtime::BlockMetaBuilder;
+use futuresdr::runtime::Kernel;
+use futuresdr::runtime::MessageIo;
+use futuresdr::runtime::MessageIoBuilder;
+use futuresdr::runtime::Pmt;
+use futuresdr::runtime::Result;
+use futuresdr::runtime::StreamIoBuilder;
+use futuresdr::runtime::WorkIo;
+
+/// Get messages out of a flowgraph into a channel.
+///
+/// Messages are awaited into the channel, so a bounded channel applies backpressure to the
+/// message port. The block finishes on [`Pmt::Finished`], or once the receiver is dropped.
+///
+/// # Message inputs
+///
+/// `in`: Messages forwarded to the channel
+///
+/// # Usage
+/// ```
+/// use async_channel;
+/// use fsdr_blocks::async_channel::AsyncChannelMessageSink;
+/// use futuresdr::runtime::{Flowgraph, Pmt};
+///
+/// let mut fg = Flowgraph::new();
+/// let (tx, rx) = async_channel::bounded::<Pmt>(16);
+///
+/// let telemetry = fg.add_block(AsyncChannelMessageSink::new(tx));
+/// ```
+pub struct AsyncChannelMessageSink {
+    sender: Sender<Pmt>,
+}
+
+impl AsyncChannelMessageSink {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(sender: Sender<Pmt>) -> Block {
+        Block::new(
+            BlockMetaBuilder::new("AsyncChannelMessageSink").build(),
+            StreamIoBuilder::new().build(),
+            MessageIoBuilder::new()
+                .add_input("in", Self::handler)
+                .build(),
+            AsyncChannelMessageSink { sender },
+        )
+    }
+
+    #[message_handler]
+    async fn handler(
+        &mut self,
+        io: &mut WorkIo,
+        _mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        if let Pmt::Finished = p {
+            io.finished = true;
+        } else if self.sender.send(p).await.is_err() {
+            io.finished = true;
+        }
+        Ok(Pmt::Ok)
+    }
+}
+
+#[doc(hidden)]
+#[async_trait]
+impl Kernel for AsyncChannelMessageSink {}
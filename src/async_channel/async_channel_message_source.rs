@@ -0,0 +1,73 @@
+use async_channel::Receiver;
+
+use futuresdr::runtime::Block;
+use futuresdr::runtime::BlockMeta;
+use futuresdr::runtime::BlockMetaBuilder;
+use futuresdr::runtime::Kernel;
+use futuresdr::runtime::MessageIo;
+use futuresdr::runtime::MessageIoBuilder;
+use futuresdr::runtime::Pmt;
+use futuresdr::runtime::Result;
+use futuresdr::runtime::StreamIo;
+use futuresdr::runtime::StreamIoBuilder;
+use futuresdr::runtime::WorkIo;
+
+/// Forward messages received on a channel to a message port of the flowgraph.
+///
+/// The block finishes once all senders are dropped or the channel is closed.
+///
+/// # Message outputs
+///
+/// `out`: Messages pushed into the channel
+///
+/// # Usage
+/// ```
+/// use async_channel;
+/// use fsdr_blocks::async_channel::AsyncChannelMessageSource;
+/// use futuresdr::runtime::{Flowgraph, Pmt};
+///
+/// let mut fg = Flowgraph::new();
+/// let (tx, rx) = async_channel::unbounded::<Pmt>();
+///
+/// let commands = fg.add_block(AsyncChannelMessageSource::new(rx));
+/// tx.try_send(Pmt::F64(100e6)).unwrap();
+/// ```
+pub struct AsyncChannelMessageSource {
+    receiver: Receiver<Pmt>,
+}
+
+impl AsyncChannelMessageSource {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(receiver: Receiver<Pmt>) -> Block {
+        Block::new(
+            BlockMetaBuilder::new("AsyncChannelMessageSource").build(),
+            StreamIoBuilder::new().build(),
+            MessageIoBuilder::new().add_output("out").build(),
+            AsyncChannelMessageSource { receiver },
+        )
+    }
+}
+
+#[doc(hidden)]
+#[async_trait]
+impl Kernel for AsyncChannelMessageSource {
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _sio: &mut StreamIo,
+        mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        match self.receiver.recv().await {
+            Ok(p) => {
+                mio.post(0, p).await;
+                io.call_again = true;
+            }
+            Err(_) => {
+                io.finished = true;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,22 +1,44 @@
-use async_channel::Sender;
+use async_channel::{Sender, TrySendError};
 
+use futuresdr::log::debug;
+use futuresdr::macros::message_handler;
 use futuresdr::runtime::Block;
 use futuresdr::runtime::BlockMeta;
 use futuresdr::runtime::BlockMetaBuilder;
 use futuresdr::runtime::Kernel;
 use futuresdr::runtime::MessageIo;
 use futuresdr::runtime::MessageIoBuilder;
+use futuresdr::runtime::Pmt;
 use futuresdr::runtime::Result;
 use futuresdr::runtime::StreamIo;
 use futuresdr::runtime::StreamIoBuilder;
+use futuresdr::runtime::Tag;
 use futuresdr::runtime::WorkIo;
 
+use crate::stream::OverflowPolicy;
+
 /// Get samples out of a Flowgraph into a channel.
 ///
+/// By default, samples are sent in chunks of whatever size is available.
+/// With [`AsyncChannelSink::with_len_tag`], one chunk is sent per burst instead: a burst
+/// starts at a `Tag::NamedUsize(key, burst_len)` and spans `burst_len` samples.
+/// Samples outside of bursts are discarded, as well as a burst left incomplete
+/// at the end of the stream.
+///
+/// When the channel is full, the configured [`OverflowPolicy`] applies,
+/// [`OverflowPolicy::DropNewest`] by default. See [`AsyncChannelSinkBuilder`].
+/// Chunks carry no tags, so the discontinuities are only reported through the
+/// `dropped` counter.
+///
 /// # Inputs
 ///
 /// `in`: Samples retrieved from teh flowgraph
 ///
+/// # Messages
+///
+/// - input `dropped`: returns the total number of dropped samples as a [`Pmt::U64`]
+/// - output `dropped`: the total number of dropped samples, each time samples are dropped
+///
 /// # Usage
 /// ```
 /// use async_channel;
@@ -33,17 +55,76 @@ use futuresdr::runtime::WorkIo;
 /// ```
 pub struct AsyncChannelSink<T: Send + 'static> {
     sender: Sender<Box<[T]>>,
+    policy: OverflowPolicy,
+    dropped: u64,
+    len_tag: Option<String>,
+    burst: Vec<T>,
+    remaining: usize,
 }
 
 impl<T: Send + Clone + 'static> AsyncChannelSink<T> {
     #[allow(clippy::new_ret_no_self)]
     pub fn new(sender: Sender<Box<[T]>>) -> Block {
-        Block::new(
-            BlockMetaBuilder::new("AsyncChannelSink").build(),
-            StreamIoBuilder::new().add_input::<T>("in").build(),
-            MessageIoBuilder::new().build(),
-            AsyncChannelSink::<T> { sender },
-        )
+        AsyncChannelSinkBuilder::new(sender).build()
+    }
+
+    /// Send one chunk per burst delimited by length tags under `key`
+    /// (usually [`DEFAULT_LEN_TAG_KEY`](crate::async_channel::DEFAULT_LEN_TAG_KEY)).
+    pub fn with_len_tag(sender: Sender<Box<[T]>>, key: &str) -> Block {
+        AsyncChannelSinkBuilder::new(sender).len_tag(key).build()
+    }
+
+    #[message_handler]
+    async fn dropped(
+        &mut self,
+        _io: &mut WorkIo,
+        _mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+        _p: Pmt,
+    ) -> Result<Pmt> {
+        Ok(Pmt::U64(self.dropped))
+    }
+
+    /// Accumulate the samples of `i` belonging to bursts, returning the completed ones.
+    fn bursts(&mut self, i: &[T], tags: &[(usize, usize)]) -> Vec<Box<[T]>> {
+        let mut completed = Vec::new();
+        let mut pos = 0;
+        while pos < i.len() {
+            if self.remaining > 0 {
+                let n = std::cmp::min(self.remaining, i.len() - pos);
+                self.burst.extend_from_slice(&i[pos..pos + n]);
+                pos += n;
+                self.remaining -= n;
+                if self.remaining == 0 {
+                    completed.push(std::mem::take(&mut self.burst).into_boxed_slice());
+                }
+            } else if let Some((index, len)) = tags.iter().find(|(index, _)| *index >= pos) {
+                pos = *index;
+                self.remaining = *len;
+                self.burst.reserve(*len);
+            } else {
+                pos = i.len();
+            }
+        }
+        completed
+    }
+
+    /// Send a chunk according to the overflow policy, returning the number of samples dropped.
+    async fn send(sender: &Sender<Box<[T]>>, policy: OverflowPolicy, chunk: Box<[T]>) -> usize {
+        match policy {
+            OverflowPolicy::Block => {
+                let _ = sender.send(chunk).await;
+                0
+            }
+            OverflowPolicy::DropNewest => match sender.try_send(chunk) {
+                Err(TrySendError::Full(chunk)) => chunk.len(),
+                _ => 0,
+            },
+            OverflowPolicy::DropOldest => match sender.force_send(chunk) {
+                Ok(Some(old)) => old.len(),
+                _ => 0,
+            },
+        }
     }
 }
 
@@ -54,27 +135,110 @@ impl<T: Send + Clone + 'static> Kernel for AsyncChannelSink<T> {
         &mut self,
         io: &mut WorkIo,
         sio: &mut StreamIo,
-        _mio: &mut MessageIo<Self>,
+        mio: &mut MessageIo<Self>,
         _meta: &mut BlockMeta,
     ) -> Result<()> {
         let i = sio.input(0).slice::<T>();
 
-        if !i.is_empty() {
-            match self.sender.try_send(i.into()) {
-                Ok(_) => {
-                    // info!("sent data...");
-                }
-                Err(_err) => {
-                    // info!("{}", err.to_string());
-                }
-            }
-            sio.input(0).consume(i.len());
+        let chunks = if let (Some(key), false) = (&self.len_tag, i.is_empty()) {
+            let mut tags: Vec<(usize, usize)> = sio
+                .input(0)
+                .tags()
+                .iter()
+                .filter_map(|t| match &t.tag {
+                    Tag::NamedUsize(k, len) if k == key && *len > 0 && t.index < i.len() => {
+                        Some((t.index, *len))
+                    }
+                    _ => None,
+                })
+                .collect();
+            tags.sort_unstable();
+            self.bursts(i, &tags)
+        } else if !i.is_empty() {
+            vec![i.into()]
+        } else {
+            Vec::new()
+        };
+        sio.input(0).consume(i.len());
+
+        let mut dropped = 0;
+        for chunk in chunks {
+            dropped += Self::send(&self.sender, self.policy, chunk).await;
+        }
+        if dropped > 0 {
+            self.dropped += dropped as u64;
+            mio.post(0, Pmt::U64(self.dropped)).await;
         }
 
         if sio.input(0).finished() {
+            if self.remaining > 0 {
+                debug!(
+                    "AsyncChannelSink: discarding incomplete burst of {} samples",
+                    self.burst.len()
+                );
+            }
             io.finished = true;
         }
 
         Ok(())
     }
 }
+
+/// Build an [`AsyncChannelSink`].
+///
+/// # Usage
+/// ```
+/// use fsdr_blocks::async_channel::{AsyncChannelSinkBuilder, DEFAULT_LEN_TAG_KEY};
+/// use fsdr_blocks::stream::OverflowPolicy;
+///
+/// let (tx, rx) = async_channel::bounded::<Box<[f32]>>(4);
+/// let blk = AsyncChannelSinkBuilder::new(tx)
+///     .len_tag(DEFAULT_LEN_TAG_KEY)
+///     .overflow(OverflowPolicy::DropOldest)
+///     .build();
+/// ```
+pub struct AsyncChannelSinkBuilder<T: Send + 'static> {
+    sender: Sender<Box<[T]>>,
+    policy: OverflowPolicy,
+    len_tag: Option<String>,
+}
+
+impl<T: Send + Clone + 'static> AsyncChannelSinkBuilder<T> {
+    pub fn new(sender: Sender<Box<[T]>>) -> AsyncChannelSinkBuilder<T> {
+        AsyncChannelSinkBuilder {
+            sender,
+            policy: OverflowPolicy::default(),
+            len_tag: None,
+        }
+    }
+
+    /// Send one chunk per burst delimited by length tags under `key`
+    pub fn len_tag(mut self, key: &str) -> AsyncChannelSinkBuilder<T> {
+        self.len_tag = Some(key.to_string());
+        self
+    }
+
+    pub fn overflow(mut self, policy: OverflowPolicy) -> AsyncChannelSinkBuilder<T> {
+        self.policy = policy;
+        self
+    }
+
+    pub fn build(self) -> Block {
+        Block::new(
+            BlockMetaBuilder::new("AsyncChannelSink").build(),
+            StreamIoBuilder::new().add_input::<T>("in").build(),
+            MessageIoBuilder::<AsyncChannelSink<T>>::new()
+                .add_input("dropped", AsyncChannelSink::dropped)
+                .add_output("dropped")
+                .build(),
+            AsyncChannelSink::<T> {
+                sender: self.sender,
+                policy: self.policy,
+                dropped: 0,
+                len_tag: self.len_tag,
+                burst: Vec::new(),
+                remaining: 0,
+            },
+        )
+    }
+}
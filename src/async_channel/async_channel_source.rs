@@ -10,10 +10,15 @@ use futuresdr::runtime::MessageIoBuilder;
 use futuresdr::runtime::Result;
 use futuresdr::runtime::StreamIo;
 use futuresdr::runtime::StreamIoBuilder;
+use futuresdr::runtime::Tag;
 use futuresdr::runtime::WorkIo;
 
 /// Push samples through a channel into a stream connection.
 ///
+/// With [`AsyncChannelSource::with_len_tag`], the first sample of each chunk is tagged with
+/// `Tag::NamedUsize(key, chunk_len)`, so that chunk boundaries survive in the stream,
+/// e.g. to be regrouped by [`AsyncChannelSink::with_len_tag`](crate::async_channel::AsyncChannelSink::with_len_tag).
+///
 /// # Outputs
 ///
 /// `out`: Samples pushed into the channel
@@ -34,11 +39,22 @@ use futuresdr::runtime::WorkIo;
 pub struct AsyncChannelSource<T: Send + 'static> {
     receiver: Receiver<Box<[T]>>,
     current: Option<(Box<[T]>, usize)>,
+    len_tag: Option<String>,
 }
 
 impl<T: Send + 'static> AsyncChannelSource<T> {
     #[allow(clippy::new_ret_no_self)]
     pub fn new(receiver: Receiver<Box<[T]>>) -> Block {
+        Self::create(receiver, None)
+    }
+
+    /// Tag the start of each chunk with its length, under `key`
+    /// (usually [`DEFAULT_LEN_TAG_KEY`](crate::async_channel::DEFAULT_LEN_TAG_KEY)).
+    pub fn with_len_tag(receiver: Receiver<Box<[T]>>, key: &str) -> Block {
+        Self::create(receiver, Some(key.to_string()))
+    }
+
+    fn create(receiver: Receiver<Box<[T]>>, len_tag: Option<String>) -> Block {
         Block::new(
             BlockMetaBuilder::new("AsyncChannelSource").build(),
             StreamIoBuilder::new().add_output::<T>("out").build(),
@@ -46,6 +62,7 @@ impl<T: Send + 'static> AsyncChannelSource<T> {
             AsyncChannelSource::<T> {
                 receiver,
                 current: None,
+                len_tag,
             },
         )
     }
@@ -83,6 +100,10 @@ impl<T: Send + 'static> Kernel for AsyncChannelSource<T> {
 
         if let Some((data, index)) = &mut self.current {
             let n = std::cmp::min(data.len() - *index, out.len());
+            if let (Some(key), 0) = (&self.len_tag, *index) {
+                sio.output(0)
+                    .add_tag(0, Tag::NamedUsize(key.clone(), data.len()));
+            }
             unsafe {
                 std::ptr::copy_nonoverlapping(data.as_ptr().add(*index), out.as_mut_ptr(), n);
             };
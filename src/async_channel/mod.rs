@@ -1,6 +1,17 @@
 //! ## Blocks related to channels
+mod async_channel_message_sink;
+mod async_channel_message_source;
 mod async_channel_sink;
 mod async_channel_source;
+mod sink_adapter;
+mod stream_source;
 
-pub use async_channel_sink::AsyncChannelSink;
+pub use async_channel_message_sink::AsyncChannelMessageSink;
+pub use async_channel_message_source::AsyncChannelMessageSource;
+pub use async_channel_sink::{AsyncChannelSink, AsyncChannelSinkBuilder};
 pub use async_channel_source::AsyncChannelSource;
+pub use sink_adapter::SinkAdapter;
+pub use stream_source::StreamSource;
+
+/// Conventional key of the tags holding the length of a burst, as in GNU Radio
+pub const DEFAULT_LEN_TAG_KEY: &str = "packet_len";
@@ -0,0 +1,125 @@
+use std::fmt::Debug;
+use std::pin::Pin;
+
+use futuresdr::futures::Sink;
+use futuresdr::futures::SinkExt;
+
+use futuresdr::log::debug;
+use futuresdr::runtime::Block;
+use futuresdr::runtime::BlockMeta;
+use futuresdr::runtime::BlockMetaBuilder;
+use futuresdr::runtime::Kernel;
+use futuresdr::runtime::MessageIo;
+use futuresdr::runtime::MessageIoBuilder;
+use futuresdr::runtime::Result;
+use futuresdr::runtime::StreamIo;
+use futuresdr::runtime::StreamIoBuilder;
+use futuresdr::runtime::WorkIo;
+
+/// Send samples out of a flowgraph into an asynchronous [`Sink`].
+///
+/// Samples are sent in chunks of whatever size is available, waiting for the sink to be
+/// ready, which applies backpressure to the flowgraph. The sink is closed when the block
+/// terminates. With the sending half of a `futures::channel::mpsc` channel, the
+/// receiving half lets the flowgraph output be consumed as a `Stream`.
+///
+/// The block finishes if the sink returns an error, e.g. when the receiving half is dropped.
+///
+/// # Inputs
+///
+/// `in`: Samples sent to the sink
+///
+/// # Usage
+/// ```
+/// use fsdr_blocks::async_channel::SinkAdapter;
+/// use futuresdr::blocks::VectorSource;
+/// use futuresdr::futures::channel::mpsc;
+/// use futuresdr::futures::StreamExt;
+/// use futuresdr::runtime::{Flowgraph, Runtime};
+///
+/// let mut fg = Flowgraph::new();
+/// let (tx, rx) = mpsc::channel::<Vec<u32>>(4);
+/// let src = fg.add_block(VectorSource::<u32>::new((0..100).collect()));
+/// let snk = fg.add_block(SinkAdapter::<u32, _>::new(tx));
+/// fg.connect_stream(src, "out", snk, "in").unwrap();
+///
+/// let reader = std::thread::spawn(move || {
+///     futuresdr::async_io::block_on(rx.map(|v| v.len()).fold(0, |a, n| async move { a + n }))
+/// });
+/// Runtime::new().run(fg).unwrap();
+/// assert_eq!(reader.join().unwrap(), 100);
+/// ```
+pub struct SinkAdapter<T, S>
+where
+    T: Send + Copy + 'static,
+    S: Sink<Vec<T>> + Send + 'static,
+    S::Error: Debug,
+{
+    sink: Pin<Box<S>>,
+    _p: std::marker::PhantomData<T>,
+}
+
+impl<T, S> SinkAdapter<T, S>
+where
+    T: Send + Copy + 'static,
+    S: Sink<Vec<T>> + Send + 'static,
+    S::Error: Debug,
+{
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(sink: S) -> Block {
+        Block::new(
+            BlockMetaBuilder::new("SinkAdapter").build(),
+            StreamIoBuilder::new().add_input::<T>("in").build(),
+            MessageIoBuilder::new().build(),
+            SinkAdapter::<T, S> {
+                sink: Box::pin(sink),
+                _p: std::marker::PhantomData,
+            },
+        )
+    }
+}
+
+#[doc(hidden)]
+#[async_trait]
+impl<T, S> Kernel for SinkAdapter<T, S>
+where
+    T: Send + Copy + 'static,
+    S: Sink<Vec<T>> + Send + 'static,
+    S::Error: Debug,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        sio: &mut StreamIo,
+        _mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let i = sio.input(0).slice::<T>();
+
+        if !i.is_empty() {
+            let chunk = i.to_vec();
+            sio.input(0).consume(i.len());
+            if let Err(e) = self.sink.send(chunk).await {
+                debug!("SinkAdapter: sink closed ({:?})", e);
+                io.finished = true;
+                return Ok(());
+            }
+        }
+
+        if sio.input(0).finished() {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+
+    async fn deinit(
+        &mut self,
+        _sio: &mut StreamIo,
+        _mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let _ = self.sink.close().await;
+        Ok(())
+    }
+}
@@ -0,0 +1,115 @@
+use std::pin::Pin;
+
+use futuresdr::futures::FutureExt;
+use futuresdr::futures::Stream;
+use futuresdr::futures::StreamExt;
+
+use futuresdr::runtime::Block;
+use futuresdr::runtime::BlockMeta;
+use futuresdr::runtime::BlockMetaBuilder;
+use futuresdr::runtime::Kernel;
+use futuresdr::runtime::MessageIo;
+use futuresdr::runtime::MessageIoBuilder;
+use futuresdr::runtime::Result;
+use futuresdr::runtime::StreamIo;
+use futuresdr::runtime::StreamIoBuilder;
+use futuresdr::runtime::WorkIo;
+
+/// Feed the items of an asynchronous [`Stream`] into a stream connection.
+///
+/// The block waits for at least one item, then takes all the items that are
+/// immediately available. It finishes when the stream ends.
+///
+/// # Outputs
+///
+/// `out`: Items of the stream
+///
+/// # Usage
+/// ```
+/// use fsdr_blocks::async_channel::StreamSource;
+/// use futuresdr::blocks::NullSink;
+/// use futuresdr::futures::stream;
+/// use futuresdr::runtime::{Flowgraph, Runtime};
+///
+/// let mut fg = Flowgraph::new();
+/// let src = fg.add_block(StreamSource::<u32, _>::new(stream::iter(0..100u32)));
+/// let snk = fg.add_block(NullSink::<u32>::new());
+/// fg.connect_stream(src, "out", snk, "in").unwrap();
+/// Runtime::new().run(fg).unwrap();
+/// ```
+pub struct StreamSource<T, S>
+where
+    T: Send + Copy + 'static,
+    S: Stream<Item = T> + Send + 'static,
+{
+    stream: Pin<Box<S>>,
+}
+
+impl<T, S> StreamSource<T, S>
+where
+    T: Send + Copy + 'static,
+    S: Stream<Item = T> + Send + 'static,
+{
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(stream: S) -> Block {
+        Block::new(
+            BlockMetaBuilder::new("StreamSource").build(),
+            StreamIoBuilder::new().add_output::<T>("out").build(),
+            MessageIoBuilder::new().build(),
+            StreamSource::<T, S> {
+                stream: Box::pin(stream),
+            },
+        )
+    }
+}
+
+#[doc(hidden)]
+#[async_trait]
+impl<T, S> Kernel for StreamSource<T, S>
+where
+    T: Send + Copy + 'static,
+    S: Stream<Item = T> + Send + 'static,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        sio: &mut StreamIo,
+        _mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let out = sio.output(0).slice::<T>();
+        if out.is_empty() {
+            return Ok(());
+        }
+
+        match self.stream.next().await {
+            Some(item) => out[0] = item,
+            None => {
+                io.finished = true;
+                return Ok(());
+            }
+        }
+
+        let mut n = 1;
+        while n < out.len() {
+            match self.stream.next().now_or_never() {
+                Some(Some(item)) => {
+                    out[n] = item;
+                    n += 1;
+                }
+                Some(None) => {
+                    io.finished = true;
+                    break;
+                }
+                None => break,
+            }
+        }
+        sio.output(0).produce(n);
+
+        if !io.finished {
+            io.call_again = true;
+        }
+
+        Ok(())
+    }
+}
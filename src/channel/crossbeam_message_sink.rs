@@ -0,0 +1,84 @@
+use async_trait::async_trait;
+use crossbeam_channel::{Sender, TrySendError};
+use futuresdr::log::warn;
+use futuresdr::macros::message_handler;
+use futuresdr::runtime::BlockMeta;
+use futuresdr::runtime::BlockMetaBuilder;
+use futuresdr::runtime::Kernel;
+use futuresdr::runtime::MessageIo;
+use futuresdr::runtime::MessageIoBuilder;
+use futuresdr::runtime::Pmt;
+use futuresdr::runtime::Result;
+use futuresdr::runtime::StreamIoBuilder;
+use futuresdr::runtime::WorkIo;
+use futuresdr::runtime::{Block, TypedBlock};
+
+/// Get messages out of a flowgraph into a crossbeam channel.
+///
+/// Messages that do not fit in a full bounded channel are dropped with a warning.
+/// The block finishes on [`Pmt::Finished`], or once the receiver is dropped.
+///
+/// # Message inputs
+///
+/// `in`: Messages forwarded to the channel
+///
+/// # Usage
+/// ```
+/// use crossbeam_channel;
+/// use fsdr_blocks::channel::CrossbeamMessageSink;
+/// use futuresdr::runtime::{Flowgraph, Pmt};
+///
+/// let mut fg = Flowgraph::new();
+/// let (tx, rx) = crossbeam_channel::unbounded::<Pmt>();
+///
+/// let telemetry = fg.add_block(CrossbeamMessageSink::new(tx));
+/// ```
+pub struct CrossbeamMessageSink {
+    sender: Sender<Pmt>,
+}
+
+impl CrossbeamMessageSink {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(sender: Sender<Pmt>) -> Block {
+        Block::from_typed(Self::new_typed(sender))
+    }
+
+    pub fn new_typed(sender: Sender<Pmt>) -> TypedBlock<Self> {
+        TypedBlock::new(
+            BlockMetaBuilder::new("CrossbeamMessageSink").build(),
+            StreamIoBuilder::new().build(),
+            MessageIoBuilder::new()
+                .add_input("in", Self::handler)
+                .build(),
+            CrossbeamMessageSink { sender },
+        )
+    }
+
+    #[message_handler]
+    async fn handler(
+        &mut self,
+        io: &mut WorkIo,
+        _mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        if let Pmt::Finished = p {
+            io.finished = true;
+            return Ok(Pmt::Ok);
+        }
+        match self.sender.try_send(p) {
+            Ok(_) => {}
+            Err(TrySendError::Full(p)) => {
+                warn!("CrossbeamMessageSink: channel full, dropping {:?}", p);
+            }
+            Err(TrySendError::Disconnected(_)) => {
+                io.finished = true;
+            }
+        }
+        Ok(Pmt::Ok)
+    }
+}
+
+#[doc(hidden)]
+#[async_trait]
+impl Kernel for CrossbeamMessageSink {}
@@ -0,0 +1,87 @@
+use async_trait::async_trait;
+use crossbeam_channel::{Receiver, RecvTimeoutError};
+use futuresdr::runtime::BlockMeta;
+use futuresdr::runtime::BlockMetaBuilder;
+use futuresdr::runtime::Kernel;
+use futuresdr::runtime::MessageIo;
+use futuresdr::runtime::MessageIoBuilder;
+use futuresdr::runtime::Pmt;
+use futuresdr::runtime::Result;
+use futuresdr::runtime::StreamIo;
+use futuresdr::runtime::StreamIoBuilder;
+use futuresdr::runtime::WorkIo;
+use futuresdr::runtime::{Block, TypedBlock};
+use std::time::Duration;
+
+/// Period at which the channel is polled, so that the block stays responsive to termination.
+const RECV_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Forward messages received on a crossbeam channel to a message port of the flowgraph.
+///
+/// The block runs on its own thread, waiting on the channel.
+/// It finishes once all senders are dropped.
+///
+/// # Message outputs
+///
+/// `out`: Messages pushed into the channel
+///
+/// # Usage
+/// ```
+/// use crossbeam_channel;
+/// use fsdr_blocks::channel::CrossbeamMessageSource;
+/// use futuresdr::runtime::{Flowgraph, Pmt};
+///
+/// let mut fg = Flowgraph::new();
+/// let (tx, rx) = crossbeam_channel::unbounded::<Pmt>();
+///
+/// let commands = fg.add_block(CrossbeamMessageSource::new(rx));
+/// tx.send(Pmt::F64(100e6)).unwrap();
+/// ```
+pub struct CrossbeamMessageSource {
+    receiver: Receiver<Pmt>,
+}
+
+impl CrossbeamMessageSource {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(receiver: Receiver<Pmt>) -> Block {
+        Block::from_typed(Self::new_typed(receiver))
+    }
+
+    pub fn new_typed(receiver: Receiver<Pmt>) -> TypedBlock<Self> {
+        TypedBlock::new(
+            BlockMetaBuilder::new("CrossbeamMessageSource")
+                .blocking()
+                .build(),
+            StreamIoBuilder::new().build(),
+            MessageIoBuilder::new().add_output("out").build(),
+            CrossbeamMessageSource { receiver },
+        )
+    }
+}
+
+#[doc(hidden)]
+#[async_trait]
+impl Kernel for CrossbeamMessageSource {
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _sio: &mut StreamIo,
+        mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        match self.receiver.recv_timeout(RECV_TIMEOUT) {
+            Ok(p) => {
+                mio.post(0, p).await;
+                io.call_again = true;
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                io.call_again = true;
+            }
+            Err(RecvTimeoutError::Disconnected) => {
+                io.finished = true;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,22 +1,35 @@
 use async_trait::async_trait;
-use crossbeam_channel::Sender;
+use crossbeam_channel::{Receiver, Sender};
+use futuresdr::macros::message_handler;
 use futuresdr::runtime::BlockMeta;
 use futuresdr::runtime::BlockMetaBuilder;
 use futuresdr::runtime::Kernel;
 use futuresdr::runtime::MessageIo;
 use futuresdr::runtime::MessageIoBuilder;
+use futuresdr::runtime::Pmt;
 use futuresdr::runtime::Result;
 use futuresdr::runtime::StreamIo;
 use futuresdr::runtime::StreamIoBuilder;
 use futuresdr::runtime::WorkIo;
 use futuresdr::runtime::{Block, TypedBlock};
 
+use super::overflow::OverflowSender;
+use crate::stream::OverflowPolicy;
+
 /// Push samples originating from a stream in a flowgraph into a crossbeam channel.
 ///
+/// When the channel is full, the configured [`OverflowPolicy`] applies,
+/// [`OverflowPolicy::DropNewest`] by default. See [`CrossbeamSinkBuilder`].
+///
 /// # Inputs
 ///
 /// `in`: Samples pushed into the channel
 ///
+/// # Messages
+///
+/// - input `dropped`: returns the total number of dropped samples as a [`Pmt::U64`]
+/// - output `dropped`: the total number of dropped samples, each time samples are dropped
+///
 /// # Usage
 /// ```
 /// use crossbeam_channel;
@@ -37,7 +50,8 @@ use futuresdr::runtime::{Block, TypedBlock};
 /// assert_eq!(orig, rx.recv().unwrap().to_vec());
 /// ```
 pub struct CrossbeamSink<T: Send + Copy + 'static> {
-    sender: Sender<Box<[T]>>,
+    sender: OverflowSender<Box<[T]>>,
+    dropped: u64,
 }
 
 impl<T: Send + Copy + 'static> CrossbeamSink<T> {
@@ -47,12 +61,20 @@ impl<T: Send + Copy + 'static> CrossbeamSink<T> {
     }
 
     pub fn new_typed(sender: Sender<Box<[T]>>) -> TypedBlock<Self> {
-        TypedBlock::new(
-            BlockMetaBuilder::new("CrossbeamSink").build(),
-            StreamIoBuilder::new().add_input::<T>("in").build(),
-            MessageIoBuilder::<Self>::new().build(),
-            CrossbeamSink::<T> { sender },
-        )
+        CrossbeamSinkBuilder::new(sender)
+            .build_typed()
+            .expect("the default overflow policy needs no receiver")
+    }
+
+    #[message_handler]
+    async fn dropped(
+        &mut self,
+        _io: &mut WorkIo,
+        _mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+        _p: Pmt,
+    ) -> Result<Pmt> {
+        Ok(Pmt::U64(self.dropped))
     }
 }
 
@@ -63,21 +85,26 @@ impl<T: Send + Copy + 'static> Kernel for CrossbeamSink<T> {
         &mut self,
         io: &mut WorkIo,
         sio: &mut StreamIo,
-        _mio: &mut MessageIo<Self>,
+        mio: &mut MessageIo<Self>,
         _meta: &mut BlockMeta,
     ) -> Result<()> {
         let i = sio.input(0).slice::<T>();
 
         if !i.is_empty() {
-            match self.sender.try_send(i.into()) {
-                Ok(_) => {
-                    //info!("sent data...");
+            let evicted = self.sender.make_room(|b| b.len());
+            match self.sender.send(i.into(), |b| b.len()) {
+                Ok(dropped) => {
+                    sio.input(0).consume(i.len());
+                    if evicted + dropped > 0 {
+                        self.dropped += (evicted + dropped) as u64;
+                        mio.post(0, Pmt::U64(self.dropped)).await;
+                    }
                 }
-                Err(_err) => {
-                    //info!("{}", err.to_string());
+                Err(_) => {
+                    io.call_again = true;
+                    return Ok(());
                 }
             }
-            sio.input(0).consume(i.len());
         }
 
         if sio.input(0).finished() {
@@ -87,3 +114,72 @@ impl<T: Send + Copy + 'static> Kernel for CrossbeamSink<T> {
         Ok(())
     }
 }
+
+/// Build a [`CrossbeamSink`] with a given [`OverflowPolicy`].
+///
+/// [`OverflowPolicy::DropOldest`] needs a receiver of the channel to evict old samples:
+/// building fails without one.
+/// With [`OverflowPolicy::Block`], the block runs on its own thread.
+///
+/// # Usage
+/// ```
+/// use crossbeam_channel;
+/// use fsdr_blocks::channel::CrossbeamSinkBuilder;
+/// use fsdr_blocks::stream::OverflowPolicy;
+///
+/// let (tx, rx) = crossbeam_channel::bounded::<Box<[f32]>>(4);
+/// let blk = CrossbeamSinkBuilder::new(tx)
+///     .overflow(OverflowPolicy::DropOldest)
+///     .receiver(rx.clone())
+///     .build()
+///     .unwrap();
+/// ```
+pub struct CrossbeamSinkBuilder<T: Send + Copy + 'static> {
+    sender: Sender<Box<[T]>>,
+    receiver: Option<Receiver<Box<[T]>>>,
+    policy: OverflowPolicy,
+}
+
+impl<T: Send + Copy + 'static> CrossbeamSinkBuilder<T> {
+    pub fn new(sender: Sender<Box<[T]>>) -> CrossbeamSinkBuilder<T> {
+        CrossbeamSinkBuilder {
+            sender,
+            receiver: None,
+            policy: OverflowPolicy::default(),
+        }
+    }
+
+    pub fn overflow(mut self, policy: OverflowPolicy) -> CrossbeamSinkBuilder<T> {
+        self.policy = policy;
+        self
+    }
+
+    /// Receiver of the channel, used to evict old samples with [`OverflowPolicy::DropOldest`]
+    pub fn receiver(mut self, receiver: Receiver<Box<[T]>>) -> CrossbeamSinkBuilder<T> {
+        self.receiver = Some(receiver);
+        self
+    }
+
+    pub fn build(self) -> Result<Block> {
+        Ok(Block::from_typed(self.build_typed()?))
+    }
+
+    pub fn build_typed(self) -> Result<TypedBlock<CrossbeamSink<T>>> {
+        let sender = OverflowSender::new(self.sender, self.receiver, self.policy)?;
+        let meta = BlockMetaBuilder::new("CrossbeamSink");
+        let meta = if self.policy == OverflowPolicy::Block {
+            meta.blocking()
+        } else {
+            meta
+        };
+        Ok(TypedBlock::new(
+            meta.build(),
+            StreamIoBuilder::new().add_input::<T>("in").build(),
+            MessageIoBuilder::<CrossbeamSink<T>>::new()
+                .add_input("dropped", CrossbeamSink::dropped)
+                .add_output("dropped")
+                .build(),
+            CrossbeamSink::<T> { sender, dropped: 0 },
+        ))
+    }
+}
@@ -0,0 +1,231 @@
+use async_trait::async_trait;
+use crossbeam_channel::{Receiver, Sender};
+use futuresdr::macros::message_handler;
+use futuresdr::runtime::BlockMeta;
+use futuresdr::runtime::BlockMetaBuilder;
+use futuresdr::runtime::ItemTag;
+use futuresdr::runtime::Kernel;
+use futuresdr::runtime::MessageIo;
+use futuresdr::runtime::MessageIoBuilder;
+use futuresdr::runtime::Pmt;
+use futuresdr::runtime::Result;
+use futuresdr::runtime::StreamIo;
+use futuresdr::runtime::StreamIoBuilder;
+use futuresdr::runtime::Tag;
+use futuresdr::runtime::WorkIo;
+use futuresdr::runtime::{Block, TypedBlock};
+
+use super::overflow::OverflowSender;
+use crate::stream::{OverflowPolicy, TaggedBatch, OVERFLOW_TAG_KEY};
+
+/// Push samples originating from a stream in a flowgraph into a crossbeam channel,
+/// along with their tags.
+///
+/// Like [`CrossbeamSink`](crate::channel::CrossbeamSink), but each batch carries the tags
+/// of its samples, with indices relative to the batch.
+///
+/// When the channel is full, the configured [`OverflowPolicy`] applies,
+/// [`OverflowPolicy::DropNewest`] by default. See [`CrossbeamTaggedSinkBuilder`].
+/// The first batch sent after samples were dropped starts with a
+/// `Tag::NamedUsize(OVERFLOW_TAG_KEY, n)` tag, `n` being the number of samples lost
+/// at this discontinuity.
+///
+/// # Inputs
+///
+/// `in`: Samples pushed into the channel
+///
+/// # Messages
+///
+/// - input `dropped`: returns the total number of dropped samples as a [`Pmt::U64`]
+/// - output `dropped`: the total number of dropped samples, each time samples are dropped
+///
+/// # Usage
+/// ```
+/// use crossbeam_channel;
+/// use fsdr_blocks::channel::CrossbeamTaggedSink;
+/// use fsdr_blocks::stream::TaggedBatch;
+/// use futuresdr::blocks::VectorSource;
+/// use futuresdr::runtime::{Flowgraph, Runtime};
+///
+/// let mut fg = Flowgraph::new();
+/// let (tx, rx) = crossbeam_channel::unbounded::<TaggedBatch<f32>>();
+///
+/// let orig: Vec<f32> = vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0];
+/// let vector_src = fg.add_block(VectorSource::<f32>::new(orig.clone()));
+/// let crossbeam_sink = fg.add_block(CrossbeamTaggedSink::<f32>::new(tx.clone()));
+///
+/// fg.connect_stream(vector_src, "out", crossbeam_sink, "in").unwrap();
+/// Runtime::new().run(fg).unwrap();
+///
+/// assert_eq!(orig, rx.recv().unwrap().samples.to_vec());
+/// ```
+pub struct CrossbeamTaggedSink<T: Send + Copy + 'static> {
+    sender: OverflowSender<TaggedBatch<T>>,
+    dropped: u64,
+    /// samples lost since the last batch sent
+    discontinuity: usize,
+}
+
+impl<T: Send + Copy + 'static> CrossbeamTaggedSink<T> {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(sender: Sender<TaggedBatch<T>>) -> Block {
+        Block::from_typed(Self::new_typed(sender))
+    }
+
+    pub fn new_typed(sender: Sender<TaggedBatch<T>>) -> TypedBlock<Self> {
+        CrossbeamTaggedSinkBuilder::new(sender)
+            .build_typed()
+            .expect("the default overflow policy needs no receiver")
+    }
+
+    #[message_handler]
+    async fn dropped(
+        &mut self,
+        _io: &mut WorkIo,
+        _mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+        _p: Pmt,
+    ) -> Result<Pmt> {
+        Ok(Pmt::U64(self.dropped))
+    }
+}
+
+#[doc(hidden)]
+#[async_trait]
+impl<T: Send + Copy + 'static> Kernel for CrossbeamTaggedSink<T> {
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        sio: &mut StreamIo,
+        mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let i = sio.input(0).slice::<T>();
+
+        if !i.is_empty() {
+            let evicted = self.sender.make_room(|b| b.len());
+            self.discontinuity += evicted;
+
+            let mut tags: Vec<ItemTag> = sio
+                .input(0)
+                .tags()
+                .iter()
+                .filter(|t| t.index < i.len())
+                .cloned()
+                .collect();
+            if self.discontinuity > 0 {
+                tags.insert(
+                    0,
+                    ItemTag {
+                        index: 0,
+                        tag: Tag::NamedUsize(OVERFLOW_TAG_KEY.to_string(), self.discontinuity),
+                    },
+                );
+            }
+
+            match self
+                .sender
+                .send(TaggedBatch::new(i.into(), tags), |b| b.len())
+            {
+                Ok(dropped) => {
+                    sio.input(0).consume(i.len());
+                    if dropped == 0 {
+                        self.discontinuity = 0;
+                    } else {
+                        self.discontinuity += dropped;
+                    }
+                    if evicted + dropped > 0 {
+                        self.dropped += (evicted + dropped) as u64;
+                        mio.post(0, Pmt::U64(self.dropped)).await;
+                    }
+                }
+                Err(_) => {
+                    if evicted > 0 {
+                        self.dropped += evicted as u64;
+                        mio.post(0, Pmt::U64(self.dropped)).await;
+                    }
+                    io.call_again = true;
+                    return Ok(());
+                }
+            }
+        }
+
+        if sio.input(0).finished() {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
+
+/// Build a [`CrossbeamTaggedSink`] with a given [`OverflowPolicy`].
+///
+/// [`OverflowPolicy::DropOldest`] needs a receiver of the channel to evict old samples:
+/// building fails without one.
+/// With [`OverflowPolicy::Block`], the block runs on its own thread.
+///
+/// # Usage
+/// ```
+/// use crossbeam_channel;
+/// use fsdr_blocks::channel::CrossbeamTaggedSinkBuilder;
+/// use fsdr_blocks::stream::{OverflowPolicy, TaggedBatch};
+///
+/// let (tx, rx) = crossbeam_channel::bounded::<TaggedBatch<f32>>(4);
+/// let blk = CrossbeamTaggedSinkBuilder::new(tx)
+///     .overflow(OverflowPolicy::Block)
+///     .build()
+///     .unwrap();
+/// ```
+pub struct CrossbeamTaggedSinkBuilder<T: Send + Copy + 'static> {
+    sender: Sender<TaggedBatch<T>>,
+    receiver: Option<Receiver<TaggedBatch<T>>>,
+    policy: OverflowPolicy,
+}
+
+impl<T: Send + Copy + 'static> CrossbeamTaggedSinkBuilder<T> {
+    pub fn new(sender: Sender<TaggedBatch<T>>) -> CrossbeamTaggedSinkBuilder<T> {
+        CrossbeamTaggedSinkBuilder {
+            sender,
+            receiver: None,
+            policy: OverflowPolicy::default(),
+        }
+    }
+
+    pub fn overflow(mut self, policy: OverflowPolicy) -> CrossbeamTaggedSinkBuilder<T> {
+        self.policy = policy;
+        self
+    }
+
+    /// Receiver of the channel, used to evict old samples with [`OverflowPolicy::DropOldest`]
+    pub fn receiver(mut self, receiver: Receiver<TaggedBatch<T>>) -> CrossbeamTaggedSinkBuilder<T> {
+        self.receiver = Some(receiver);
+        self
+    }
+
+    pub fn build(self) -> Result<Block> {
+        Ok(Block::from_typed(self.build_typed()?))
+    }
+
+    pub fn build_typed(self) -> Result<TypedBlock<CrossbeamTaggedSink<T>>> {
+        let sender = OverflowSender::new(self.sender, self.receiver, self.policy)?;
+        let meta = BlockMetaBuilder::new("CrossbeamTaggedSink");
+        let meta = if self.policy == OverflowPolicy::Block {
+            meta.blocking()
+        } else {
+            meta
+        };
+        Ok(TypedBlock::new(
+            meta.build(),
+            StreamIoBuilder::new().add_input::<T>("in").build(),
+            MessageIoBuilder::<CrossbeamTaggedSink<T>>::new()
+                .add_input("dropped", CrossbeamTaggedSink::dropped)
+                .add_output("dropped")
+                .build(),
+            CrossbeamTaggedSink::<T> {
+                sender,
+                dropped: 0,
+                discontinuity: 0,
+            },
+        ))
+    }
+}
@@ -0,0 +1,134 @@
+use async_trait::async_trait;
+use crossbeam_channel::{Receiver, TryRecvError};
+use futuresdr::runtime::BlockMeta;
+use futuresdr::runtime::BlockMetaBuilder;
+use futuresdr::runtime::Kernel;
+use futuresdr::runtime::MessageIo;
+use futuresdr::runtime::MessageIoBuilder;
+use futuresdr::runtime::Result;
+use futuresdr::runtime::StreamIo;
+use futuresdr::runtime::StreamIoBuilder;
+use futuresdr::runtime::WorkIo;
+use futuresdr::runtime::{Block, TypedBlock};
+
+use crate::stream::TaggedBatch;
+
+/// Push samples from a channel into a flowgraph stream connection, along with their tags.
+///
+/// Like [`CrossbeamSource`](crate::channel::CrossbeamSource), but tags of each batch are
+/// added to the output stream at the position of their sample.
+///
+/// # Outputs
+///
+/// `out`: Samples pushed into the channel
+///
+/// # Usage
+/// ```
+/// use crossbeam_channel;
+/// use fsdr_blocks::channel::CrossbeamTaggedSource;
+/// use fsdr_blocks::stream::TaggedBatch;
+/// use futuresdr::blocks::{VectorSink, VectorSinkBuilder};
+/// use futuresdr::runtime::{Flowgraph, ItemTag, Runtime, Tag};
+///
+/// let mut fg = Flowgraph::new();
+/// let (tx, rx) = crossbeam_channel::unbounded::<TaggedBatch<u32>>();
+///
+/// let crossbeam_source = fg.add_block(CrossbeamTaggedSource::<u32>::new(rx));
+/// let vector_sink = fg.add_block(VectorSinkBuilder::<u32>::new().build());
+///
+/// fg.connect_stream(crossbeam_source, "out", vector_sink, "in").unwrap();
+/// let tags = vec![ItemTag {
+///     index: 1,
+///     tag: Tag::NamedUsize("burst_start".to_string(), 1),
+/// }];
+/// tx.send(TaggedBatch::new(vec![0, 1, 2].into_boxed_slice(), tags))
+///     .unwrap();
+/// drop(tx);
+///
+/// fg = Runtime::new().run(fg).unwrap();
+///
+/// let snk = fg.kernel::<VectorSink<u32>>(vector_sink).unwrap();
+/// assert_eq!(snk.items(), &vec![0, 1, 2]);
+/// ```
+pub struct CrossbeamTaggedSource<T: Send + 'static> {
+    receiver: Receiver<TaggedBatch<T>>,
+    current: Option<(TaggedBatch<T>, usize)>,
+}
+
+impl<T: Send + 'static> CrossbeamTaggedSource<T> {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(receiver: Receiver<TaggedBatch<T>>) -> Block {
+        Block::from_typed(Self::new_typed(receiver))
+    }
+
+    pub fn new_typed(receiver: Receiver<TaggedBatch<T>>) -> TypedBlock<Self> {
+        TypedBlock::new(
+            BlockMetaBuilder::new("CrossbeamTaggedSource").build(),
+            StreamIoBuilder::new().add_output::<T>("out").build(),
+            MessageIoBuilder::new().build(),
+            CrossbeamTaggedSource::<T> {
+                receiver,
+                current: None,
+            },
+        )
+    }
+}
+
+#[doc(hidden)]
+#[async_trait]
+impl<T: Send + 'static> Kernel for CrossbeamTaggedSource<T> {
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        sio: &mut StreamIo,
+        _mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let out = sio.output(0).slice::<T>();
+        if out.is_empty() {
+            return Ok(());
+        }
+
+        if self.current.is_none() {
+            match self.receiver.try_recv() {
+                Ok(batch) => {
+                    self.current = Some((batch, 0));
+                }
+                Err(TryRecvError::Empty) => {}
+                Err(TryRecvError::Disconnected) => {
+                    io.finished = true;
+                    return Ok(());
+                }
+            }
+        }
+
+        if let Some((batch, index)) = &mut self.current {
+            let n = std::cmp::min(batch.len() - *index, out.len());
+            unsafe {
+                std::ptr::copy_nonoverlapping(
+                    batch.samples.as_ptr().add(*index),
+                    out.as_mut_ptr(),
+                    n,
+                );
+            };
+            for t in batch
+                .tags
+                .iter()
+                .filter(|t| t.index >= *index && t.index < *index + n)
+            {
+                sio.output(0).add_tag(t.index - *index, t.tag.clone());
+            }
+            sio.output(0).produce(n);
+            *index += n;
+            if *index == batch.len() {
+                self.current = None;
+            }
+        }
+
+        if self.current.is_none() {
+            io.call_again = true;
+        }
+
+        Ok(())
+    }
+}
@@ -1,6 +1,15 @@
 //! ## Blocks related to channels
+mod crossbeam_message_sink;
+mod crossbeam_message_source;
 mod crossbeam_sink;
 mod crossbeam_source;
+mod crossbeam_tagged_sink;
+mod crossbeam_tagged_source;
+mod overflow;
 
-pub use crossbeam_sink::CrossbeamSink;
+pub use crossbeam_message_sink::CrossbeamMessageSink;
+pub use crossbeam_message_source::CrossbeamMessageSource;
+pub use crossbeam_sink::{CrossbeamSink, CrossbeamSinkBuilder};
 pub use crossbeam_source::CrossbeamSource;
+pub use crossbeam_tagged_sink::{CrossbeamTaggedSink, CrossbeamTaggedSinkBuilder};
+pub use crossbeam_tagged_source::CrossbeamTaggedSource;
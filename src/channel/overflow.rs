@@ -0,0 +1,71 @@
+use crate::stream::OverflowPolicy;
+use crossbeam_channel::{Receiver, SendTimeoutError, Sender, TrySendError};
+use futuresdr::anyhow::bail;
+use futuresdr::runtime::Result;
+use std::time::Duration;
+
+/// How long a send waits with [`OverflowPolicy::Block`], before giving the block a chance
+/// to handle messages and termination.
+const SEND_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// [`OverflowPolicy`] along with the receiver needed to evict old messages
+enum Policy<M> {
+    Block,
+    DropNewest,
+    DropOldest(Receiver<M>),
+}
+
+/// Sender applying an [`OverflowPolicy`] when the channel is full.
+pub(crate) struct OverflowSender<M> {
+    sender: Sender<M>,
+    policy: Policy<M>,
+}
+
+impl<M> OverflowSender<M> {
+    /// Fails with [`OverflowPolicy::DropOldest`] if no receiver of the channel is given.
+    pub fn new(
+        sender: Sender<M>,
+        receiver: Option<Receiver<M>>,
+        policy: OverflowPolicy,
+    ) -> Result<OverflowSender<M>> {
+        let policy = match (policy, receiver) {
+            (OverflowPolicy::Block, _) => Policy::Block,
+            (OverflowPolicy::DropNewest, _) => Policy::DropNewest,
+            (OverflowPolicy::DropOldest, Some(receiver)) => Policy::DropOldest(receiver),
+            (OverflowPolicy::DropOldest, None) => {
+                bail!("OverflowPolicy::DropOldest requires a receiver of the channel")
+            }
+        };
+        Ok(OverflowSender { sender, policy })
+    }
+
+    /// With [`OverflowPolicy::DropOldest`], evict pending messages while the channel is full.
+    /// Returns the number of samples evicted.
+    pub fn make_room(&self, len: impl Fn(&M) -> usize) -> usize {
+        let mut evicted = 0;
+        if let Policy::DropOldest(receiver) = &self.policy {
+            while self.sender.is_full() {
+                match receiver.try_recv() {
+                    Ok(old) => evicted += len(&old),
+                    Err(_) => break,
+                }
+            }
+        }
+        evicted
+    }
+
+    /// Send `msg`, returning the number of samples dropped,
+    /// or the message back if it has to be retried later.
+    pub fn send(&self, msg: M, len: impl Fn(&M) -> usize) -> std::result::Result<usize, M> {
+        match self.policy {
+            Policy::Block => match self.sender.send_timeout(msg, SEND_TIMEOUT) {
+                Ok(()) | Err(SendTimeoutError::Disconnected(_)) => Ok(0),
+                Err(SendTimeoutError::Timeout(msg)) => Err(msg),
+            },
+            Policy::DropNewest | Policy::DropOldest(_) => match self.sender.try_send(msg) {
+                Ok(()) | Err(TrySendError::Disconnected(_)) => Ok(0),
+                Err(TrySendError::Full(msg)) => Ok(len(&msg)),
+            },
+        }
+    }
+}
@@ -0,0 +1,382 @@
+use async_trait::async_trait;
+use std::collections::VecDeque;
+use std::ops::RangeInclusive;
+
+use futuresdr::macros::message_handler;
+use futuresdr::runtime::BlockMeta;
+use futuresdr::runtime::BlockMetaBuilder;
+use futuresdr::runtime::Kernel;
+use futuresdr::runtime::MessageIo;
+use futuresdr::runtime::MessageIoBuilder;
+use futuresdr::runtime::Pmt;
+use futuresdr::runtime::Result;
+use futuresdr::runtime::StreamIo;
+use futuresdr::runtime::StreamIoBuilder;
+use futuresdr::runtime::WorkIo;
+use futuresdr::runtime::{Block, TypedBlock};
+
+use crate::cw::shared::CWAlphabet::{self, *};
+
+/// Marks longer than this many dots are dashes, and spaces longer than it end a character
+const DASH_THRESHOLD: f32 = 2.0;
+/// Spaces longer than this many dots end a word
+const WORD_THRESHOLD: f32 = 5.0;
+/// Minimum estimated change in words per minute posted on the `wpm` port
+const WPM_REPORT_STEP: f32 = 0.5;
+
+/// Decode keyed baseband into CW symbols, at an unknown and drifting speed.
+///
+/// The baseband is keyed down above `threshold`. The length of a dot is estimated from the
+/// durations of the last marks, which are split into dots and dashes with a 2-means
+/// clustering, starting from the previous estimate. The estimate is bounded by a range of
+/// speeds, so that it cannot run away on noise.
+///
+/// Marks longer than 2 dots are dashes, spaces longer than 2 dots end a character, and
+/// spaces longer than 5 dots end a word. Gaps are emitted as soon as they are reached,
+/// like [`msg_to_cw`](crate::cw::shared::msg_to_cw) does, i.e., each character ends with
+/// a [`LetterSpace`], and each word with an additional [`WordSpace`], so that the output
+/// suits [`CWToChar`](crate::cw::cw_to_char::CWToChar), including the last word when the
+/// transmission ends.
+///
+/// # Inputs
+///
+/// `in`: Keyed baseband f32 samples
+///
+/// # Outputs
+///
+/// `out`: CW symbols
+///
+/// # Messages
+///
+/// - input `wpm`: returns the estimated speed in words per minute with [`Pmt::Null`],
+///   or restarts the estimation from a speed given as a number.
+/// - output `wpm`: estimated speed in words per minute as [`Pmt::F32`], whenever it
+///   changed by at least 0.5 at the end of a character.
+///
+/// # Usage
+/// ```
+/// use fsdr_blocks::cw::adaptive_baseband_to_cw::AdaptiveBaseBandToCWBuilder;
+///
+/// let decoder = AdaptiveBaseBandToCWBuilder::new(8000.0)
+///     .wpm(25.0)
+///     .wpm_range(10.0, 40.0)
+///     .history(16)
+///     .build();
+/// ```
+pub struct AdaptiveBaseBandToCW {
+    sample_rate: f32,
+    threshold: f32,
+    /// bounds of the dot length in samples
+    dot_range: RangeInclusive<f32>,
+    history: usize,
+    /// durations in samples of the last marks
+    marks: VecDeque<usize>,
+    /// estimated dot length in samples
+    dot: f32,
+    /// estimated speed last posted on the `wpm` port, or the initial one
+    reported_wpm: f32,
+    key_down: bool,
+    /// length of the current mark or space in samples
+    run: usize,
+    /// set when a character has started, but its letter space is not emitted yet
+    in_letter: bool,
+    /// set when a word has started, but its word space is not emitted yet
+    in_word: bool,
+}
+
+impl AdaptiveBaseBandToCW {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(sample_rate: f32) -> Block {
+        AdaptiveBaseBandToCWBuilder::new(sample_rate).build()
+    }
+
+    pub fn new_typed(sample_rate: f32) -> TypedBlock<Self> {
+        AdaptiveBaseBandToCWBuilder::new(sample_rate).build_typed()
+    }
+
+    /// Estimated speed in words per minute
+    pub fn wpm(&self) -> f32 {
+        1.2 * self.sample_rate / self.dot
+    }
+
+    #[message_handler]
+    async fn wpm_handler(
+        &mut self,
+        _io: &mut WorkIo,
+        _mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        let wpm = match p {
+            Pmt::Null => return Ok(Pmt::F32(self.wpm())),
+            Pmt::F32(v) => v,
+            Pmt::F64(v) => v as f32,
+            Pmt::U32(v) => v as f32,
+            Pmt::U64(v) => v as f32,
+            Pmt::Usize(v) => v as f32,
+            _ => return Ok(Pmt::InvalidValue),
+        };
+        if wpm <= 0.0 {
+            return Ok(Pmt::InvalidValue);
+        }
+        self.marks.clear();
+        self.dot = self.clamp(1.2 * self.sample_rate / wpm);
+        Ok(Pmt::Ok)
+    }
+
+    /// Symbol of the current mark
+    fn mark_symbol(&self) -> CWAlphabet {
+        if (self.run as f32) < DASH_THRESHOLD * self.dot {
+            Dot
+        } else {
+            Dash
+        }
+    }
+
+    fn clamp(&self, dot: f32) -> f32 {
+        dot.clamp(*self.dot_range.start(), *self.dot_range.end())
+    }
+
+    /// Update the dot length estimate with the duration of a new mark
+    fn estimate(&mut self, mark: usize) {
+        self.marks.push_back(mark);
+        if self.marks.len() > self.history {
+            self.marks.pop_front();
+        }
+
+        // 2-means of the marks, starting from the dots and dashes of the current estimate
+        let (mut dot, mut dash) = (self.dot, 3.0 * self.dot);
+        let (mut dots, mut dashes) = ((0.0, 0), (0.0, 0));
+        for _ in 0..8 {
+            let boundary = (dot + dash) / 2.0;
+            dots = (0.0, 0);
+            dashes = (0.0, 0);
+            for m in self.marks.iter().map(|m| *m as f32) {
+                let cluster = if m < boundary { &mut dots } else { &mut dashes };
+                cluster.0 += m;
+                cluster.1 += 1;
+            }
+            if dots.1 > 0 {
+                dot = dots.0 / dots.1 as f32;
+            }
+            if dashes.1 > 0 {
+                dash = dashes.0 / dashes.1 as f32;
+            }
+        }
+
+        let estimate = if dots.1 > 0 && dashes.1 > 0 && (2.0..=4.5).contains(&(dash / dot)) {
+            // dashes weigh as much as dots, at a third of their length
+            (dots.0 + dashes.0 / 3.0) / (dots.1 + dashes.1) as f32
+        } else {
+            // a single kind of marks, which the current estimate tells apart
+            let n = self.marks.len() as f32;
+            let mean = self.marks.iter().sum::<usize>() as f32 / n;
+            if mean < DASH_THRESHOLD * self.dot {
+                mean
+            } else {
+                mean / 3.0
+            }
+        };
+        self.dot = self.clamp(estimate);
+    }
+
+    /// Decode the key state of a sample, emitting at most a mark and a gap. Returns the
+    /// estimated speed at the end of a character, when it changed enough to be posted.
+    #[inline(always)]
+    pub(crate) fn push(
+        &mut self,
+        key_down: bool,
+        emit: &mut impl FnMut(CWAlphabet),
+    ) -> Option<f32> {
+        if key_down != self.key_down {
+            if self.key_down {
+                // the mark ended
+                self.estimate(self.run);
+                emit(self.mark_symbol());
+                self.in_letter = true;
+            }
+            self.key_down = key_down;
+            self.run = 0;
+        }
+        self.run = self.run.saturating_add(1);
+
+        if !self.key_down {
+            let space = self.run as f32;
+            if self.in_letter && space >= DASH_THRESHOLD * self.dot {
+                emit(LetterSpace);
+                self.in_letter = false;
+                self.in_word = true;
+
+                let wpm = self.wpm();
+                if (wpm - self.reported_wpm).abs() >= WPM_REPORT_STEP {
+                    self.reported_wpm = wpm;
+                    return Some(wpm);
+                }
+            } else if self.in_word && space >= WORD_THRESHOLD * self.dot {
+                emit(WordSpace);
+                self.in_word = false;
+            }
+        }
+        None
+    }
+
+    /// End the last word of the transmission, emitting at most three symbols
+    pub(crate) fn flush(&mut self, emit: &mut impl FnMut(CWAlphabet)) {
+        if self.key_down && self.run > 0 {
+            emit(self.mark_symbol());
+            self.in_letter = true;
+        }
+        if self.in_letter {
+            emit(LetterSpace);
+            self.in_word = true;
+        }
+        if self.in_word {
+            emit(WordSpace);
+        }
+        self.key_down = false;
+        self.run = 0;
+        self.in_letter = false;
+        self.in_word = false;
+    }
+}
+
+#[doc(hidden)]
+#[async_trait]
+impl Kernel for AdaptiveBaseBandToCW {
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        sio: &mut StreamIo,
+        mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let i = sio.input(0).slice::<f32>();
+        let o = sio.output(0).slice::<CWAlphabet>();
+
+        let mut consumed = 0;
+        let mut produced = 0;
+        // at most a mark and a gap per sample
+        while consumed < i.len() && produced + 2 <= o.len() {
+            let key_down = i[consumed] > self.threshold;
+            consumed += 1;
+
+            let wpm = self.push(key_down, &mut |symbol| {
+                o[produced] = symbol;
+                produced += 1;
+            });
+            if let Some(wpm) = wpm {
+                mio.post(0, Pmt::F32(wpm)).await;
+            }
+        }
+
+        // end the last word of the transmission
+        if sio.input(0).finished() && consumed == i.len() && o.len() - produced >= 3 {
+            self.flush(&mut |symbol| {
+                o[produced] = symbol;
+                produced += 1;
+            });
+            io.finished = true;
+        }
+
+        sio.input(0).consume(consumed);
+        sio.output(0).produce(produced);
+
+        Ok(())
+    }
+}
+
+/// Builder for [`AdaptiveBaseBandToCW`] block
+pub struct AdaptiveBaseBandToCWBuilder {
+    sample_rate: f32,
+    wpm: f32,
+    wpm_range: (f32, f32),
+    history: usize,
+    threshold: f32,
+}
+
+impl AdaptiveBaseBandToCWBuilder {
+    /// Create builder for a decoder of `sample_rate` samples per second
+    ///
+    /// ## Defaults
+    /// - `wpm`: 20, initial estimate
+    /// - `wpm_range`: 5 to 60
+    /// - `history`: 16 marks
+    /// - `threshold`: 0.5
+    pub fn new(sample_rate: f32) -> AdaptiveBaseBandToCWBuilder {
+        AdaptiveBaseBandToCWBuilder {
+            sample_rate,
+            wpm: 20.0,
+            wpm_range: (5.0, 60.0),
+            history: 16,
+            threshold: 0.5,
+        }
+    }
+
+    /// Initial speed estimate in words per minute
+    pub fn wpm(mut self, wpm: f32) -> AdaptiveBaseBandToCWBuilder {
+        self.wpm = wpm;
+        self
+    }
+
+    /// Bounds of the speed estimate in words per minute
+    pub fn wpm_range(mut self, min: f32, max: f32) -> AdaptiveBaseBandToCWBuilder {
+        self.wpm_range = (min, max);
+        self
+    }
+
+    /// Number of marks the speed is estimated from; fewer follow speed changes faster
+    pub fn history(mut self, history: usize) -> AdaptiveBaseBandToCWBuilder {
+        self.history = history;
+        self
+    }
+
+    /// Level above which the baseband is keyed down
+    pub fn threshold(mut self, threshold: f32) -> AdaptiveBaseBandToCWBuilder {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Create the decoder, for use in other blocks
+    pub(crate) fn build_decoder(&self) -> AdaptiveBaseBandToCW {
+        let (min, max) = self.wpm_range;
+        assert!(
+            0.0 < min && min <= self.wpm && self.wpm <= max,
+            "wpm must be positive and within wpm_range"
+        );
+        assert!(self.history > 0, "history must not be empty");
+        let dot = |wpm: f32| 1.2 * self.sample_rate / wpm;
+
+        AdaptiveBaseBandToCW {
+            sample_rate: self.sample_rate,
+            threshold: self.threshold,
+            dot_range: dot(max)..=dot(min),
+            history: self.history,
+            marks: VecDeque::with_capacity(self.history + 1),
+            dot: dot(self.wpm),
+            reported_wpm: self.wpm,
+            key_down: false,
+            run: 0,
+            in_letter: false,
+            in_word: false,
+        }
+    }
+
+    pub fn build_typed(self) -> TypedBlock<AdaptiveBaseBandToCW> {
+        TypedBlock::new(
+            BlockMetaBuilder::new("AdaptiveBBToCW").build(),
+            StreamIoBuilder::new()
+                .add_input::<f32>("in")
+                .add_output::<CWAlphabet>("out")
+                .build(),
+            MessageIoBuilder::new()
+                .add_input("wpm", AdaptiveBaseBandToCW::wpm_handler)
+                .add_output("wpm")
+                .build(),
+            self.build_decoder(),
+        )
+    }
+
+    pub fn build(self) -> Block {
+        Block::from_typed(self.build_typed())
+    }
+}
@@ -1,11 +1,14 @@
 use async_trait::async_trait;
+use std::collections::HashMap;
 use std::ops::RangeInclusive;
 
+use futuresdr::log::debug;
 use futuresdr::runtime::BlockMeta;
 use futuresdr::runtime::BlockMetaBuilder;
 use futuresdr::runtime::Kernel;
 use futuresdr::runtime::MessageIo;
 use futuresdr::runtime::MessageIoBuilder;
+use futuresdr::runtime::Pmt;
 use futuresdr::runtime::Result;
 use futuresdr::runtime::StreamIo;
 use futuresdr::runtime::StreamIoBuilder;
@@ -14,6 +17,42 @@ use futuresdr::runtime::{Block, TypedBlock};
 
 use crate::cw::shared::CWAlphabet::{self, *};
 
+/// Decode keyed baseband into CW symbols, at a fixed speed.
+///
+/// Marks and spaces are classified by their duration in samples, within a tolerance of
+/// `100 - accuracy` percent of a dot.
+///
+/// # Inputs
+///
+/// `in`: Keyed baseband f32 samples
+///
+/// # Outputs
+///
+/// `out`: CW symbols
+///
+/// # Messages
+///
+/// `events`: With [`BaseBandToCWBuilder::debug`], each mark, and each space following
+/// one, as a [`Pmt::MapStrPmt`] with
+/// - `event`: `mark` or `space`
+/// - `duration`: length in samples
+/// - `symbol`: the symbol it was classified as, e.g., `Dot`, or [`Pmt::Null`] when the
+///   duration matches no symbol, or for the gap between the elements of a character
+/// - `confidence`: between 0 and 1, how close the duration is to the one of the symbol,
+///   or of the element gap
+///
+/// Events are also logged at debug level.
+///
+/// # Usage
+/// ```
+/// use fsdr_blocks::cw::baseband_to_cw::BaseBandToCWBuilder;
+///
+/// let decoder = BaseBandToCWBuilder::new()
+///     .samples_per_dot(480)
+///     .accuracy(90)
+///     .debug(true)
+///     .build();
+/// ```
 pub struct BaseBandToCW {
     samples_per_dot: usize,
     sample_count: usize,
@@ -25,6 +64,9 @@ pub struct BaseBandToCW {
     dash_range: RangeInclusive<usize>,
     letterspace_range: RangeInclusive<usize>,
     wordspace_range: RangeInclusive<usize>,
+    debug: bool,
+    /// whether a mark ended already, so that a space follows it
+    after_mark: bool,
 }
 
 impl BaseBandToCW {
@@ -40,40 +82,43 @@ impl BaseBandToCW {
         accuracy: usize, // 100 = 100% accuracy = How accurate the timeslots for symbols and between symbols have to be kept
         samples_per_dot: usize,
     ) -> TypedBlock<Self> {
-        let tolerance_per_dot =
-            (samples_per_dot as f32 - ((accuracy as f32 / 100.) * samples_per_dot as f32)) as usize;
-        let dot_range = samples_per_dot - tolerance_per_dot..=samples_per_dot + tolerance_per_dot;
-        let dash_range =
-            3 * samples_per_dot - tolerance_per_dot..=3 * samples_per_dot + tolerance_per_dot;
-        let letterspace_range =
-            3 * samples_per_dot - tolerance_per_dot..=3 * samples_per_dot + tolerance_per_dot;
-        let wordspace_range =
-            7 * samples_per_dot - tolerance_per_dot..=7 * samples_per_dot + tolerance_per_dot;
-
-        println!("samples per dot: {}", samples_per_dot);
-        println!("dot_range: {:?}", dot_range);
-        println!("dash_range: {:?}", dash_range);
-        println!("letterspace_range: {:?}", letterspace_range);
-        println!("wordspace_range: {:?}", wordspace_range);
+        BaseBandToCWBuilder::new()
+            .accuracy(accuracy)
+            .samples_per_dot(samples_per_dot)
+            .build_typed()
+    }
 
-        TypedBlock::new(
-            BlockMetaBuilder::new("BBToCW").build(),
-            StreamIoBuilder::new()
-                .add_input::<f32>("in")
-                .add_output::<CWAlphabet>("out")
-                .build(),
-            MessageIoBuilder::new().build(),
-            BaseBandToCW {
-                samples_per_dot,
-                sample_count: 0,
-                power_before: 0.,
-                tolerance_per_dot, // // Tolerance towards the sending end in sticking to the time slots
-                dot_range,         // How many samples are still interpreted as a dot
-                dash_range,
-                letterspace_range,
-                wordspace_range,
-            },
-        )
+    /// Log a mark or space of `duration` samples, classified as `symbol`, with the
+    /// confidence of a symbol of `nominal` dots, and describe it in debug mode
+    fn event(
+        &self,
+        event: &str,
+        duration: usize,
+        symbol: Option<CWAlphabet>,
+        nominal: usize,
+    ) -> Option<Pmt> {
+        let nominal = (nominal * self.samples_per_dot) as f32;
+        let confidence = if symbol.is_none() && event == "mark" {
+            0.0
+        } else {
+            (1.0 - (duration as f32 - nominal).abs() / nominal).max(0.0)
+        };
+        debug!(
+            "BaseBandToCW: {} of {} samples -> {:?} ({:.2})",
+            event, duration, symbol, confidence
+        );
+        if !self.debug {
+            return None;
+        }
+        Some(Pmt::MapStrPmt(HashMap::from([
+            ("event".to_string(), Pmt::String(event.to_string())),
+            ("duration".to_string(), Pmt::Usize(duration)),
+            (
+                "symbol".to_string(),
+                symbol.map_or(Pmt::Null, |s| Pmt::String(format!("{s:?}"))),
+            ),
+            ("confidence".to_string(), Pmt::F32(confidence)),
+        ])))
     }
 }
 
@@ -84,7 +129,7 @@ impl Kernel for BaseBandToCW {
         &mut self,
         io: &mut WorkIo,
         sio: &mut StreamIo,
-        _mio: &mut MessageIo<Self>,
+        mio: &mut MessageIo<Self>,
         _meta: &mut BlockMeta,
     ) -> Result<()> {
         let i = sio.input(0).slice::<f32>();
@@ -99,52 +144,48 @@ impl Kernel for BaseBandToCW {
         let threshold = 0.5; //(self.avg_power_min + self.avg_power_max) / 2.;
 
         let mut symbol = None;
+        let mut events = vec![];
         for sample in i.iter() {
             let power = (*sample).abs(); //.powi(2); // Not required
 
             if (power > threshold) && (self.power_before <= threshold) {
                 // Signal is starting
-                match self.sample_count {
+                let nominal = match self.sample_count {
                     x if self.wordspace_range.contains(&x) => {
                         symbol = Some(WordSpace);
+                        7
                     } // Wordspace 7 dots (incl tolerance)
                     x if self.letterspace_range.contains(&x) => {
                         symbol = Some(LetterSpace);
+                        3
                     } // Letterspace (Longer than 3 dots (incl tolerance), but shorter than 7 dots (incl tolerance))
-                    x if self.dot_range.contains(&x) => {} // SymbolSpace (Is a valid symbol)
-                    _ => {
-                        //info!("Signal pause not a symbol: {} samples", self.sample_count);
-                    }
-                }
+                    _ => 1, // SymbolSpace, or not a symbol
+                };
 
-                println!(
-                    "Signal was paused for: {} -> {:?}",
-                    self.sample_count,
-                    symbol.or(None)
-                );
+                // the samples before the first mark are no space
+                if self.after_mark {
+                    events.extend(self.event("space", self.sample_count, symbol, nominal));
+                }
 
                 self.sample_count = 0;
                 end_of_transmission = false;
             }
             if (power <= threshold) && (self.power_before > threshold) {
                 // Signal is stopping
-                match self.sample_count {
+                let nominal = match self.sample_count {
                     x if self.dot_range.contains(&x) => {
                         symbol = Some(Dot);
+                        1
                     }
                     x if self.dash_range.contains(&x) => {
                         symbol = Some(Dash);
+                        3
                     }
-                    _ => {
-                        //info!("Signal length not a symbol: {} samples", self.sample_count);
-                    }
-                }
+                    _ => 1, // Signal length not a symbol
+                };
 
-                println!(
-                    "Signal was present for: {} -> {:?}",
-                    self.sample_count,
-                    symbol.or(None)
-                );
+                events.extend(self.event("mark", self.sample_count, symbol, nominal));
+                self.after_mark = true;
 
                 self.sample_count = 0;
             }
@@ -160,7 +201,7 @@ impl Kernel for BaseBandToCW {
                 && !end_of_transmission
             {
                 // End of transmission
-                //println!("Transmission ended!");
+                debug!("BaseBandToCW: transmission ended");
                 end_of_transmission = true;
                 o[produced] = LetterSpace;
                 o[produced + 1] = WordSpace;
@@ -180,6 +221,10 @@ impl Kernel for BaseBandToCW {
         sio.input(0).consume(consumed);
         sio.output(0).produce(produced);
 
+        for event in events {
+            mio.post(0, event).await;
+        }
+
         if sio.input(0).finished() && consumed == i.len() {
             io.finished = true;
         }
@@ -188,9 +233,11 @@ impl Kernel for BaseBandToCW {
     }
 }
 
+/// Builder for [`BaseBandToCW`] block
 pub struct BaseBandToCWBuilder {
     samles_per_dot: usize,
     accuracy: usize,
+    debug: bool,
 }
 
 impl Default for BaseBandToCWBuilder {
@@ -198,11 +245,18 @@ impl Default for BaseBandToCWBuilder {
         BaseBandToCWBuilder {
             samles_per_dot: 60,
             accuracy: 90,
+            debug: false,
         }
     }
 }
 
 impl BaseBandToCWBuilder {
+    /// Create builder for a decoder
+    ///
+    /// ## Defaults
+    /// - `samples_per_dot`: 60
+    /// - `accuracy`: 90
+    /// - `debug`: false
     pub fn new() -> BaseBandToCWBuilder {
         BaseBandToCWBuilder::default()
     }
@@ -217,7 +271,53 @@ impl BaseBandToCWBuilder {
         self
     }
 
+    /// Post each mark and space on the `events` message output
+    pub fn debug(mut self, debug: bool) -> BaseBandToCWBuilder {
+        self.debug = debug;
+        self
+    }
+
+    pub fn build_typed(self) -> TypedBlock<BaseBandToCW> {
+        let samples_per_dot = self.samles_per_dot;
+        let tolerance_per_dot = (samples_per_dot as f32
+            - ((self.accuracy as f32 / 100.) * samples_per_dot as f32))
+            as usize;
+        let dot_range = samples_per_dot - tolerance_per_dot..=samples_per_dot + tolerance_per_dot;
+        let dash_range =
+            3 * samples_per_dot - tolerance_per_dot..=3 * samples_per_dot + tolerance_per_dot;
+        let letterspace_range =
+            3 * samples_per_dot - tolerance_per_dot..=3 * samples_per_dot + tolerance_per_dot;
+        let wordspace_range =
+            7 * samples_per_dot - tolerance_per_dot..=7 * samples_per_dot + tolerance_per_dot;
+
+        debug!(
+            "BaseBandToCW: {} samples per dot, dot {:?}, dash {:?}, letter space {:?}, word space {:?}",
+            samples_per_dot, dot_range, dash_range, letterspace_range, wordspace_range
+        );
+
+        TypedBlock::new(
+            BlockMetaBuilder::new("BBToCW").build(),
+            StreamIoBuilder::new()
+                .add_input::<f32>("in")
+                .add_output::<CWAlphabet>("out")
+                .build(),
+            MessageIoBuilder::new().add_output("events").build(),
+            BaseBandToCW {
+                samples_per_dot,
+                sample_count: 0,
+                power_before: 0.,
+                tolerance_per_dot, // // Tolerance towards the sending end in sticking to the time slots
+                dot_range,         // How many samples are still interpreted as a dot
+                dash_range,
+                letterspace_range,
+                wordspace_range,
+                debug: self.debug,
+                after_mark: false,
+            },
+        )
+    }
+
     pub fn build(self) -> Block {
-        BaseBandToCW::new(self.accuracy, self.samles_per_dot)
+        Block::from_typed(self.build_typed())
     }
 }
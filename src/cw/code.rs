@@ -0,0 +1,368 @@
+use bimap::BiMap;
+
+use crate::cw::shared::get_alphabet;
+use crate::cw::shared::CWAlphabet::{self, *};
+
+/// Punctuation of ITU-R M.1677 missing from [`get_alphabet`]
+const ITU_PUNCTUATION: &[(char, &str)] = &[
+    ('(', "-.--."),
+    (')', "-.--.-"),
+    ('=', "-...-"),
+    ('+', ".-.-."),
+    ('@', ".--.-."),
+];
+
+/// Common prosigns, sent as a single character without letter spaces
+const PROSIGNS: &[(&str, &str)] = &[
+    ("<AR>", ".-.-."),
+    ("<AS>", ".-..."),
+    ("<BK>", "-...-.-"),
+    ("<BT>", "-...-"),
+    ("<CT>", "-.-.-"),
+    ("<HH>", "........"),
+    ("<KN>", "-.--."),
+    ("<SK>", "...-.-"),
+    ("<SN>", "...-."),
+    ("<SOS>", "...---..."),
+];
+
+/// Accented Latin letters
+const ACCENTED_LETTERS: &[(char, &str)] = &[
+    ('Ä', ".-.-"),
+    ('Á', ".--.-"),
+    ('Ç', "-.-.."),
+    ('É', "..-.."),
+    ('È', ".-..-"),
+    ('Ñ', "--.--"),
+    ('Ö', "---."),
+    ('Ü', "..--"),
+];
+
+/// Russian letters
+const CYRILLIC: &[(char, &str)] = &[
+    ('А', ".-"),
+    ('Б', "-..."),
+    ('В', ".--"),
+    ('Г', "--."),
+    ('Д', "-.."),
+    ('Е', "."),
+    ('Ж', "...-"),
+    ('З', "--.."),
+    ('И', ".."),
+    ('Й', ".---"),
+    ('К', "-.-"),
+    ('Л', ".-.."),
+    ('М', "--"),
+    ('Н', "-."),
+    ('О', "---"),
+    ('П', ".--."),
+    ('Р', ".-."),
+    ('С', "..."),
+    ('Т', "-"),
+    ('У', "..-"),
+    ('Ф', "..-."),
+    ('Х', "...."),
+    ('Ц', "-.-."),
+    ('Ч', "---."),
+    ('Ш', "----"),
+    ('Щ', "--.-"),
+    ('Ъ', "--.--"),
+    ('Ы', "-.--"),
+    ('Ь', "-..-"),
+    ('Э', "..-.."),
+    ('Ю', "..--"),
+    ('Я', ".-.-"),
+];
+
+/// Greek letters
+const GREEK: &[(char, &str)] = &[
+    ('Α', ".-"),
+    ('Β', "-..."),
+    ('Γ', "--."),
+    ('Δ', "-.."),
+    ('Ε', "."),
+    ('Ζ', "--.."),
+    ('Η', "...."),
+    ('Θ', "-.-."),
+    ('Ι', ".."),
+    ('Κ', "-.-"),
+    ('Λ', ".-.."),
+    ('Μ', "--"),
+    ('Ν', "-."),
+    ('Ξ', "-..-"),
+    ('Ο', "---"),
+    ('Π', ".--."),
+    ('Ρ', ".-."),
+    ('Σ', "..."),
+    ('Τ', "-"),
+    ('Υ', "-.--"),
+    ('Φ', "..-."),
+    ('Χ', "----"),
+    ('Ψ', "--.-"),
+    ('Ω', ".--"),
+];
+
+/// Japanese Wabun code, in katakana
+const WABUN: &[(char, &str)] = &[
+    ('イ', ".-"),
+    ('ロ', ".-.-"),
+    ('ハ', "-..."),
+    ('ニ', "-.-."),
+    ('ホ', "-.."),
+    ('ヘ', "."),
+    ('ト', "..-.."),
+    ('チ', "..-."),
+    ('リ', "--."),
+    ('ヌ', "...."),
+    ('ル', "-.--."),
+    ('ヲ', ".---"),
+    ('ワ', "-.-"),
+    ('カ', ".-.."),
+    ('ヨ', "--"),
+    ('タ', "-."),
+    ('レ', "---"),
+    ('ソ', "---."),
+    ('ツ', ".--."),
+    ('ネ', "--.-"),
+    ('ナ', ".-."),
+    ('ラ', "..."),
+    ('ム', "-"),
+    ('ウ', "..-"),
+    ('ヰ', ".-..-"),
+    ('ノ', "..--"),
+    ('オ', ".-..."),
+    ('ク', "...-"),
+    ('ヤ', ".--"),
+    ('マ', "-..-"),
+    ('ケ', "-.--"),
+    ('フ', "--.."),
+    ('コ', "----"),
+    ('エ', "-.---"),
+    ('テ', ".-.--"),
+    ('ア', "--.--"),
+    ('サ', "-.-.-"),
+    ('キ', "-.-.."),
+    ('ユ', "-..--"),
+    ('メ', "-...-"),
+    ('ミ', "..-.-"),
+    ('シ', "--.-."),
+    ('ヱ', ".--.."),
+    ('ヒ', "--..-"),
+    ('モ', "-..-."),
+    ('セ', ".---."),
+    ('ス', "---.-"),
+    ('ン', ".-.-."),
+    ('゛', ".."),
+    ('゜', "..--."),
+    ('ー', ".--.-"),
+    ('、', ".-.-.-"),
+    ('」', ".-.-.."),
+    ('（', "-.--.-"),
+    ('）', ".-..-."),
+];
+
+/// Convert dots and dashes written as `.` and `-` to CW symbols
+fn symbols(code: &str) -> Vec<CWAlphabet> {
+    code.chars()
+        .map(|c| if c == '.' { Dot } else { Dash })
+        .collect()
+}
+
+/// A table of the characters and prosigns of a CW code.
+///
+/// Characters are mapped one to one to their dots and dashes, like the table of
+/// [`get_alphabet`], which converts into a code without prosigns. Prosigns are tokens of
+/// several characters between angle brackets, e.g., `<AR>`, sent as a single character.
+/// When a prosign and a character share their dots and dashes, the prosign is decoded.
+///
+/// Characters are looked up as is first, and then in upper case, so that tables in upper
+/// case encode lower case text. Whitespace is sent as a word space.
+///
+/// # Usage
+/// ```
+/// use fsdr_blocks::cw::code::CWCode;
+/// use fsdr_blocks::cw::shared::CWAlphabet::*;
+///
+/// let code = CWCode::itu().with_prosigns();
+/// assert_eq!(
+///     code.encode("e <ar>"),
+///     vec![Dot, LetterSpace, WordSpace, Dot, Dash, Dot, Dash, Dot, LetterSpace]
+/// );
+/// assert_eq!(code.decode(&[Dot, Dash, Dot, Dash, Dot]), Some("<AR>".to_string()));
+///
+/// let code = CWCode::cyrillic();
+/// assert_eq!(code.decode(&[Dot, Dash, Dot, Dash]), Some("Я".to_string()));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CWCode {
+    chars: BiMap<char, Vec<CWAlphabet>>,
+    prosigns: BiMap<String, Vec<CWAlphabet>>,
+}
+
+impl CWCode {
+    /// Create an empty code
+    pub fn new() -> CWCode {
+        CWCode::default()
+    }
+
+    /// Latin letters, digits, and punctuation of ITU-R M.1677, as well as `;`
+    pub fn itu() -> CWCode {
+        let mut code = CWCode::from(get_alphabet());
+        code.extend(ITU_PUNCTUATION);
+        code
+    }
+
+    /// Latin letters, including accented ones, digits, and punctuation
+    pub fn international() -> CWCode {
+        let mut code = CWCode::itu();
+        code.extend(ACCENTED_LETTERS);
+        code
+    }
+
+    /// Russian letters, digits, and punctuation
+    pub fn cyrillic() -> CWCode {
+        let mut code = CWCode::itu();
+        code.chars.retain(|c, _| !c.is_ascii_alphabetic());
+        code.extend(CYRILLIC);
+        code
+    }
+
+    /// Greek letters, digits, and punctuation
+    pub fn greek() -> CWCode {
+        let mut code = CWCode::itu();
+        code.chars.retain(|c, _| !c.is_ascii_alphabetic());
+        code.extend(GREEK);
+        code
+    }
+
+    /// Japanese Wabun code in katakana, and digits.
+    ///
+    /// Voiced kana are sent as the unvoiced kana followed by `゛` or `゜`.
+    pub fn wabun() -> CWCode {
+        let mut code = CWCode::new();
+        code.chars = get_alphabet();
+        code.chars.retain(|c, _| c.is_ascii_digit() || *c == ' ');
+        code.extend(WABUN);
+        code
+    }
+
+    /// Add the common prosigns `<AR>`, `<AS>`, `<BK>`, `<BT>`, `<CT>`, `<HH>`, `<KN>`,
+    /// `<SK>`, `<SN>` and `<SOS>`
+    pub fn with_prosigns(mut self) -> CWCode {
+        for (prosign, code) in PROSIGNS {
+            self.insert_prosign(prosign, symbols(code));
+        }
+        self
+    }
+
+    /// Add a character, replacing any character with the same symbols
+    pub fn insert(&mut self, c: char, symbols: Vec<CWAlphabet>) {
+        self.chars.insert(c, symbols);
+    }
+
+    /// Add a prosign, written between angle brackets, e.g., `<AR>`, replacing any prosign
+    /// with the same symbols
+    pub fn insert_prosign(&mut self, prosign: &str, symbols: Vec<CWAlphabet>) {
+        assert!(
+            prosign.len() > 2 && prosign.starts_with('<') && prosign.ends_with('>'),
+            "prosigns are written between angle brackets"
+        );
+        self.prosigns.insert(prosign.to_string(), symbols);
+    }
+
+    /// Characters of the code, and their symbols
+    pub fn chars(&self) -> &BiMap<char, Vec<CWAlphabet>> {
+        &self.chars
+    }
+
+    /// Prosigns of the code, and their symbols
+    pub fn prosigns(&self) -> &BiMap<String, Vec<CWAlphabet>> {
+        &self.prosigns
+    }
+
+    /// Character or prosign of the symbols of a single character
+    pub fn decode(&self, symbols: &[CWAlphabet]) -> Option<String> {
+        self.prosigns
+            .get_by_right(symbols)
+            .cloned()
+            .or_else(|| self.chars.get_by_right(symbols).map(|c| c.to_string()))
+    }
+
+    /// Convert text to CW symbols, like [`msg_to_cw`](crate::cw::shared::msg_to_cw).
+    ///
+    /// Each character or prosign ends with a [`LetterSpace`], whitespace is a
+    /// [`WordSpace`], and characters missing from the code are [`Unknown`].
+    pub fn encode(&self, text: &str) -> Vec<CWAlphabet> {
+        let mut cw = vec![];
+        let mut rest = text;
+        while let Some(c) = rest.chars().next() {
+            if c.is_whitespace() {
+                cw.push(WordSpace);
+                rest = &rest[c.len_utf8()..];
+            } else if let Some((prosign, symbols)) = self.prosign(rest) {
+                cw.extend_from_slice(symbols);
+                cw.push(LetterSpace);
+                rest = &rest[prosign.len()..];
+            } else {
+                match self.char(c) {
+                    Some(symbols) => {
+                        cw.extend_from_slice(symbols);
+                        cw.push(LetterSpace);
+                    }
+                    None => cw.push(Unknown),
+                }
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+        cw
+    }
+
+    /// Length in bytes of the start of `text` that can be encoded, without splitting a
+    /// prosign whose end is not received yet
+    pub(crate) fn complete(&self, text: &str) -> usize {
+        match text.rfind('<') {
+            Some(start)
+                if !text[start..].contains('>')
+                    && self.prosigns.left_values().any(|p| {
+                        p.get(..text.len() - start)
+                            .is_some_and(|p| p.eq_ignore_ascii_case(&text[start..]))
+                    }) =>
+            {
+                start
+            }
+            _ => text.len(),
+        }
+    }
+
+    fn extend(&mut self, table: &[(char, &str)]) {
+        for (c, code) in table {
+            self.chars.insert(*c, symbols(code));
+        }
+    }
+
+    fn prosign(&self, text: &str) -> Option<(&String, &Vec<CWAlphabet>)> {
+        self.prosigns.iter().find(|(prosign, _)| {
+            text.get(..prosign.len())
+                .is_some_and(|t| t.eq_ignore_ascii_case(prosign))
+        })
+    }
+
+    fn char(&self, c: char) -> Option<&Vec<CWAlphabet>> {
+        self.chars.get_by_left(&c).or_else(|| {
+            let mut upper = c.to_uppercase();
+            match (upper.next(), upper.next()) {
+                (Some(u), None) => self.chars.get_by_left(&u),
+                _ => None,
+            }
+        })
+    }
+}
+
+impl From<BiMap<char, Vec<CWAlphabet>>> for CWCode {
+    fn from(chars: BiMap<char, Vec<CWAlphabet>>) -> Self {
+        CWCode {
+            chars,
+            prosigns: BiMap::new(),
+        }
+    }
+}
@@ -0,0 +1,349 @@
+use async_trait::async_trait;
+use std::collections::VecDeque;
+use std::f32::consts::PI;
+
+use futuresdr::macros::message_handler;
+use futuresdr::runtime::BlockMeta;
+use futuresdr::runtime::BlockMetaBuilder;
+use futuresdr::runtime::Kernel;
+use futuresdr::runtime::MessageIo;
+use futuresdr::runtime::MessageIoBuilder;
+use futuresdr::runtime::Pmt;
+use futuresdr::runtime::Result;
+use futuresdr::runtime::StreamIo;
+use futuresdr::runtime::StreamIoBuilder;
+use futuresdr::runtime::WorkIo;
+use futuresdr::runtime::{Block, TypedBlock};
+
+use crate::cw::code::CWCode;
+use crate::cw::shared::CWAlphabet::*;
+
+/// Encode text into keyed CW baseband.
+///
+/// Text is sent at `wpm` words per minute, with a dot lasting `1.2 / wpm` seconds
+/// (PARIS standard). With Farnsworth spacing, characters are still sent at `wpm`,
+/// but the gaps between characters and words are stretched to a lower overall speed.
+/// Each key down and key up is shaped with a raised cosine of `rise_time` seconds
+/// to avoid key clicks.
+///
+/// Characters and prosigns, e.g., `<AR>`, are looked up case-insensitively in the
+/// [`CWCode`], and those it does not contain are skipped. Text is sent as is, so that
+/// consecutive messages are joined unless they end with a space.
+/// The baseband envelope, between 0 and 1, is only produced while there is text to send.
+///
+/// # Inputs
+///
+/// `in`: Characters to send, with [`CWEncoderBuilder::stream_input`]
+///
+/// # Outputs
+///
+/// `out`: Keyed baseband f32 samples
+///
+/// # Messages
+///
+/// `in`: Text to send as [`Pmt::String`]. The block finishes on [`Pmt::Finished`],
+/// once the text received before is sent.
+///
+/// # Usage
+/// ```
+/// use fsdr_blocks::cw::code::CWCode;
+/// use fsdr_blocks::cw::cw_encoder::CWEncoderBuilder;
+///
+/// let encoder = CWEncoderBuilder::new(48000.0)
+///     .wpm(25.0)
+///     .farnsworth(15.0)
+///     .rise_time(0.005)
+///     .code(CWCode::itu().with_prosigns())
+///     .build();
+/// ```
+pub struct CWEncoder {
+    code: CWCode,
+    /// text not sent yet, as it may start a prosign
+    text: String,
+    timing: CWTiming,
+    /// key state and number of samples, of the elements still to send
+    segments: VecDeque<(bool, usize)>,
+    /// position on the raised cosine ramp, from 0 (key up) to `timing.ramp` (key down)
+    ramp_position: usize,
+    stream_input: bool,
+    input_finished: bool,
+}
+
+/// Durations of the CW elements in samples
+#[derive(Debug, Clone, Copy)]
+struct CWTiming {
+    dot: usize,
+    dash: usize,
+    /// gap between the elements of a character
+    element_gap: usize,
+    /// gap between characters, following the last element gap
+    letter_gap: usize,
+    /// gap between words, following the last letter gap
+    word_gap: usize,
+    /// length of the raised cosine shaping key down and key up
+    ramp: usize,
+}
+
+impl CWTiming {
+    fn new(sample_rate: f32, wpm: f32, farnsworth: Option<f32>, rise_time: f32) -> CWTiming {
+        let dot = 1.2 / wpm;
+        // ARRL Farnsworth timing: the 19 spacing units of PARIS are stretched to fit the
+        // overall speed, while its 31 character units are sent at `wpm`
+        let spacing = match farnsworth {
+            Some(overall) => (60.0 * wpm - 37.2 * overall) / (overall * wpm) / 19.0,
+            None => dot,
+        };
+        let samples = |seconds: f32| (seconds * sample_rate).round() as usize;
+        let element_gap = samples(dot);
+        let letter_gap = samples(3.0 * spacing);
+        let word_gap = samples(7.0 * spacing);
+        CWTiming {
+            dot: samples(dot),
+            dash: samples(3.0 * dot),
+            element_gap,
+            letter_gap: letter_gap.saturating_sub(element_gap),
+            word_gap: word_gap.saturating_sub(letter_gap),
+            ramp: samples(rise_time),
+        }
+    }
+}
+
+impl CWEncoder {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(sample_rate: f32, wpm: f32) -> Block {
+        CWEncoderBuilder::new(sample_rate).wpm(wpm).build()
+    }
+
+    pub fn new_typed(sample_rate: f32, wpm: f32) -> TypedBlock<Self> {
+        CWEncoderBuilder::new(sample_rate).wpm(wpm).build_typed()
+    }
+
+    #[message_handler]
+    async fn text(
+        &mut self,
+        _io: &mut WorkIo,
+        _mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        match p {
+            Pmt::String(text) => {
+                self.enqueue(&text);
+                Ok(Pmt::Ok)
+            }
+            Pmt::Finished => {
+                self.flush();
+                self.input_finished = true;
+                Ok(Pmt::Ok)
+            }
+            _ => Ok(Pmt::InvalidValue),
+        }
+    }
+
+    /// Queue the key down and key up segments of text, up to a prosign not complete yet
+    fn enqueue(&mut self, text: &str) {
+        self.text.push_str(text);
+        let n = self.code.complete(&self.text);
+        let text: String = self.text.drain(..n).collect();
+        self.send(&text);
+    }
+
+    /// Queue the rest of the text
+    fn flush(&mut self) {
+        let text = std::mem::take(&mut self.text);
+        self.send(&text);
+    }
+
+    fn send(&mut self, text: &str) {
+        let timing = self.timing;
+        for symbol in self.code.encode(text) {
+            match symbol {
+                Dot => {
+                    self.push(true, timing.dot);
+                    self.push(false, timing.element_gap);
+                }
+                Dash => {
+                    self.push(true, timing.dash);
+                    self.push(false, timing.element_gap);
+                }
+                LetterSpace => self.push(false, timing.letter_gap),
+                WordSpace => self.push(false, timing.word_gap),
+                Unknown => {}
+            }
+        }
+    }
+
+    fn push(&mut self, key_down: bool, len: usize) {
+        if len > 0 {
+            self.segments.push_back((key_down, len));
+        }
+    }
+
+    /// Envelope of the next sample, following the key state
+    #[inline(always)]
+    fn shape(&mut self, key_down: bool) -> f32 {
+        let ramp = self.timing.ramp;
+        if ramp == 0 {
+            return if key_down { 1.0 } else { 0.0 };
+        }
+        // the ramp up ends, and the ramp down starts, at full level, so that the key down
+        // lasts about as long above half the level as without shaping
+        if key_down {
+            self.ramp_position = (self.ramp_position + 1).min(ramp);
+        }
+        let level = 0.5 - 0.5 * (PI * self.ramp_position as f32 / ramp as f32).cos();
+        if !key_down {
+            self.ramp_position = self.ramp_position.saturating_sub(1);
+        }
+        level
+    }
+}
+
+#[doc(hidden)]
+#[async_trait]
+impl Kernel for CWEncoder {
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        sio: &mut StreamIo,
+        _mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        if self.stream_input {
+            let i = sio.input(0).slice::<char>();
+            let text: String = i.iter().collect();
+            self.enqueue(&text);
+            let n = i.len();
+            sio.input(0).consume(n);
+            if sio.input(0).finished() {
+                self.flush();
+                self.input_finished = true;
+            }
+        }
+
+        let o = sio.output(0).slice::<f32>();
+        let mut produced = 0;
+        while produced < o.len() {
+            let Some((key_down, left)) = self.segments.front_mut() else {
+                break;
+            };
+            let key_down = *key_down;
+            *left -= 1;
+            if *left == 0 {
+                self.segments.pop_front();
+            }
+            o[produced] = self.shape(key_down);
+            produced += 1;
+        }
+        sio.output(0).produce(produced);
+
+        if self.input_finished && self.segments.is_empty() {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
+
+/// Builder for [`CWEncoder`] block
+pub struct CWEncoderBuilder {
+    sample_rate: f32,
+    wpm: f32,
+    farnsworth: Option<f32>,
+    rise_time: f32,
+    stream_input: bool,
+    code: CWCode,
+}
+
+impl CWEncoderBuilder {
+    /// Create builder for an encoder producing `sample_rate` samples per second
+    ///
+    /// ## Defaults
+    /// - `wpm`: 20
+    /// - `farnsworth`: none
+    /// - `rise_time`: 5 ms
+    /// - `stream_input`: false
+    /// - `code`: [`CWCode::itu`]
+    pub fn new(sample_rate: f32) -> CWEncoderBuilder {
+        CWEncoderBuilder {
+            sample_rate,
+            wpm: 20.0,
+            farnsworth: None,
+            rise_time: 0.005,
+            stream_input: false,
+            code: CWCode::itu(),
+        }
+    }
+
+    /// Speed of the characters in words per minute
+    pub fn wpm(mut self, wpm: f32) -> CWEncoderBuilder {
+        self.wpm = wpm;
+        self
+    }
+
+    /// Overall speed in words per minute, at most `wpm`, with Farnsworth spacing
+    pub fn farnsworth(mut self, wpm: f32) -> CWEncoderBuilder {
+        self.farnsworth = Some(wpm);
+        self
+    }
+
+    /// Duration in seconds of the key down and key up ramps, shorter than a dot
+    pub fn rise_time(mut self, rise_time: f32) -> CWEncoderBuilder {
+        self.rise_time = rise_time;
+        self
+    }
+
+    /// Also read the characters to send from a `char` stream input
+    pub fn stream_input(mut self, stream_input: bool) -> CWEncoderBuilder {
+        self.stream_input = stream_input;
+        self
+    }
+
+    /// Code of the characters and prosigns to send
+    pub fn code(mut self, code: impl Into<CWCode>) -> CWEncoderBuilder {
+        self.code = code.into();
+        self
+    }
+
+    pub fn build_typed(self) -> TypedBlock<CWEncoder> {
+        assert!(self.wpm > 0.0, "wpm must be positive");
+        if let Some(farnsworth) = self.farnsworth {
+            assert!(
+                0.0 < farnsworth && farnsworth <= self.wpm,
+                "farnsworth speed must be positive and at most wpm"
+            );
+        }
+        let timing = CWTiming::new(self.sample_rate, self.wpm, self.farnsworth, self.rise_time);
+        assert!(timing.dot > 0, "a dot must last at least one sample");
+        assert!(
+            timing.ramp < timing.dot,
+            "rise time must be shorter than a dot"
+        );
+
+        let mut sio = StreamIoBuilder::new();
+        if self.stream_input {
+            sio = sio.add_input::<char>("in");
+        }
+
+        TypedBlock::new(
+            BlockMetaBuilder::new("CWEncoder").build(),
+            sio.add_output::<f32>("out").build(),
+            MessageIoBuilder::new()
+                .add_input("in", CWEncoder::text)
+                .build(),
+            CWEncoder {
+                code: self.code,
+                text: String::new(),
+                timing,
+                segments: VecDeque::new(),
+                ramp_position: 0,
+                stream_input: self.stream_input,
+                input_finished: false,
+            },
+        )
+    }
+
+    pub fn build(self) -> Block {
+        Block::from_typed(self.build_typed())
+    }
+}
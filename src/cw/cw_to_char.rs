@@ -1,45 +1,105 @@
 use async_trait::async_trait;
+use std::collections::VecDeque;
 
 use futuresdr::runtime::BlockMeta;
 use futuresdr::runtime::BlockMetaBuilder;
 use futuresdr::runtime::Kernel;
 use futuresdr::runtime::MessageIo;
 use futuresdr::runtime::MessageIoBuilder;
+use futuresdr::runtime::Pmt;
 use futuresdr::runtime::Result;
 use futuresdr::runtime::StreamIo;
 use futuresdr::runtime::StreamIoBuilder;
 use futuresdr::runtime::WorkIo;
 use futuresdr::runtime::{Block, TypedBlock};
 
+use crate::cw::code::CWCode;
 use crate::cw::shared::get_alphabet;
 use crate::cw::shared::CWAlphabet::{self, LetterSpace, WordSpace};
 use bimap::BiMap;
 
+/// Decode CW symbols into text.
+///
+/// Symbols are decoded with a [`CWCode`] at each [`LetterSpace`] or [`WordSpace`], into the
+/// characters of a character or prosign, or `unknown` for symbols missing from the code.
+/// Each word space is decoded into a space.
+///
+/// # Inputs
+///
+/// `in`: CW symbols
+///
+/// # Outputs
+///
+/// `out`: Decoded characters
+///
+/// # Messages
+///
+/// `words`: Each decoded word as [`Pmt::String`], at its word space, or when the input
+/// finishes.
+///
+/// # Usage
+/// ```
+/// use fsdr_blocks::cw::code::CWCode;
+/// use fsdr_blocks::cw::cw_to_char::CWToCharBuilder;
+///
+/// let cw_to_char = CWToCharBuilder::new()
+///     .code(CWCode::international().with_prosigns())
+///     .unknown('*')
+///     .build();
+/// ```
 pub struct CWToChar {
     // Required to keep the state of already received pulses
     symbol_vec: Vec<CWAlphabet>,
-    alphabet: BiMap<char, Vec<CWAlphabet>>,
+    code: CWCode,
+    unknown: char,
+    /// decoded characters not produced yet
+    pending: VecDeque<char>,
+    /// current word
+    word: String,
 }
 
 impl CWToChar {
     #[allow(clippy::new_ret_no_self)]
-    pub fn new(alphabet: BiMap<char, Vec<CWAlphabet>>) -> Block {
-        Block::from_typed(Self::new_typed(alphabet))
+    pub fn new(code: impl Into<CWCode>) -> Block {
+        Block::from_typed(Self::new_typed(code))
     }
 
-    pub fn new_typed(alphabet: BiMap<char, Vec<CWAlphabet>>) -> TypedBlock<Self> {
-        TypedBlock::new(
-            BlockMetaBuilder::new("CWToChar").build(),
-            StreamIoBuilder::new()
-                .add_input::<CWAlphabet>("in")
-                .add_output::<char>("out")
-                .build(),
-            MessageIoBuilder::new().build(),
-            CWToChar {
-                symbol_vec: vec![],
-                alphabet,
-            },
-        )
+    pub fn new_typed(code: impl Into<CWCode>) -> TypedBlock<Self> {
+        CWToCharBuilder::new().code(code).build_typed()
+    }
+
+    /// Decode a symbol, emitting the characters of the character or word space it ends.
+    /// Returns the word ended by a word space.
+    pub(crate) fn push(
+        &mut self,
+        symbol: CWAlphabet,
+        emit: &mut impl FnMut(char),
+    ) -> Option<String> {
+        if symbol != LetterSpace && symbol != WordSpace {
+            self.symbol_vec.push(symbol);
+            return None;
+        }
+        if !self.symbol_vec.is_empty() {
+            let text = self
+                .code
+                .decode(&self.symbol_vec)
+                .unwrap_or_else(|| self.unknown.to_string());
+            text.chars().for_each(&mut *emit);
+            self.word.push_str(&text);
+            self.symbol_vec.clear();
+        }
+        if symbol == WordSpace {
+            emit(' ');
+            if !self.word.is_empty() {
+                return Some(std::mem::take(&mut self.word));
+            }
+        }
+        None
+    }
+
+    /// End the transmission, returning its last word if no word space ended it
+    pub(crate) fn flush(&mut self) -> Option<String> {
+        (!self.word.is_empty()).then(|| std::mem::take(&mut self.word))
     }
 }
 
@@ -50,46 +110,37 @@ impl Kernel for CWToChar {
         &mut self,
         io: &mut WorkIo,
         sio: &mut StreamIo,
-        _mio: &mut MessageIo<Self>,
+        mio: &mut MessageIo<Self>,
         _meta: &mut BlockMeta,
     ) -> Result<()> {
-        // Not doing any checks on the output buffer length here.
-        // Assuming, that i and o are of the same length.
-        // Assuming, that one input sample generates at max one output sample.
+        // checked first, so that no symbols arrive after the slice of the input
+        let finished = sio.input(0).finished();
         let i = sio.input(0).slice::<CWAlphabet>();
-        let o = sio.output(0).slice::<char>();
-
-        let mut produced = 0;
-
-        self.symbol_vec.append(&mut i.to_vec());
-
-        if self.symbol_vec.contains(&WordSpace) || self.symbol_vec.contains(&LetterSpace) {
-            for (index, c) in self
-                .symbol_vec
-                .split_inclusive(|c| c == &LetterSpace || c == &WordSpace)
-                .filter_map(|c| c.split_last())
-                .map(|(last, elements)| {
-                    //println!("last: {}, elements: {:?}", last, elements);
-                    if last == &WordSpace {
-                        *self.alphabet.get_by_right(&vec![WordSpace]).unwrap_or(&'_')
-                    } else {
-                        *self.alphabet.get_by_right(elements).unwrap_or(&'_')
-                    }
-                })
-                .enumerate()
-            {
-                o[index] = c;
-                produced = index + 1;
-                //println!("c: {}, index: {}, produced: {}", c, index, produced);
-            }
-
-            self.symbol_vec.clear();
+        let n = i.len();
+
+        let mut pending = std::mem::take(&mut self.pending);
+        let mut words: Vec<String> = i
+            .iter()
+            .filter_map(|symbol| self.push(*symbol, &mut |c| pending.push_back(c)))
+            .collect();
+        self.pending = pending;
+        sio.input(0).consume(n);
+
+        if finished {
+            words.extend(self.flush());
+        }
+        for word in words {
+            mio.post(0, Pmt::String(word)).await;
         }
 
-        sio.input(0).consume(i.len());
+        let o = sio.output(0).slice::<char>();
+        let produced = o.len().min(self.pending.len());
+        for (o, c) in o.iter_mut().zip(self.pending.drain(..produced)) {
+            *o = c;
+        }
         sio.output(0).produce(produced);
 
-        if sio.input(0).finished() {
+        if finished && self.pending.is_empty() {
             io.finished = true;
         }
 
@@ -97,29 +148,73 @@ impl Kernel for CWToChar {
     }
 }
 
+/// Builder for [`CWToChar`] block
 pub struct CWToCharBuilder {
-    alphabet: BiMap<char, Vec<CWAlphabet>>,
+    code: CWCode,
+    unknown: char,
 }
 
 impl Default for CWToCharBuilder {
     fn default() -> Self {
         CWToCharBuilder {
-            alphabet: get_alphabet(),
+            code: CWCode::from(get_alphabet()),
+            unknown: '_',
         }
     }
 }
 
 impl CWToCharBuilder {
+    /// Create builder for a decoder
+    ///
+    /// ## Defaults
+    /// - `alphabet`: [`get_alphabet`]
+    /// - `unknown`: `_`
     pub fn new() -> CWToCharBuilder {
         CWToCharBuilder::default()
     }
 
-    /*pub fn alphabet(mut self, alphabet: BiMap<char, Vec<CWAlphabet>>) -> CWToCharBuilder {
-        self.alphabet = alphabet;
+    /// Table of the characters to decode
+    pub fn alphabet(mut self, alphabet: BiMap<char, Vec<CWAlphabet>>) -> CWToCharBuilder {
+        self.code = CWCode::from(alphabet);
+        self
+    }
+
+    /// Code of the characters and prosigns to decode
+    pub fn code(mut self, code: impl Into<CWCode>) -> CWToCharBuilder {
+        self.code = code.into();
         self
-    }*/
+    }
+
+    /// Character decoded from symbols missing from the code
+    pub fn unknown(mut self, unknown: char) -> CWToCharBuilder {
+        self.unknown = unknown;
+        self
+    }
+
+    /// Create the decoder, for use in other blocks
+    pub(crate) fn build_decoder(&self) -> CWToChar {
+        CWToChar {
+            symbol_vec: vec![],
+            code: self.code.clone(),
+            unknown: self.unknown,
+            pending: VecDeque::new(),
+            word: String::new(),
+        }
+    }
+
+    pub fn build_typed(self) -> TypedBlock<CWToChar> {
+        TypedBlock::new(
+            BlockMetaBuilder::new("CWToChar").build(),
+            StreamIoBuilder::new()
+                .add_input::<CWAlphabet>("in")
+                .add_output::<char>("out")
+                .build(),
+            MessageIoBuilder::new().add_output("words").build(),
+            self.build_decoder(),
+        )
+    }
 
     pub fn build(self) -> Block {
-        CWToChar::new(self.alphabet)
+        Block::from_typed(self.build_typed())
     }
 }
@@ -1,3 +1,8 @@
+pub mod adaptive_baseband_to_cw;
 pub mod baseband_to_cw;
+pub mod code;
+pub mod cw_encoder;
 pub mod cw_to_char;
 pub mod shared;
+pub mod skimmer;
+pub mod tone_detector;
@@ -0,0 +1,511 @@
+use async_trait::async_trait;
+use rustfft::{Fft, FftPlanner};
+use std::f32::consts::{LN_2, PI};
+use std::sync::Arc;
+
+use futuresdr::log::debug;
+use futuresdr::num_complex::{Complex32, Complex64, ComplexFloat};
+use futuresdr::runtime::Block;
+use futuresdr::runtime::BlockMeta;
+use futuresdr::runtime::BlockMetaBuilder;
+use futuresdr::runtime::Kernel;
+use futuresdr::runtime::MessageIo;
+use futuresdr::runtime::MessageIoBuilder;
+use futuresdr::runtime::Pmt;
+use futuresdr::runtime::Result;
+use futuresdr::runtime::StreamIo;
+use futuresdr::runtime::StreamIoBuilder;
+use futuresdr::runtime::WorkIo;
+use num_traits::ToPrimitive;
+
+use crate::cw::adaptive_baseband_to_cw::{AdaptiveBaseBandToCW, AdaptiveBaseBandToCWBuilder};
+use crate::cw::code::CWCode;
+use crate::cw::cw_to_char::{CWToChar, CWToCharBuilder};
+use crate::cw::shared::CWAlphabet;
+use crate::cw::tone_detector::{CWToneDetector, CWToneDetectorBuilder, CWToneDetectorConfig};
+use crate::serde_pmt::config::Configurable;
+
+/// Decode all the CW signals of a wide band, like a CW skimmer.
+///
+/// The input is cut into frames of `fft_size` samples. The spectrum of each frame, with a
+/// Hann window, is searched for peaks `peak_snr` dB above the median noise floor, which
+/// are CW carriers. A channel is started for each new carrier at least `bandwidth` Hz
+/// away from the others, up to `max_channels`. Its frequency follows the strongest peak
+/// of the carrier so far, which the frames keyed down all along estimate best.
+///
+/// Each channel decodes its carrier like a [`CWToneDetector`] followed by an
+/// [`AdaptiveBaseBandToCW`] and a [`CWToChar`], starting with the frame before the one
+/// the carrier was found in, so that its first character is not cut. Symbols missing from
+/// the code are decoded as `unknown`. A channel is dropped when its carrier stayed silent
+/// for `timeout` seconds, or when the input finishes, after its last word.
+///
+/// Audio, i.e., real samples, is searched for carriers at positive frequencies, and complex
+/// baseband at positive and negative ones, as told by [`SkimmerSample::REAL`].
+///
+/// # Inputs
+///
+/// `in`: Audio or baseband samples, implementing [`SkimmerSample`]
+///
+/// # Messages
+///
+/// `text`: Each decoded word as a [`Pmt::VecPmt`] of the frequency of its carrier in Hz as
+/// [`Pmt::F32`], and of the word as [`Pmt::String`].
+///
+/// # Usage
+/// ```
+/// use fsdr_blocks::cw::code::CWCode;
+/// use fsdr_blocks::cw::skimmer::CWSkimmerBuilder;
+///
+/// let skimmer = CWSkimmerBuilder::<f32>::new(48000.0)
+///     .fft_size(4096)
+///     .peak_snr(15.0)
+///     .max_channels(64)
+///     .code(CWCode::itu().with_prosigns())
+///     .unknown('*')
+///     .build();
+/// ```
+pub struct CWSkimmer<T> {
+    sample_rate: f32,
+    fft_size: usize,
+    /// minimum ratio of a peak to the mean noise power of a bin
+    peak_threshold: f32,
+    bandwidth: f32,
+    max_channels: usize,
+    /// samples of silence after which a channel is dropped
+    timeout: usize,
+    /// minimum SNR in dB of the tone detectors
+    min_snr: f32,
+    decoder: AdaptiveBaseBandToCWBuilder,
+    to_char: CWToCharBuilder,
+    /// Hann window, and its energy
+    window: Vec<f32>,
+    window_energy: f32,
+    fft: Arc<dyn Fft<f32>>,
+    fft_scratch: Vec<Complex32>,
+    /// frame being received, and the one before
+    frame: Vec<T>,
+    previous: Vec<T>,
+    channels: Vec<Channel<T>>,
+}
+
+/// Samples of the band decoded by a [`CWSkimmer`]
+pub trait SkimmerSample: Send + Sync + ComplexFloat + 'static {
+    /// Whether the samples are real, so that their spectrum is symmetric
+    const REAL: bool;
+}
+
+impl SkimmerSample for f32 {
+    const REAL: bool = true;
+}
+
+impl SkimmerSample for f64 {
+    const REAL: bool = true;
+}
+
+impl SkimmerSample for Complex32 {
+    const REAL: bool = false;
+}
+
+impl SkimmerSample for Complex64 {
+    const REAL: bool = false;
+}
+
+/// Decoder of a single carrier
+struct Channel<T> {
+    frequency: f32,
+    /// power of the strongest peak of the carrier in the spectrum
+    peak: f32,
+    detector: CWToneDetector<T>,
+    decoder: AdaptiveBaseBandToCW,
+    to_char: CWToChar,
+    /// samples since the key was last down
+    idle: usize,
+}
+
+impl<T> Channel<T>
+where
+    T: SkimmerSample,
+{
+    /// Tune to a better estimate of the frequency of the carrier
+    fn retune(&mut self, frequency: f32, peak: f32) {
+        self.frequency = frequency;
+        self.peak = peak;
+        let config = CWToneDetectorConfig {
+            frequency,
+            ..self.detector.config()
+        };
+        // the frequency is checked against the sample rate already
+        self.detector.set_config(config).unwrap();
+    }
+
+    /// Decode samples, adding the words they end to `text`
+    fn decode(&mut self, samples: &[T], text: &mut Vec<Pmt>) {
+        let mut symbols = Vec::new();
+        for x in samples {
+            let key_down = self.detector.key(*x);
+            self.idle = if key_down { 0 } else { self.idle + 1 };
+            self.decoder
+                .push(key_down, &mut |symbol| symbols.push(symbol));
+        }
+        for symbol in symbols {
+            self.symbol(symbol, text);
+        }
+    }
+
+    /// Decode the last word
+    fn flush(&mut self, text: &mut Vec<Pmt>) {
+        let mut symbols = Vec::new();
+        self.decoder.flush(&mut |symbol| symbols.push(symbol));
+        for symbol in symbols {
+            self.symbol(symbol, text);
+        }
+    }
+
+    fn symbol(&mut self, symbol: CWAlphabet, text: &mut Vec<Pmt>) {
+        if let Some(word) = self.to_char.push(symbol, &mut |_| {}) {
+            debug!("CWSkimmer: {} Hz: {}", self.frequency, word);
+            text.push(Pmt::VecPmt(vec![
+                Pmt::F32(self.frequency),
+                Pmt::String(word),
+            ]));
+        }
+    }
+}
+
+impl<T> CWSkimmer<T>
+where
+    T: SkimmerSample,
+{
+    /// Create a skimmer of `sample_rate` samples per second
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(sample_rate: f32) -> Block {
+        CWSkimmerBuilder::<T>::new(sample_rate).build()
+    }
+
+    /// Frequencies of the carriers currently decoded, in Hz
+    pub fn frequencies(&self) -> Vec<f32> {
+        self.channels.iter().map(|c| c.frequency).collect()
+    }
+
+    /// Start channels for the new carriers in the current frame
+    fn find_carriers(&mut self) {
+        let n = self.fft_size;
+        let mut spectrum: Vec<Complex32> = self
+            .frame
+            .iter()
+            .zip(&self.window)
+            .map(|(x, w)| {
+                let re = x.re().to_f32().unwrap_or_default();
+                let im = x.im().to_f32().unwrap_or_default();
+                Complex32::new(re, im) * *w
+            })
+            .collect();
+        self.fft
+            .process_with_scratch(&mut spectrum, &mut self.fft_scratch);
+        let power: Vec<f32> = spectrum.iter().map(|x| x.norm_sqr()).collect();
+
+        // bins of the band, without DC, and without negative frequencies for real input
+        let bins: Vec<usize> = if T::REAL {
+            (1..n / 2).collect()
+        } else {
+            (1..n).filter(|k| *k != n / 2).collect()
+        };
+
+        // the power of a bin of noise is exponentially distributed, its median is ln 2 of
+        // its mean
+        let mut sorted: Vec<f32> = bins.iter().map(|k| power[*k]).collect();
+        let mid = sorted.len() / 2;
+        let median = *sorted.select_nth_unstable_by(mid, |a, b| a.total_cmp(b)).1;
+        let noise = median / LN_2;
+
+        // maxima within the bandwidth, rather than keying sidebands of a carrier
+        let span = (self.bandwidth * n as f32 / self.sample_rate)
+            .ceil()
+            .max(1.0) as usize;
+        let mut peaks: Vec<(usize, f32)> = bins
+            .iter()
+            .copied()
+            .filter(|k| {
+                power[*k] > self.peak_threshold * noise
+                    && (1..=span).all(|d| {
+                        power[*k] > power[(k + n - d) % n] && power[*k] >= power[(k + d) % n]
+                    })
+            })
+            .map(|k| (k, power[k]))
+            .collect();
+        peaks.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        for (k, peak) in peaks {
+            // parabolic interpolation of the log power around the peak
+            let (a, b, c) = (
+                power[(k + n - 1) % n].max(f32::MIN_POSITIVE).ln(),
+                power[k].ln(),
+                power[(k + 1) % n].max(f32::MIN_POSITIVE).ln(),
+            );
+            let offset = 0.5 * (a - c) / (a - 2.0 * b + c);
+            let bin = if k > n / 2 {
+                k as f32 - n as f32
+            } else {
+                k as f32
+            };
+            let frequency = (bin + offset) * self.sample_rate / n as f32;
+            if frequency.abs() >= self.sample_rate / 2.0 {
+                continue;
+            }
+            if let Some(channel) = self
+                .channels
+                .iter_mut()
+                .find(|c| (c.frequency - frequency).abs() < self.bandwidth)
+            {
+                // frames keyed down all along estimate the frequency best
+                if peak > channel.peak {
+                    channel.retune(frequency, peak);
+                }
+                continue;
+            }
+            if self.channels.len() >= self.max_channels {
+                continue;
+            }
+
+            debug!("CWSkimmer: carrier at {} Hz", frequency);
+            let mut detector = CWToneDetectorBuilder::<T>::new(self.sample_rate, frequency)
+                .bandwidth(self.bandwidth)
+                .min_snr(self.min_snr)
+                .build_detector();
+            // mean amplitude of the noise in the tone filter, from its power in a bin
+            let noise_power = noise / self.window_energy;
+            detector.seed_noise((PI / 4.0 * noise_power / detector.filter_len() as f32).sqrt());
+            self.channels.push(Channel {
+                frequency,
+                peak,
+                detector,
+                decoder: self.decoder.build_decoder(),
+                to_char: self.to_char.build_decoder(),
+                idle: 0,
+            });
+        }
+    }
+
+    /// Decode a frame, and the one before for new channels
+    fn decode(&mut self, frame: &[T], new: usize, text: &mut Vec<Pmt>) {
+        let previous = std::mem::take(&mut self.previous);
+        for (i, channel) in self.channels.iter_mut().enumerate() {
+            if i >= new {
+                channel.decode(&previous, text);
+            }
+            channel.decode(frame, text);
+        }
+        self.previous = previous;
+
+        let timeout = self.timeout;
+        self.channels.retain_mut(|channel| {
+            if channel.idle < timeout {
+                return true;
+            }
+            debug!("CWSkimmer: carrier at {} Hz is gone", channel.frequency);
+            channel.flush(text);
+            false
+        });
+    }
+}
+
+#[doc(hidden)]
+#[async_trait]
+impl<T> Kernel for CWSkimmer<T>
+where
+    T: SkimmerSample,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        sio: &mut StreamIo,
+        mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        // checked first, so that no samples arrive after the slice of the input
+        let finished = sio.input(0).finished();
+        let i = sio.input(0).slice::<T>();
+
+        let mut text = vec![];
+        let mut consumed = 0;
+        while consumed < i.len() {
+            let n = (self.fft_size - self.frame.len()).min(i.len() - consumed);
+            self.frame.extend_from_slice(&i[consumed..consumed + n]);
+            consumed += n;
+
+            if self.frame.len() == self.fft_size {
+                let new = self.channels.len();
+                self.find_carriers();
+                let frame = std::mem::take(&mut self.frame);
+                self.decode(&frame, new, &mut text);
+                self.frame = std::mem::replace(&mut self.previous, frame);
+                self.frame.clear();
+            }
+        }
+        sio.input(0).consume(consumed);
+
+        if finished {
+            let frame = std::mem::take(&mut self.frame);
+            let new = self.channels.len();
+            self.decode(&frame, new, &mut text);
+            for mut channel in self.channels.drain(..) {
+                channel.flush(&mut text);
+            }
+            io.finished = true;
+        }
+
+        for t in text {
+            mio.post(0, t).await;
+        }
+
+        Ok(())
+    }
+}
+
+/// Builder for [`CWSkimmer`] block
+pub struct CWSkimmerBuilder<T>
+where
+    T: SkimmerSample,
+{
+    sample_rate: f32,
+    fft_size: usize,
+    peak_snr: f32,
+    bandwidth: f32,
+    max_channels: usize,
+    timeout: f32,
+    min_snr: f32,
+    wpm_range: (f32, f32),
+    code: CWCode,
+    unknown: char,
+    _type: std::marker::PhantomData<T>,
+}
+
+impl<T> CWSkimmerBuilder<T>
+where
+    T: SkimmerSample,
+{
+    /// Create builder for a skimmer of `sample_rate` samples per second
+    ///
+    /// ## Defaults
+    /// - `fft_size`: 1024
+    /// - `peak_snr`: 15 dB
+    /// - `bandwidth`: 50 Hz
+    /// - `max_channels`: 32
+    /// - `timeout`: 10 s
+    /// - `min_snr`: 10 dB
+    /// - `wpm_range`: 5 to 60
+    /// - `code`: [`CWCode::itu`]
+    /// - `unknown`: `_`
+    pub fn new(sample_rate: f32) -> CWSkimmerBuilder<T> {
+        CWSkimmerBuilder {
+            sample_rate,
+            fft_size: 1024,
+            peak_snr: 15.0,
+            bandwidth: 50.0,
+            max_channels: 32,
+            timeout: 10.0,
+            min_snr: 10.0,
+            wpm_range: (5.0, 60.0),
+            code: CWCode::itu(),
+            unknown: '_',
+            _type: std::marker::PhantomData,
+        }
+    }
+
+    /// Number of samples of the spectrum the carriers are found in
+    pub fn fft_size(mut self, fft_size: usize) -> CWSkimmerBuilder<T> {
+        self.fft_size = fft_size;
+        self
+    }
+
+    /// Minimum ratio in dB of a carrier to the noise power in the spectrum
+    pub fn peak_snr(mut self, peak_snr: f32) -> CWSkimmerBuilder<T> {
+        self.peak_snr = peak_snr;
+        self
+    }
+
+    /// Bandwidth in Hz of the channels, and minimum spacing of their carriers
+    pub fn bandwidth(mut self, bandwidth: f32) -> CWSkimmerBuilder<T> {
+        self.bandwidth = bandwidth;
+        self
+    }
+
+    /// Maximum number of carriers decoded at the same time
+    pub fn max_channels(mut self, max_channels: usize) -> CWSkimmerBuilder<T> {
+        self.max_channels = max_channels;
+        self
+    }
+
+    /// Silence in seconds after which a carrier is no longer decoded
+    pub fn timeout(mut self, timeout: f32) -> CWSkimmerBuilder<T> {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Minimum ratio in dB of a carrier to the noise floor of its channel to key down
+    pub fn min_snr(mut self, min_snr: f32) -> CWSkimmerBuilder<T> {
+        self.min_snr = min_snr;
+        self
+    }
+
+    /// Bounds of the speed of the carriers in words per minute
+    pub fn wpm_range(mut self, min: f32, max: f32) -> CWSkimmerBuilder<T> {
+        self.wpm_range = (min, max);
+        self
+    }
+
+    /// Code of the characters and prosigns to decode
+    pub fn code(mut self, code: impl Into<CWCode>) -> CWSkimmerBuilder<T> {
+        self.code = code.into();
+        self
+    }
+
+    /// Character decoded from symbols missing from the code
+    pub fn unknown(mut self, unknown: char) -> CWSkimmerBuilder<T> {
+        self.unknown = unknown;
+        self
+    }
+
+    /// Create [`CWSkimmer`] block
+    pub fn build(self) -> Block {
+        assert!(self.fft_size >= 16, "fft_size must be at least 16");
+        assert!(self.max_channels > 0, "max_channels must not be 0");
+        let (min, max) = self.wpm_range;
+        let n = self.fft_size;
+
+        let window: Vec<f32> = (0..n)
+            .map(|k| 0.5 - 0.5 * (2.0 * PI * k as f32 / n as f32).cos())
+            .collect();
+        let window_energy = window.iter().map(|w| w * w).sum();
+        let fft = FftPlanner::new().plan_fft_forward(n);
+        let fft_scratch = vec![Complex32::new(0.0, 0.0); fft.get_inplace_scratch_len()];
+
+        Block::new(
+            BlockMetaBuilder::new("CWSkimmer").build(),
+            StreamIoBuilder::new().add_input::<T>("in").build(),
+            MessageIoBuilder::<CWSkimmer<T>>::new()
+                .add_output("text")
+                .build(),
+            CWSkimmer::<T> {
+                sample_rate: self.sample_rate,
+                fft_size: n,
+                peak_threshold: 10f32.powf(self.peak_snr / 10.0),
+                bandwidth: self.bandwidth,
+                max_channels: self.max_channels,
+                timeout: (self.timeout * self.sample_rate) as usize,
+                min_snr: self.min_snr,
+                decoder: AdaptiveBaseBandToCWBuilder::new(self.sample_rate)
+                    .wpm_range(min, max)
+                    .wpm((min * max).sqrt()),
+                to_char: CWToCharBuilder::new().code(self.code).unknown(self.unknown),
+                window,
+                window_energy,
+                fft,
+                fft_scratch,
+                frame: Vec::with_capacity(n),
+                previous: vec![],
+                channels: vec![],
+            },
+        )
+    }
+}
@@ -0,0 +1,424 @@
+use async_trait::async_trait;
+use std::f64::consts::PI;
+
+use futuresdr::num_complex::{Complex64, ComplexFloat};
+use futuresdr::runtime::Block;
+use futuresdr::runtime::BlockMeta;
+use futuresdr::runtime::BlockMetaBuilder;
+use futuresdr::runtime::Kernel;
+use futuresdr::runtime::MessageIo;
+use futuresdr::runtime::MessageIoBuilder;
+use futuresdr::runtime::Result;
+use futuresdr::runtime::StreamIo;
+use futuresdr::runtime::StreamIoBuilder;
+use futuresdr::runtime::WorkIo;
+use num_traits::ToPrimitive;
+use serde::{Deserialize, Serialize};
+
+use crate::serde_pmt::config::{get_config, set_config, set_config_field, Configurable};
+
+/// Detect a CW tone, and key a clean baseband from it.
+///
+/// The amplitude of the tone is measured with a sliding DFT bin at `frequency`, i.e.,
+/// a filter matched to `sample_rate / bandwidth` samples of the tone, updated on each
+/// sample. The noise floor is averaged over `decay` seconds while the key is up, and the
+/// tone amplitude is tracked while the key is down. It decays to the noise floor over
+/// `decay` seconds when the key stays up.
+///
+/// The key goes down when the amplitude rises above the threshold, halfway between the
+/// noise floor and the tone, but at least `min_snr` dB above the noise floor, and up when
+/// it falls below it, with `hysteresis` dB between both. Changes of the key lasting less
+/// than a quarter of the filter length are ignored as noise.
+/// As the amplitude of a keyed tone ramps over the filter length, crossing it halfway keeps
+/// the length of marks and spaces, so that the output suits
+/// [`BaseBandToCW`](crate::cw::baseband_to_cw::BaseBandToCW) and
+/// [`AdaptiveBaseBandToCW`](crate::cw::adaptive_baseband_to_cw::AdaptiveBaseBandToCW),
+/// delayed by three quarters of the filter length.
+///
+/// # Inputs
+///
+/// `in`: Audio or baseband samples, implementing [`ComplexFloat`]
+///
+/// # Outputs
+///
+/// - `out`: Keyed baseband, 1.0 while the tone is detected, 0.0 otherwise
+/// - `snr`: Ratio of the tone amplitude to the noise floor in dB, as `f32`, when enabled
+///   with [`CWToneDetectorBuilder::snr_output`]
+///
+/// # Messages
+///
+/// - `frequency`: tune the detector to the tone frequency in Hz.
+/// - `config`: set any fields of the [`CWToneDetectorConfig`] with a
+///   [`Pmt::MapStrPmt`](futuresdr::runtime::Pmt::MapStrPmt).
+/// - `get_config`: get the current [`CWToneDetectorConfig`].
+///
+/// See [`serde_pmt::config`](crate::serde_pmt::config) for the replies.
+///
+/// # Usage
+/// ```
+/// use fsdr_blocks::cw::tone_detector::CWToneDetectorBuilder;
+///
+/// let detector = CWToneDetectorBuilder::<f32>::new(8000.0, 700.0)
+///     .bandwidth(100.0)
+///     .min_snr(10.0)
+///     .snr_output(true)
+///     .build();
+/// ```
+pub struct CWToneDetector<T> {
+    sample_rate: f32,
+    config: CWToneDetectorConfig,
+    snr_output: bool,
+    /// last products of the input with the tone, summed into the DFT bin
+    window: Vec<Complex64>,
+    index: usize,
+    sum: Complex64,
+    /// phase of the tone, and its rotation per sample
+    phasor: Complex64,
+    rotation: Complex64,
+    /// samples until the window is filled
+    warmup: usize,
+    noise: f32,
+    /// samples the noise floor is averaged over so far
+    noise_samples: usize,
+    tone: f32,
+    /// tracking rate over the window length, and over `decay`
+    attack_rate: f32,
+    decay_rate: f32,
+    key_down: bool,
+    /// samples the key has been about to change for
+    pending: usize,
+    _type: std::marker::PhantomData<T>,
+}
+
+/// Parameters of the [`CWToneDetector`] block, settable through its `config` message handler
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CWToneDetectorConfig {
+    /// frequency of the tone in Hz, negative for complex baseband below the center
+    pub frequency: f32,
+    /// bandwidth of the filter in Hz, the inverse of its length
+    pub bandwidth: f32,
+    /// minimum ratio in dB of the tone to the noise floor to key down
+    pub min_snr: f32,
+    /// gap in dB between the key down and key up thresholds
+    pub hysteresis: f32,
+    /// time constant in seconds of the noise floor average, and of the decay of the tone
+    pub decay: f32,
+}
+
+impl<T> CWToneDetector<T>
+where
+    T: Send + Sync + ComplexFloat + 'static,
+{
+    /// Create a detector of a tone at `frequency` Hz in `sample_rate` samples per second
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(sample_rate: f32, frequency: f32) -> Block {
+        CWToneDetectorBuilder::<T>::new(sample_rate, frequency).build()
+    }
+
+    fn check(&self, config: &CWToneDetectorConfig) -> std::result::Result<(), String> {
+        if config.frequency.abs() >= self.sample_rate / 2.0 {
+            return Err("frequency must be below half the sample rate".to_string());
+        }
+        if !(0.0 < config.bandwidth && config.bandwidth <= self.sample_rate) {
+            return Err("bandwidth must be positive and at most the sample rate".to_string());
+        }
+        if config.min_snr < 0.0 || config.hysteresis < 0.0 {
+            return Err("min_snr and hysteresis must not be negative".to_string());
+        }
+        if config.decay <= 0.0 {
+            return Err("decay must be positive".to_string());
+        }
+        Ok(())
+    }
+
+    /// Set up the filter and trackers for the current configuration
+    fn reset(&mut self) {
+        let len = (self.sample_rate / self.config.bandwidth).round().max(1.0) as usize;
+        if len != self.window.len() {
+            self.window = vec![Complex64::new(0.0, 0.0); len];
+            self.index = 0;
+            self.sum = Complex64::new(0.0, 0.0);
+            self.warmup = len;
+            self.noise_samples = 0;
+        }
+        let omega = 2.0 * PI * self.config.frequency as f64 / self.sample_rate as f64;
+        self.rotation = Complex64::from_polar(1.0, -omega);
+        self.attack_rate = 1.0 - (-1.0 / len as f32).exp();
+        self.decay_rate = 1.0 - (-1.0 / (self.config.decay * self.sample_rate)).exp();
+    }
+
+    /// Amplitude of the tone in the window, after a new input sample
+    #[inline(always)]
+    fn amplitude(&mut self, input: T) -> f32 {
+        let re = input.re().to_f64().unwrap_or_default();
+        let im = input.im().to_f64().unwrap_or_default();
+        let product = Complex64::new(re, im) * self.phasor;
+        self.phasor *= self.rotation;
+        if self.index == 0 {
+            // keep the phasor on the unit circle
+            self.phasor /= self.phasor.norm();
+        }
+
+        self.sum += product - self.window[self.index];
+        self.window[self.index] = product;
+        self.index = (self.index + 1) % self.window.len();
+        (self.sum.norm() / self.window.len() as f64) as f32
+    }
+
+    /// Key state after a new input sample
+    #[inline(always)]
+    pub(crate) fn key(&mut self, input: T) -> bool {
+        let amplitude = self.amplitude(input);
+        self.detect(amplitude);
+        self.key_down
+    }
+
+    /// Start from a noise floor estimated elsewhere, as the amplitude of the tone filter,
+    /// instead of the amplitude at the end of the warmup, which a tone may be keying
+    pub(crate) fn seed_noise(&mut self, noise: f32) {
+        self.noise = noise;
+        self.tone = noise;
+        self.noise_samples = self.window.len();
+    }
+
+    /// Length of the tone filter in samples
+    pub(crate) fn filter_len(&self) -> usize {
+        self.window.len()
+    }
+
+    /// Update the trackers and the key with the amplitude of the tone
+    #[inline(always)]
+    fn detect(&mut self, amplitude: f32) {
+        if self.warmup > 0 {
+            self.warmup -= 1;
+            if self.noise_samples == 0 {
+                self.noise = amplitude;
+            }
+            self.tone = amplitude.max(self.noise);
+            return;
+        }
+
+        if self.key_down {
+            self.tone += self.attack_rate * (amplitude - self.tone);
+        } else {
+            // average of the whole noise at first, then over `decay`
+            self.noise_samples += 1;
+            let rate = (1.0 / self.noise_samples as f32).max(self.decay_rate);
+            self.noise += rate * (amplitude - self.noise);
+            self.tone += self.decay_rate * (self.noise - self.tone);
+        }
+        self.tone = self.tone.max(self.noise);
+
+        let min_snr = 10f32.powf(self.config.min_snr / 20.0);
+        let threshold = ((self.noise + self.tone) / 2.0).max(self.noise * min_snr);
+        let hysteresis = 10f32.powf(self.config.hysteresis / 40.0);
+        let key_down = if self.key_down {
+            amplitude >= threshold / hysteresis
+        } else {
+            amplitude > threshold * hysteresis
+        };
+
+        // ignore glitches much shorter than the filter, delaying both edges alike
+        if key_down == self.key_down {
+            self.pending = 0;
+        } else {
+            self.pending += 1;
+            if self.pending >= self.window.len() / 4 {
+                self.key_down = key_down;
+                self.pending = 0;
+            }
+        }
+    }
+
+    /// Ratio of the tone to the noise floor in dB
+    fn snr(&self) -> f32 {
+        20.0 * (self.tone.max(f32::MIN_POSITIVE) / self.noise.max(f32::MIN_POSITIVE)).log10()
+    }
+}
+
+impl<T> Configurable for CWToneDetector<T>
+where
+    T: Send + Sync + ComplexFloat + 'static,
+{
+    type Config = CWToneDetectorConfig;
+
+    fn config(&self) -> CWToneDetectorConfig {
+        self.config.clone()
+    }
+
+    fn set_config(&mut self, config: CWToneDetectorConfig) -> std::result::Result<(), String> {
+        self.check(&config)?;
+        self.config = config;
+        self.reset();
+        Ok(())
+    }
+}
+
+#[doc(hidden)]
+#[async_trait]
+impl<T> Kernel for CWToneDetector<T>
+where
+    T: Send + Sync + ComplexFloat + 'static,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        sio: &mut StreamIo,
+        _mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let i = sio.input(0).slice::<T>();
+        let o = sio.output(0).slice::<f32>();
+        let s = if self.snr_output {
+            sio.output(1).slice::<f32>()
+        } else {
+            &mut []
+        };
+
+        let n = if self.snr_output {
+            i.len().min(o.len()).min(s.len())
+        } else {
+            i.len().min(o.len())
+        };
+
+        for k in 0..n {
+            o[k] = if self.key(i[k]) { 1.0 } else { 0.0 };
+            if self.snr_output {
+                s[k] = self.snr();
+            }
+        }
+
+        sio.input(0).consume(n);
+        sio.output(0).produce(n);
+        if self.snr_output {
+            sio.output(1).produce(n);
+        }
+
+        if sio.input(0).finished() && n == i.len() {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
+
+/// Builder for [`CWToneDetector`] block
+pub struct CWToneDetectorBuilder<T>
+where
+    T: Send + Sync + ComplexFloat + 'static,
+{
+    sample_rate: f32,
+    config: CWToneDetectorConfig,
+    snr_output: bool,
+    _type: std::marker::PhantomData<T>,
+}
+
+impl<T> CWToneDetectorBuilder<T>
+where
+    T: Send + Sync + ComplexFloat + 'static,
+{
+    /// Create builder for a detector of a tone at `frequency` Hz in `sample_rate`
+    /// samples per second
+    ///
+    /// ## Defaults
+    /// - `bandwidth`: 50 Hz
+    /// - `min_snr`: 10 dB
+    /// - `hysteresis`: 1 dB
+    /// - `decay`: 2 s
+    /// - `snr_output`: false
+    pub fn new(sample_rate: f32, frequency: f32) -> CWToneDetectorBuilder<T> {
+        CWToneDetectorBuilder {
+            sample_rate,
+            config: CWToneDetectorConfig {
+                frequency,
+                bandwidth: 50.0,
+                min_snr: 10.0,
+                hysteresis: 1.0,
+                decay: 2.0,
+            },
+            snr_output: false,
+            _type: std::marker::PhantomData,
+        }
+    }
+
+    /// Bandwidth of the filter in Hz; narrower rejects more noise, but smears short marks
+    pub fn bandwidth(mut self, bandwidth: f32) -> CWToneDetectorBuilder<T> {
+        self.config.bandwidth = bandwidth;
+        self
+    }
+
+    /// Minimum ratio in dB of the tone to the noise floor to key down
+    pub fn min_snr(mut self, min_snr: f32) -> CWToneDetectorBuilder<T> {
+        self.config.min_snr = min_snr;
+        self
+    }
+
+    /// Gap in dB between the key down and key up thresholds
+    pub fn hysteresis(mut self, hysteresis: f32) -> CWToneDetectorBuilder<T> {
+        self.config.hysteresis = hysteresis;
+        self
+    }
+
+    /// Time constant in seconds of the noise floor average, and of the decay of the tone
+    pub fn decay(mut self, decay: f32) -> CWToneDetectorBuilder<T> {
+        self.config.decay = decay;
+        self
+    }
+
+    /// Output the SNR estimate on the `snr` stream
+    pub fn snr_output(mut self, snr_output: bool) -> CWToneDetectorBuilder<T> {
+        self.snr_output = snr_output;
+        self
+    }
+
+    /// Create the detector, for use in other blocks
+    pub(crate) fn build_detector(&self) -> CWToneDetector<T> {
+        let mut detector = CWToneDetector::<T> {
+            sample_rate: self.sample_rate,
+            config: self.config.clone(),
+            snr_output: self.snr_output,
+            window: vec![],
+            index: 0,
+            sum: Complex64::new(0.0, 0.0),
+            phasor: Complex64::new(1.0, 0.0),
+            rotation: Complex64::new(1.0, 0.0),
+            warmup: 0,
+            noise: 0.0,
+            noise_samples: 0,
+            tone: 0.0,
+            attack_rate: 0.0,
+            decay_rate: 0.0,
+            key_down: false,
+            pending: 0,
+            _type: std::marker::PhantomData,
+        };
+        if let Err(e) = detector.check(&self.config) {
+            panic!("invalid tone detector config: {e}");
+        }
+        detector.reset();
+        detector
+    }
+
+    /// Create [`CWToneDetector`] block
+    pub fn build(self) -> Block {
+        let detector = self.build_detector();
+
+        let mut sio = StreamIoBuilder::new()
+            .add_input::<T>("in")
+            .add_output::<f32>("out");
+        if self.snr_output {
+            sio = sio.add_output::<f32>("snr");
+        }
+
+        Block::new(
+            BlockMetaBuilder::new("CWToneDetector").build(),
+            sio.build(),
+            MessageIoBuilder::<CWToneDetector<T>>::new()
+                .add_input("frequency", set_config_field("frequency"))
+                .add_input("config", set_config)
+                .add_input("get_config", get_config)
+                .build(),
+            detector,
+        )
+    }
+}
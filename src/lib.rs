@@ -15,9 +15,15 @@ pub mod async_channel;
 #[cfg(feature = "cw")]
 pub mod cw;
 
+#[cfg(feature = "zeromq")]
+pub mod zeromq;
+
 pub mod agc;
 pub mod math;
+pub mod message;
+pub mod net;
 pub mod sigmf;
+pub mod squelch;
 pub mod stdinout;
 pub mod stream;
 pub mod type_converters;
@@ -0,0 +1,16 @@
+//! ## Blocks recording and replaying messages
+//!
+//! [`MessageRecorder`] writes every message it receives to a file, along with the time
+//! it was received at. [`MessageReplayer`] posts the messages of such a recording again,
+//! with their original timing or as fast as possible, e.g. to reproduce a sequence of
+//! AGC or tuning commands deterministically in tests.
+//!
+//! Messages are serialized with [`serde_pmt`](crate::serde_pmt), in one of the
+//! [`RecordFormat`]s.
+mod record;
+mod recorder;
+mod replayer;
+
+pub use record::{MessageRecord, RecordFormat};
+pub use recorder::{MessageRecorder, MessageRecorderBuilder};
+pub use replayer::{MessageReplayer, MessageReplayerBuilder, ReplayTiming};
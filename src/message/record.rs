@@ -0,0 +1,85 @@
+use futuresdr::anyhow::anyhow;
+use futuresdr::runtime::Pmt;
+use futuresdr::runtime::Result;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+use crate::serde_pmt::cbor::{from_cbor_bytes, to_cbor_bytes};
+use crate::serde_pmt::json::{from_json, to_json};
+
+/// Serialization of a recording of messages
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum RecordFormat {
+    /// One JSON object per line, e.g. `{"pmt": {"F32": 0.5}, "time_ns": 1500000}`,
+    /// with the message in the [`json`](crate::serde_pmt::json) representation.
+    #[default]
+    JsonLines,
+    /// A sequence of CBOR items, the time in nanoseconds as a [`Pmt::U64`] followed by the
+    /// message, both in the [`cbor`](crate::serde_pmt::cbor) representation.
+    Cbor,
+}
+
+/// A message, with the time it was received at relative to the start of the recording
+#[derive(Debug, Clone, PartialEq)]
+pub struct MessageRecord {
+    pub time: Duration,
+    pub pmt: Pmt,
+}
+
+impl MessageRecord {
+    pub fn new(time: Duration, pmt: Pmt) -> MessageRecord {
+        MessageRecord { time, pmt }
+    }
+
+    /// Append the serialization of the record to `out`.
+    pub fn encode(&self, format: RecordFormat, out: &mut Vec<u8>) -> Result<()> {
+        let time_ns = u64::try_from(self.time.as_nanos()).unwrap_or(u64::MAX);
+        match format {
+            RecordFormat::JsonLines => {
+                let line = json!({ "time_ns": time_ns, "pmt": to_json(&self.pmt)? });
+                serde_json::to_writer(&mut *out, &line)?;
+                out.push(b'\n');
+            }
+            RecordFormat::Cbor => {
+                to_cbor_bytes(&Pmt::U64(time_ns), out)?;
+                to_cbor_bytes(&self.pmt, out)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Decode all the records of a recording.
+    pub fn decode_all(format: RecordFormat, mut data: &[u8]) -> Result<Vec<MessageRecord>> {
+        let mut records = vec![];
+        match format {
+            RecordFormat::JsonLines => {
+                for line in std::str::from_utf8(data)?.lines() {
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    let value: Value = serde_json::from_str(line)?;
+                    let time_ns = value["time_ns"]
+                        .as_u64()
+                        .ok_or_else(|| anyhow!("record without time_ns: {}", line))?;
+                    records.push(MessageRecord::new(
+                        Duration::from_nanos(time_ns),
+                        from_json(&value["pmt"])?,
+                    ));
+                }
+            }
+            RecordFormat::Cbor => {
+                while !data.is_empty() {
+                    let time_ns = match from_cbor_bytes(&mut data)? {
+                        Pmt::U64(t) => t,
+                        p => return Err(anyhow!("expected the time of a record, got {:?}", p)),
+                    };
+                    records.push(MessageRecord::new(
+                        Duration::from_nanos(time_ns),
+                        from_cbor_bytes(&mut data)?,
+                    ));
+                }
+            }
+        }
+        Ok(records)
+    }
+}
@@ -0,0 +1,151 @@
+use async_trait::async_trait;
+use futuresdr::macros::message_handler;
+use futuresdr::runtime::BlockMeta;
+use futuresdr::runtime::BlockMetaBuilder;
+use futuresdr::runtime::Kernel;
+use futuresdr::runtime::MessageIo;
+use futuresdr::runtime::MessageIoBuilder;
+use futuresdr::runtime::Pmt;
+use futuresdr::runtime::Result;
+use futuresdr::runtime::StreamIo;
+use futuresdr::runtime::StreamIoBuilder;
+use futuresdr::runtime::WorkIo;
+use futuresdr::runtime::{Block, TypedBlock};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::time::Instant;
+
+use super::{MessageRecord, RecordFormat};
+
+/// Record messages to a file, with the time they were received at.
+///
+/// Times are relative to the start of the flowgraph. Each record is flushed to the file
+/// as soon as it is received, so that the recording is usable even if the flowgraph
+/// does not terminate properly.
+/// The block finishes on [`Pmt::Finished`], which is not recorded.
+///
+/// # Message inputs
+///
+/// `in`: Messages to record
+///
+/// # Usage
+/// ```no_run
+/// use fsdr_blocks::message::{MessageRecorderBuilder, RecordFormat};
+/// use futuresdr::runtime::Flowgraph;
+///
+/// let mut fg = Flowgraph::new();
+/// let recorder = fg.add_block(
+///     MessageRecorderBuilder::new("agc_commands.cbor")
+///         .format(RecordFormat::Cbor)
+///         .build(),
+/// );
+/// ```
+pub struct MessageRecorder {
+    path: PathBuf,
+    format: RecordFormat,
+    writer: Option<BufWriter<File>>,
+    start: Instant,
+    buffer: Vec<u8>,
+}
+
+impl MessageRecorder {
+    /// Record messages as [`RecordFormat::JsonLines`]
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new<P: Into<PathBuf>>(path: P) -> Block {
+        MessageRecorderBuilder::new(path).build()
+    }
+
+    pub fn new_typed<P: Into<PathBuf>>(path: P) -> TypedBlock<Self> {
+        MessageRecorderBuilder::new(path).build_typed()
+    }
+
+    #[message_handler]
+    async fn record(
+        &mut self,
+        io: &mut WorkIo,
+        _mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        if let Pmt::Finished = p {
+            io.finished = true;
+            return Ok(Pmt::Ok);
+        }
+        if let Some(writer) = self.writer.as_mut() {
+            self.buffer.clear();
+            MessageRecord::new(self.start.elapsed(), p).encode(self.format, &mut self.buffer)?;
+            writer.write_all(&self.buffer)?;
+            writer.flush()?;
+        }
+        Ok(Pmt::Ok)
+    }
+}
+
+#[doc(hidden)]
+#[async_trait]
+impl Kernel for MessageRecorder {
+    async fn init(
+        &mut self,
+        _sio: &mut StreamIo,
+        _mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        self.writer = Some(BufWriter::new(File::create(&self.path)?));
+        self.start = Instant::now();
+        Ok(())
+    }
+
+    async fn deinit(
+        &mut self,
+        _sio: &mut StreamIo,
+        _mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        if let Some(mut writer) = self.writer.take() {
+            writer.flush()?;
+        }
+        Ok(())
+    }
+}
+
+/// Build a [`MessageRecorder`].
+pub struct MessageRecorderBuilder {
+    path: PathBuf,
+    format: RecordFormat,
+}
+
+impl MessageRecorderBuilder {
+    pub fn new<P: Into<PathBuf>>(path: P) -> MessageRecorderBuilder {
+        MessageRecorderBuilder {
+            path: path.into(),
+            format: RecordFormat::default(),
+        }
+    }
+
+    pub fn format(mut self, format: RecordFormat) -> MessageRecorderBuilder {
+        self.format = format;
+        self
+    }
+
+    pub fn build(self) -> Block {
+        Block::from_typed(self.build_typed())
+    }
+
+    pub fn build_typed(self) -> TypedBlock<MessageRecorder> {
+        TypedBlock::new(
+            BlockMetaBuilder::new("MessageRecorder").build(),
+            StreamIoBuilder::new().build(),
+            MessageIoBuilder::new()
+                .add_input("in", MessageRecorder::record)
+                .build(),
+            MessageRecorder {
+                path: self.path,
+                format: self.format,
+                writer: None,
+                start: Instant::now(),
+                buffer: vec![],
+            },
+        )
+    }
+}
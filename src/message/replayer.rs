@@ -0,0 +1,170 @@
+use async_trait::async_trait;
+use futuresdr::async_io::Timer;
+use futuresdr::runtime::BlockMeta;
+use futuresdr::runtime::BlockMetaBuilder;
+use futuresdr::runtime::Kernel;
+use futuresdr::runtime::MessageIo;
+use futuresdr::runtime::MessageIoBuilder;
+use futuresdr::runtime::Result;
+use futuresdr::runtime::StreamIo;
+use futuresdr::runtime::StreamIoBuilder;
+use futuresdr::runtime::WorkIo;
+use futuresdr::runtime::{Block, TypedBlock};
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::time::Instant;
+
+use super::{MessageRecord, RecordFormat};
+
+/// Pace at which a [`MessageReplayer`] posts the recorded messages
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum ReplayTiming {
+    /// Post each message at its recorded time, relative to the start of the flowgraph
+    #[default]
+    Original,
+    /// Post all the messages at once, in their recorded order
+    AsFastAsPossible,
+}
+
+/// Post the messages of a recording, e.g. made with a [`MessageRecorder`](super::MessageRecorder).
+///
+/// The block finishes once all the messages are posted.
+///
+/// # Message outputs
+///
+/// `out`: Recorded messages
+///
+/// # Usage
+/// ```no_run
+/// use fsdr_blocks::message::{MessageReplayerBuilder, RecordFormat, ReplayTiming};
+/// use futuresdr::runtime::Flowgraph;
+///
+/// let mut fg = Flowgraph::new();
+/// let replayer = fg.add_block(
+///     MessageReplayerBuilder::new("agc_commands.cbor")
+///         .format(RecordFormat::Cbor)
+///         .timing(ReplayTiming::AsFastAsPossible)
+///         .build(),
+/// );
+/// ```
+pub struct MessageReplayer {
+    path: Option<PathBuf>,
+    format: RecordFormat,
+    timing: ReplayTiming,
+    records: VecDeque<MessageRecord>,
+    start: Instant,
+}
+
+impl MessageReplayer {
+    /// Replay a [`RecordFormat::JsonLines`] recording with its original timing
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new<P: Into<PathBuf>>(path: P) -> Block {
+        MessageReplayerBuilder::new(path).build()
+    }
+
+    pub fn new_typed<P: Into<PathBuf>>(path: P) -> TypedBlock<Self> {
+        MessageReplayerBuilder::new(path).build_typed()
+    }
+}
+
+#[doc(hidden)]
+#[async_trait]
+impl Kernel for MessageReplayer {
+    async fn init(
+        &mut self,
+        _sio: &mut StreamIo,
+        _mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        if let Some(path) = &self.path {
+            let data = std::fs::read(path)?;
+            self.records = MessageRecord::decode_all(self.format, &data)?.into();
+        }
+        self.start = Instant::now();
+        Ok(())
+    }
+
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _sio: &mut StreamIo,
+        mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        while let Some(record) = self.records.front() {
+            if self.timing == ReplayTiming::Original {
+                let deadline = self.start + record.time;
+                if Instant::now() < deadline {
+                    io.block_on(async move {
+                        Timer::at(deadline).await;
+                    });
+                    return Ok(());
+                }
+            }
+            let record = self.records.pop_front().unwrap();
+            mio.post(0, record.pmt).await;
+        }
+
+        io.finished = true;
+        Ok(())
+    }
+}
+
+/// Build a [`MessageReplayer`].
+pub struct MessageReplayerBuilder {
+    path: Option<PathBuf>,
+    format: RecordFormat,
+    timing: ReplayTiming,
+    records: Vec<MessageRecord>,
+}
+
+impl MessageReplayerBuilder {
+    /// Replay the recording stored at `path`, read when the flowgraph starts
+    pub fn new<P: Into<PathBuf>>(path: P) -> MessageReplayerBuilder {
+        MessageReplayerBuilder {
+            path: Some(path.into()),
+            format: RecordFormat::default(),
+            timing: ReplayTiming::default(),
+            records: vec![],
+        }
+    }
+
+    /// Replay records already in memory
+    pub fn from_records(records: Vec<MessageRecord>) -> MessageReplayerBuilder {
+        MessageReplayerBuilder {
+            path: None,
+            format: RecordFormat::default(),
+            timing: ReplayTiming::default(),
+            records,
+        }
+    }
+
+    pub fn format(mut self, format: RecordFormat) -> MessageReplayerBuilder {
+        self.format = format;
+        self
+    }
+
+    pub fn timing(mut self, timing: ReplayTiming) -> MessageReplayerBuilder {
+        self.timing = timing;
+        self
+    }
+
+    pub fn build(self) -> Block {
+        Block::from_typed(self.build_typed())
+    }
+
+    pub fn build_typed(self) -> TypedBlock<MessageReplayer> {
+        TypedBlock::new(
+            BlockMetaBuilder::new("MessageReplayer").build(),
+            StreamIoBuilder::new().build(),
+            MessageIoBuilder::new().add_output("out").build(),
+            MessageReplayer {
+                path: self.path,
+                format: self.format,
+                timing: self.timing,
+                records: self.records.into(),
+                start: Instant::now(),
+            },
+        )
+    }
+}
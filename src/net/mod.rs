@@ -0,0 +1,14 @@
+//! ## Blocks related to network streaming
+//!
+//! TCP streams are handled by [`crate::stdinout`] through
+//! [`ByteStreamEndpoint::TcpClient`](crate::stdinout::ByteStreamEndpoint::TcpClient)
+//! and [`ByteStreamEndpoint::TcpServer`](crate::stdinout::ByteStreamEndpoint::TcpServer).
+mod rtl_tcp_source;
+mod udp;
+mod udp_sink;
+mod udp_source;
+
+pub use rtl_tcp_source::{RtlTcpSource, RtlTcpSourceBuilder};
+pub use udp::UdpBuilder;
+pub use udp_sink::UdpSink;
+pub use udp_source::UdpSource;
@@ -0,0 +1,334 @@
+use futuresdr::log::{info, warn};
+use futuresdr::macros::message_handler;
+use futuresdr::num_complex::Complex32;
+use futuresdr::runtime::Block;
+use futuresdr::runtime::BlockMeta;
+use futuresdr::runtime::BlockMetaBuilder;
+use futuresdr::runtime::Kernel;
+use futuresdr::runtime::MessageIo;
+use futuresdr::runtime::MessageIoBuilder;
+use futuresdr::runtime::Pmt;
+use futuresdr::runtime::Result;
+use futuresdr::runtime::StreamIo;
+use futuresdr::runtime::StreamIoBuilder;
+use futuresdr::runtime::WorkIo;
+use std::io::{ErrorKind, Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+/// Period at which the socket is polled, so that the block stays responsive to messages.
+const RECV_TIMEOUT: Duration = Duration::from_millis(100);
+
+const CMD_SET_FREQUENCY: u8 = 0x01;
+const CMD_SET_SAMPLE_RATE: u8 = 0x02;
+const CMD_SET_GAIN_MODE: u8 = 0x03;
+const CMD_SET_GAIN: u8 = 0x04;
+const CMD_SET_FREQ_CORRECTION: u8 = 0x05;
+const CMD_SET_AGC_MODE: u8 = 0x08;
+
+fn pmt_to_f64(p: &Pmt) -> Option<f64> {
+    match p {
+        Pmt::F32(v) => Some(*v as f64),
+        Pmt::F64(v) => Some(*v),
+        Pmt::U32(v) => Some(*v as f64),
+        Pmt::U64(v) => Some(*v as f64),
+        Pmt::Usize(v) => Some(*v as f64),
+        _ => None,
+    }
+}
+
+/// Receive IQ samples from an `rtl_tcp` server, and control the dongle through messages.
+///
+/// The 8-bit unsigned IQ samples sent by the server are scaled to `[-1.0, 1.0]`.
+/// The connection is opened, and the initial settings sent, when the flowgraph starts.
+/// The block finishes when the server closes the connection.
+///
+/// # Outputs
+///
+/// `out`: received samples
+///
+/// # Messages
+///
+/// - `freq`: set the center frequency in Hz
+/// - `sample_rate`: set the sample rate in Hz
+/// - `gain`: set a manual gain in dB, or go back to automatic gain with [`Pmt::Null`]
+/// - `freq_correction`: set the frequency correction in ppm
+/// - `agc`: enable or disable the RTL2832 digital AGC with a [`Pmt::Bool`]
+///
+/// Numeric parameters accept any of [`Pmt::F32`], [`Pmt::F64`], [`Pmt::U32`], [`Pmt::U64`]
+/// or [`Pmt::Usize`].
+///
+/// # Usage
+/// ```
+/// use fsdr_blocks::net::RtlTcpSourceBuilder;
+///
+/// let blk = RtlTcpSourceBuilder::new("127.0.0.1:1234")
+///     .frequency(100e6)
+///     .sample_rate(2.048e6)
+///     .gain(20.0)
+///     .build();
+/// ```
+pub struct RtlTcpSource {
+    addr: String,
+    stream: Option<TcpStream>,
+    commands: Vec<(u8, u32)>,
+    bytes: Vec<u8>,
+    filled: usize,
+}
+
+impl RtlTcpSource {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(addr: &str) -> Block {
+        RtlTcpSourceBuilder::new(addr).build()
+    }
+
+    fn with_commands(addr: &str, commands: Vec<(u8, u32)>) -> Block {
+        Block::new(
+            BlockMetaBuilder::new("RtlTcpSource").blocking().build(),
+            StreamIoBuilder::new()
+                .add_output::<Complex32>("out")
+                .build(),
+            MessageIoBuilder::<Self>::new()
+                .add_input("freq", Self::freq)
+                .add_input("sample_rate", Self::sample_rate)
+                .add_input("gain", Self::gain)
+                .add_input("freq_correction", Self::freq_correction)
+                .add_input("agc", Self::agc)
+                .build(),
+            RtlTcpSource {
+                addr: addr.to_string(),
+                stream: None,
+                commands,
+                bytes: vec![0; 64 * 1024],
+                filled: 0,
+            },
+        )
+    }
+
+    /// Send a command right away if connected, or once connected otherwise.
+    fn command(&mut self, cmd: u8, param: u32) -> Result<Pmt> {
+        match self.stream.as_mut() {
+            Some(stream) => {
+                let mut buf = [0u8; 5];
+                buf[0] = cmd;
+                buf[1..].copy_from_slice(&param.to_be_bytes());
+                stream.write_all(&buf)?;
+            }
+            None => self.commands.push((cmd, param)),
+        }
+        Ok(Pmt::Ok)
+    }
+
+    #[message_handler]
+    async fn freq(
+        &mut self,
+        _io: &mut WorkIo,
+        _mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        match pmt_to_f64(&p) {
+            Some(f) => self.command(CMD_SET_FREQUENCY, f as u32),
+            None => Ok(Pmt::InvalidValue),
+        }
+    }
+
+    #[message_handler]
+    async fn sample_rate(
+        &mut self,
+        _io: &mut WorkIo,
+        _mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        match pmt_to_f64(&p) {
+            Some(r) => self.command(CMD_SET_SAMPLE_RATE, r as u32),
+            None => Ok(Pmt::InvalidValue),
+        }
+    }
+
+    #[message_handler]
+    async fn gain(
+        &mut self,
+        _io: &mut WorkIo,
+        _mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        if let Pmt::Null = p {
+            return self.command(CMD_SET_GAIN_MODE, 0);
+        }
+        match pmt_to_f64(&p) {
+            Some(g) => {
+                self.command(CMD_SET_GAIN_MODE, 1)?;
+                // rtl_tcp expects tenths of dB
+                self.command(CMD_SET_GAIN, (g * 10.0).round() as i32 as u32)
+            }
+            None => Ok(Pmt::InvalidValue),
+        }
+    }
+
+    #[message_handler]
+    async fn freq_correction(
+        &mut self,
+        _io: &mut WorkIo,
+        _mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        match pmt_to_f64(&p) {
+            Some(ppm) => self.command(CMD_SET_FREQ_CORRECTION, ppm.round() as i32 as u32),
+            None => Ok(Pmt::InvalidValue),
+        }
+    }
+
+    #[message_handler]
+    async fn agc(
+        &mut self,
+        _io: &mut WorkIo,
+        _mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        if let Pmt::Bool(b) = p {
+            self.command(CMD_SET_AGC_MODE, b as u32)
+        } else {
+            Ok(Pmt::InvalidValue)
+        }
+    }
+}
+
+#[doc(hidden)]
+#[async_trait]
+impl Kernel for RtlTcpSource {
+    async fn init(
+        &mut self,
+        _sio: &mut StreamIo,
+        _mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let mut stream = TcpStream::connect(&self.addr)?;
+        stream.set_nodelay(true)?;
+
+        // dongle info: magic, tuner type, number of gains
+        let mut header = [0u8; 12];
+        stream.read_exact(&mut header)?;
+        if &header[..4] == b"RTL0" {
+            let tuner = u32::from_be_bytes(header[4..8].try_into().unwrap());
+            let gains = u32::from_be_bytes(header[8..12].try_into().unwrap());
+            info!(
+                "RtlTcpSource: connected to {}, tuner type {}, {} gain steps",
+                self.addr, tuner, gains
+            );
+        } else {
+            warn!("RtlTcpSource: unexpected header from {}", self.addr);
+        }
+        stream.set_read_timeout(Some(RECV_TIMEOUT))?;
+        self.stream = Some(stream);
+
+        for (cmd, param) in std::mem::take(&mut self.commands) {
+            self.command(cmd, param)?;
+        }
+        Ok(())
+    }
+
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        sio: &mut StreamIo,
+        _mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let o = sio.output(0).slice::<Complex32>();
+        if o.is_empty() {
+            return Ok(());
+        }
+
+        let n_bytes = (o.len() * 2).min(self.bytes.len());
+        let stream = self.stream.as_mut().unwrap();
+        match stream.read(&mut self.bytes[self.filled..n_bytes]) {
+            Ok(0) => {
+                info!("RtlTcpSource: {} closed the connection", self.addr);
+                io.finished = true;
+                return Ok(());
+            }
+            Ok(n) => {
+                self.filled += n;
+                let produced = self.filled / 2;
+                let bytes = &self.bytes[..produced * 2];
+                for (v, iq) in o[..produced].iter_mut().zip(bytes.chunks_exact(2)) {
+                    *v = Complex32::new(
+                        (iq[0] as f32 - 127.5) / 127.5,
+                        (iq[1] as f32 - 127.5) / 127.5,
+                    );
+                }
+                self.bytes.copy_within(produced * 2..self.filled, 0);
+                self.filled -= produced * 2;
+                sio.output(0).produce(produced);
+            }
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    ErrorKind::WouldBlock | ErrorKind::TimedOut | ErrorKind::Interrupted
+                ) => {}
+            Err(e) => return Err(e.into()),
+        }
+
+        io.call_again = true;
+        Ok(())
+    }
+}
+
+/// Build an [`RtlTcpSource`] with its initial settings.
+///
+/// Settings left unset keep the server's defaults.
+pub struct RtlTcpSourceBuilder {
+    addr: String,
+    commands: Vec<(u8, u32)>,
+}
+
+impl RtlTcpSourceBuilder {
+    pub fn new(addr: &str) -> RtlTcpSourceBuilder {
+        RtlTcpSourceBuilder {
+            addr: addr.to_string(),
+            commands: Vec::new(),
+        }
+    }
+
+    /// Center frequency in Hz
+    pub fn frequency(mut self, frequency: f64) -> RtlTcpSourceBuilder {
+        self.commands.push((CMD_SET_FREQUENCY, frequency as u32));
+        self
+    }
+
+    /// Sample rate in Hz
+    pub fn sample_rate(mut self, sample_rate: f64) -> RtlTcpSourceBuilder {
+        self.commands
+            .push((CMD_SET_SAMPLE_RATE, sample_rate as u32));
+        self
+    }
+
+    /// Manual gain in dB
+    pub fn gain(mut self, gain: f64) -> RtlTcpSourceBuilder {
+        self.commands.push((CMD_SET_GAIN_MODE, 1));
+        self.commands
+            .push((CMD_SET_GAIN, (gain * 10.0).round() as i32 as u32));
+        self
+    }
+
+    /// Frequency correction in ppm
+    pub fn freq_correction(mut self, ppm: i32) -> RtlTcpSourceBuilder {
+        self.commands.push((CMD_SET_FREQ_CORRECTION, ppm as u32));
+        self
+    }
+
+    /// Enable the RTL2832 digital AGC
+    pub fn agc(mut self, agc: bool) -> RtlTcpSourceBuilder {
+        self.commands.push((CMD_SET_AGC_MODE, agc as u32));
+        self
+    }
+
+    pub fn build(self) -> Block {
+        RtlTcpSource::with_commands(&self.addr, self.commands)
+    }
+}
@@ -0,0 +1,127 @@
+use crate::net::{UdpSink, UdpSource};
+use crate::stdinout::{BytesOrder, BytesOrdered};
+use core::marker::PhantomData;
+use futuresdr::runtime::Block;
+
+/// Default UDP payload size in bytes, excluding the optional sequence header.
+/// Fits in a standard 1500 bytes Ethernet MTU, as in GNU Radio.
+pub const DEFAULT_PAYLOAD_SIZE: usize = 1472;
+
+/// Size in bytes of the optional sequence header.
+///
+/// Compatible with the "64-bit sequence number" header of GNU Radio's UDP blocks:
+/// a little endian `u64` incremented for each datagram.
+pub const SEQUENCE_HEADER_SIZE: usize = 8;
+
+enum UdpDirection {
+    Source,
+    Sink,
+}
+
+/// Build blocks exchanging samples through UDP datagrams, compatible with
+/// GNU Radio's UDP Source and UDP Sink.
+/// It also takes care of endianness.
+///
+/// # Usage
+///
+/// Receive `Complex32` on port 2000, with sequence headers to detect lost datagrams:
+/// ```
+/// # use fsdr_blocks::net::UdpBuilder;
+/// # use futuresdr::num_complex::Complex32;
+/// let blk = UdpBuilder::<Complex32>::source("0.0.0.0:2000")
+///     .sequence_header(true)
+///     .build();
+/// ```
+///
+/// Send big endian `i16` to a remote host:
+/// ```
+/// # use fsdr_blocks::net::UdpBuilder;
+/// let blk = UdpBuilder::<i16>::sink("192.168.1.10:2000")
+///     .as_be()
+///     .payload_size(1024)
+///     .build();
+/// ```
+pub struct UdpBuilder<A> {
+    direction: UdpDirection,
+    addr: String,
+    marker_type: PhantomData<A>,
+    bytes_order: BytesOrder,
+    payload_size: usize,
+    sequence_header: bool,
+}
+
+impl<A> UdpBuilder<A> {
+    /// Receive datagrams on the local address `addr`
+    pub fn source(addr: &str) -> UdpBuilder<A> {
+        UdpBuilder::<A> {
+            direction: UdpDirection::Source,
+            addr: addr.to_string(),
+            marker_type: PhantomData,
+            bytes_order: BytesOrder::Native,
+            payload_size: DEFAULT_PAYLOAD_SIZE,
+            sequence_header: false,
+        }
+    }
+
+    /// Send datagrams to the remote address `addr`
+    pub fn sink(addr: &str) -> UdpBuilder<A> {
+        UdpBuilder::<A> {
+            direction: UdpDirection::Sink,
+            ..UdpBuilder::source(addr)
+        }
+    }
+
+    pub fn as_ne(self) -> UdpBuilder<A> {
+        UdpBuilder::<A> {
+            bytes_order: BytesOrder::Native,
+            ..self
+        }
+    }
+
+    pub fn as_le(self) -> UdpBuilder<A> {
+        UdpBuilder::<A> {
+            bytes_order: BytesOrder::LittleEndian,
+            ..self
+        }
+    }
+
+    pub fn as_be(self) -> UdpBuilder<A> {
+        UdpBuilder::<A> {
+            bytes_order: BytesOrder::BigEndian,
+            ..self
+        }
+    }
+
+    /// Size in bytes of the samples carried by one datagram, excluding the sequence header.
+    /// Only relevant for sinks, sources accept any size.
+    pub fn payload_size(self, payload_size: usize) -> UdpBuilder<A> {
+        UdpBuilder::<A> {
+            payload_size,
+            ..self
+        }
+    }
+
+    /// Prefix each datagram with a sequence number, see [`SEQUENCE_HEADER_SIZE`].
+    pub fn sequence_header(self, sequence_header: bool) -> UdpBuilder<A> {
+        UdpBuilder::<A> {
+            sequence_header,
+            ..self
+        }
+    }
+}
+
+impl<A: BytesOrdered> UdpBuilder<A> {
+    pub fn build(self) -> Block {
+        match self.direction {
+            UdpDirection::Source => {
+                UdpSource::<A>::new(&self.addr, self.bytes_order, self.sequence_header)
+            }
+            UdpDirection::Sink => UdpSink::<A>::new(
+                &self.addr,
+                self.bytes_order,
+                self.payload_size,
+                self.sequence_header,
+            ),
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use crate::stdinout::{BytesOrder, BytesOrdered};
+use core::marker::PhantomData;
+use futuresdr::log::debug;
+use futuresdr::runtime::Block;
+use futuresdr::runtime::BlockMeta;
+use futuresdr::runtime::BlockMetaBuilder;
+use futuresdr::runtime::Kernel;
+use futuresdr::runtime::MessageIo;
+use futuresdr::runtime::MessageIoBuilder;
+use futuresdr::runtime::Result;
+use futuresdr::runtime::StreamIo;
+use futuresdr::runtime::StreamIoBuilder;
+use futuresdr::runtime::WorkIo;
+use std::io::ErrorKind;
+use std::net::UdpSocket;
+
+/// Send samples to a remote host as UDP datagrams.
+///
+/// Each datagram carries as many whole samples as fit in the payload size,
+/// optionally prefixed by a sequence number.
+/// The last, possibly shorter, datagram is sent at the end of the stream.
+/// The socket is opened when the flowgraph starts.
+///
+/// # Inputs
+///
+/// `in`: samples to send
+///
+/// # Usage
+/// ```
+/// use fsdr_blocks::net::UdpSink;
+/// use fsdr_blocks::stdinout::BytesOrder;
+///
+/// let blk = UdpSink::<f32>::new("127.0.0.1:2000", BytesOrder::LittleEndian, 1472, true);
+/// ```
+pub struct UdpSink<T: BytesOrdered> {
+    addr: String,
+    socket: Option<UdpSocket>,
+    bytes_order: BytesOrder,
+    items_per_datagram: usize,
+    sequence_header: bool,
+    sequence: u64,
+    datagram: Vec<u8>,
+    _type: PhantomData<T>,
+}
+
+impl<T: BytesOrdered> UdpSink<T> {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(
+        addr: &str,
+        bytes_order: BytesOrder,
+        payload_size: usize,
+        sequence_header: bool,
+    ) -> Block {
+        Block::new(
+            BlockMetaBuilder::new("UdpSink").blocking().build(),
+            StreamIoBuilder::new().add_input::<T>("in").build(),
+            MessageIoBuilder::<Self>::new().build(),
+            UdpSink::<T> {
+                addr: addr.to_string(),
+                socket: None,
+                bytes_order,
+                items_per_datagram: (payload_size / T::SIZE).max(1),
+                sequence_header,
+                sequence: 0,
+                datagram: Vec::new(),
+                _type: PhantomData,
+            },
+        )
+    }
+
+    fn send(&mut self, items: &[T]) -> Result<()> {
+        self.datagram.clear();
+        if self.sequence_header {
+            self.datagram
+                .extend_from_slice(&self.sequence.to_le_bytes());
+        }
+        let start = self.datagram.len();
+        self.datagram.resize(start + items.len() * T::SIZE, 0);
+        for (v, dst) in items
+            .iter()
+            .zip(self.datagram[start..].chunks_exact_mut(T::SIZE))
+        {
+            v.write_bytes(self.bytes_order, dst);
+        }
+        self.sequence = self.sequence.wrapping_add(1);
+
+        match self.socket.as_ref().unwrap().send(&self.datagram) {
+            Ok(_) => Ok(()),
+            // nobody listening (yet) on the other side, UDP does not care
+            Err(e) if e.kind() == ErrorKind::ConnectionRefused => {
+                debug!("UdpSink: {} refused datagram", self.addr);
+                Ok(())
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+#[doc(hidden)]
+#[async_trait]
+impl<T: BytesOrdered> Kernel for UdpSink<T> {
+    async fn init(
+        &mut self,
+        _sio: &mut StreamIo,
+        _mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let socket = UdpSocket::bind("0.0.0.0:0")?;
+        socket.connect(&self.addr)?;
+        self.socket = Some(socket);
+        Ok(())
+    }
+
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        sio: &mut StreamIo,
+        _mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let i = sio.input(0).slice::<T>();
+        let finished = sio.input(0).finished();
+
+        let mut consumed = 0;
+        for items in i.chunks(self.items_per_datagram) {
+            if items.len() < self.items_per_datagram && !finished {
+                break;
+            }
+            self.send(items)?;
+            consumed += items.len();
+        }
+        sio.input(0).consume(consumed);
+
+        if finished && consumed == i.len() {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
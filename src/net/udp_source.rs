@@ -0,0 +1,199 @@
+use crate::net::udp::SEQUENCE_HEADER_SIZE;
+use crate::stdinout::{BytesOrder, BytesOrdered};
+use core::marker::PhantomData;
+use futuresdr::log::warn;
+use futuresdr::runtime::Block;
+use futuresdr::runtime::BlockMeta;
+use futuresdr::runtime::BlockMetaBuilder;
+use futuresdr::runtime::Kernel;
+use futuresdr::runtime::MessageIo;
+use futuresdr::runtime::MessageIoBuilder;
+use futuresdr::runtime::Result;
+use futuresdr::runtime::StreamIo;
+use futuresdr::runtime::StreamIoBuilder;
+use futuresdr::runtime::Tag;
+use futuresdr::runtime::WorkIo;
+use std::io::ErrorKind;
+use std::net::UdpSocket;
+use std::time::Duration;
+
+/// Maximum size of an UDP datagram
+const MAX_DATAGRAM_SIZE: usize = 65536;
+
+/// Period at which the socket is polled, so that the block stays responsive
+/// to messages and termination when no datagram is received.
+const RECV_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Largest number of datagrams a late datagram can be behind the expected one,
+/// beyond which the sender is considered restarted
+const REORDER_WINDOW: u64 = 64;
+
+/// Receive samples from UDP datagrams sent to a local address.
+///
+/// With sequence headers, lost datagrams are detected: a warning is logged
+/// and the first sample following the gap is tagged with
+/// `Tag::NamedUsize("udp_lost", n)`, where `n` is the number of missing datagrams.
+/// Late (reordered) or duplicate datagrams, whose sequence number is lower than the
+/// expected one, are logged and dropped.
+/// A sequence number of 0, or one more than 64 datagrams behind the expected one, is
+/// taken as a restart of the sender instead: the sequence is resynchronized, and the
+/// first sample of the datagram is tagged with `Tag::NamedUsize("udp_restart", s)`,
+/// where `s` is its sequence number.
+/// Trailing bytes not forming a whole sample are dropped.
+/// The socket is bound when the flowgraph starts and the block never finishes on its own.
+///
+/// # Outputs
+///
+/// `out`: received samples
+///
+/// # Usage
+/// ```
+/// use fsdr_blocks::net::UdpSource;
+/// use fsdr_blocks::stdinout::BytesOrder;
+///
+/// let blk = UdpSource::<f32>::new("0.0.0.0:2000", BytesOrder::LittleEndian, true);
+/// ```
+pub struct UdpSource<T: BytesOrdered> {
+    addr: String,
+    socket: Option<UdpSocket>,
+    bytes_order: BytesOrder,
+    sequence_header: bool,
+    next_sequence: Option<u64>,
+    lost: usize,
+    restart: Option<usize>,
+    datagram: Vec<u8>,
+    pending: Vec<T>,
+    pending_offset: usize,
+    _type: PhantomData<T>,
+}
+
+impl<T: BytesOrdered> UdpSource<T> {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(addr: &str, bytes_order: BytesOrder, sequence_header: bool) -> Block {
+        Block::new(
+            BlockMetaBuilder::new("UdpSource").blocking().build(),
+            StreamIoBuilder::new().add_output::<T>("out").build(),
+            MessageIoBuilder::<Self>::new().build(),
+            UdpSource::<T> {
+                addr: addr.to_string(),
+                socket: None,
+                bytes_order,
+                sequence_header,
+                next_sequence: None,
+                lost: 0,
+                restart: None,
+                datagram: vec![0; MAX_DATAGRAM_SIZE],
+                pending: Vec::new(),
+                pending_offset: 0,
+                _type: PhantomData,
+            },
+        )
+    }
+
+    /// Wait for the next datagram and deserialize it into `pending`.
+    fn receive(&mut self) -> Result<()> {
+        let n = match self.socket.as_ref().unwrap().recv(&mut self.datagram) {
+            Ok(n) => n,
+            Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                return Ok(())
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut payload = &self.datagram[..n];
+        if self.sequence_header {
+            if n < SEQUENCE_HEADER_SIZE {
+                warn!("UdpSource: datagram too short for a sequence header");
+                return Ok(());
+            }
+            let (header, rest) = payload.split_at(SEQUENCE_HEADER_SIZE);
+            let sequence = u64::from_le_bytes(header.try_into().unwrap());
+            if let Some(expected) = self.next_sequence {
+                if sequence < expected && (sequence == 0 || expected - sequence > REORDER_WINDOW) {
+                    warn!(
+                        "UdpSource: sender restarted at datagram {} on {}, expecting {}",
+                        sequence, self.addr, expected
+                    );
+                    self.restart = Some(sequence as usize);
+                } else if sequence < expected {
+                    warn!(
+                        "UdpSource: dropping late or duplicate datagram {} on {}, expecting {}",
+                        sequence, self.addr, expected
+                    );
+                    return Ok(());
+                } else if sequence > expected {
+                    let lost = (sequence - expected) as usize;
+                    warn!("UdpSource: {} datagram(s) lost on {}", lost, self.addr);
+                    self.lost += lost;
+                }
+            }
+            self.next_sequence = Some(sequence.wrapping_add(1));
+            payload = rest;
+        }
+
+        let trailing = payload.len() % T::SIZE;
+        if trailing != 0 {
+            warn!("UdpSource: dropping {} trailing bytes", trailing);
+        }
+        self.pending.clear();
+        self.pending.extend(
+            payload
+                .chunks_exact(T::SIZE)
+                .map(|b| T::read_bytes(self.bytes_order, b)),
+        );
+        self.pending_offset = 0;
+        Ok(())
+    }
+}
+
+#[doc(hidden)]
+#[async_trait]
+impl<T: BytesOrdered> Kernel for UdpSource<T> {
+    async fn init(
+        &mut self,
+        _sio: &mut StreamIo,
+        _mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let socket = UdpSocket::bind(&self.addr)?;
+        socket.set_read_timeout(Some(RECV_TIMEOUT))?;
+        self.socket = Some(socket);
+        Ok(())
+    }
+
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        sio: &mut StreamIo,
+        _mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        if self.pending_offset == self.pending.len() {
+            self.receive()?;
+        }
+
+        let o = sio.output(0).slice::<T>();
+        let pending = &self.pending[self.pending_offset..];
+        let n = o.len().min(pending.len());
+        if n > 0 {
+            o[..n].copy_from_slice(&pending[..n]);
+            if self.pending_offset == 0 {
+                if self.lost > 0 {
+                    sio.output(0)
+                        .add_tag(0, Tag::NamedUsize("udp_lost".to_string(), self.lost));
+                    self.lost = 0;
+                }
+                if let Some(sequence) = self.restart.take() {
+                    sio.output(0)
+                        .add_tag(0, Tag::NamedUsize("udp_restart".to_string(), sequence));
+                }
+            }
+            self.pending_offset += n;
+            sio.output(0).produce(n);
+        }
+
+        // with a full output buffer, wait for downstream to consume
+        io.call_again = !o.is_empty();
+        Ok(())
+    }
+}
@@ -0,0 +1,299 @@
+//! Compact binary representation of [`Pmt`] in [CBOR](https://www.rfc-editor.org/rfc/rfc8949),
+//! preserving the variant of every value.
+//!
+//! Each [`Pmt`] is a CBOR map with a single entry, from the name of the variant
+//! (as in [`json`](super::json)) to its content:
+//!
+//! | [`Pmt`]                                 | content                                 |
+//! |-----------------------------------------|-----------------------------------------|
+//! | `Ok`, `InvalidValue`, `Null`, `Finished` | `null`                                 |
+//! | `Bool`                                  | `true`, `false`                         |
+//! | `String`                                | text string                             |
+//! | `Usize`, `U32`, `U64`                   | unsigned integer                        |
+//! | `F32`, `F64`                            | single and double precision float       |
+//! | `VecF32`, `VecU64`, `VecCF32`           | byte string of the little endian values (`re`, `im` for complex) |
+//! | `Blob`                                  | byte string                             |
+//! | `VecPmt`                                | array                                   |
+//! | `MapStrPmt`                             | map from text strings, sorted by key    |
+//!
+//! Integers use the shortest encoding and only definite lengths are used, so that equal
+//! values always have the same encoding. `Pmt::Any` cannot be encoded.
+
+use super::error::{Error, Result};
+use futuresdr::num_complex::Complex32;
+use futuresdr::runtime::Pmt;
+use std::collections::HashMap;
+
+const MAJOR_UNSIGNED: u8 = 0;
+const MAJOR_BYTES: u8 = 2;
+const MAJOR_TEXT: u8 = 3;
+const MAJOR_ARRAY: u8 = 4;
+const MAJOR_MAP: u8 = 5;
+const MAJOR_SIMPLE: u8 = 7;
+
+const SIMPLE_FALSE: u8 = 20;
+const SIMPLE_TRUE: u8 = 21;
+const SIMPLE_NULL: u8 = 22;
+const SIMPLE_F32: u8 = 26;
+const SIMPLE_F64: u8 = 27;
+
+/// Append the CBOR encoding of `value` to `out`.
+pub fn to_cbor_bytes(value: &Pmt, out: &mut Vec<u8>) -> Result<()> {
+    let variant = match value {
+        Pmt::Ok => "Ok",
+        Pmt::InvalidValue => "InvalidValue",
+        Pmt::Null => "Null",
+        Pmt::Finished => "Finished",
+        Pmt::Bool(_) => "Bool",
+        Pmt::String(_) => "String",
+        Pmt::Usize(_) => "Usize",
+        Pmt::U32(_) => "U32",
+        Pmt::U64(_) => "U64",
+        Pmt::F32(_) => "F32",
+        Pmt::F64(_) => "F64",
+        Pmt::VecF32(_) => "VecF32",
+        Pmt::VecU64(_) => "VecU64",
+        Pmt::VecCF32(_) => "VecCF32",
+        Pmt::Blob(_) => "Blob",
+        Pmt::VecPmt(_) => "VecPmt",
+        Pmt::MapStrPmt(_) => "MapStrPmt",
+        p => {
+            return Err(Error::Message(format!(
+                "{:?} has no CBOR representation",
+                p
+            )))
+        }
+    };
+    write_head(MAJOR_MAP, 1, out);
+    write_text(variant, out);
+
+    match value {
+        Pmt::Bool(false) => out.push(MAJOR_SIMPLE << 5 | SIMPLE_FALSE),
+        Pmt::Bool(true) => out.push(MAJOR_SIMPLE << 5 | SIMPLE_TRUE),
+        Pmt::String(s) => write_text(s, out),
+        Pmt::Usize(v) => write_head(MAJOR_UNSIGNED, *v as u64, out),
+        Pmt::U32(v) => write_head(MAJOR_UNSIGNED, *v as u64, out),
+        Pmt::U64(v) => write_head(MAJOR_UNSIGNED, *v, out),
+        Pmt::F32(v) => {
+            out.push(MAJOR_SIMPLE << 5 | SIMPLE_F32);
+            out.extend_from_slice(&v.to_be_bytes());
+        }
+        Pmt::F64(v) => {
+            out.push(MAJOR_SIMPLE << 5 | SIMPLE_F64);
+            out.extend_from_slice(&v.to_be_bytes());
+        }
+        Pmt::VecF32(v) => {
+            write_head(MAJOR_BYTES, (v.len() * 4) as u64, out);
+            v.iter()
+                .for_each(|x| out.extend_from_slice(&x.to_le_bytes()));
+        }
+        Pmt::VecU64(v) => {
+            write_head(MAJOR_BYTES, (v.len() * 8) as u64, out);
+            v.iter()
+                .for_each(|x| out.extend_from_slice(&x.to_le_bytes()));
+        }
+        Pmt::VecCF32(v) => {
+            write_head(MAJOR_BYTES, (v.len() * 8) as u64, out);
+            v.iter().for_each(|x| {
+                out.extend_from_slice(&x.re.to_le_bytes());
+                out.extend_from_slice(&x.im.to_le_bytes());
+            });
+        }
+        Pmt::Blob(v) => {
+            write_head(MAJOR_BYTES, v.len() as u64, out);
+            out.extend_from_slice(v);
+        }
+        Pmt::VecPmt(v) => {
+            write_head(MAJOR_ARRAY, v.len() as u64, out);
+            for p in v {
+                to_cbor_bytes(p, out)?;
+            }
+        }
+        Pmt::MapStrPmt(m) => {
+            let mut entries: Vec<(&String, &Pmt)> = m.iter().collect();
+            entries.sort_unstable_by_key(|(k, _)| *k);
+            write_head(MAJOR_MAP, entries.len() as u64, out);
+            for (k, v) in entries {
+                write_text(k, out);
+                to_cbor_bytes(v, out)?;
+            }
+        }
+        _ => out.push(MAJOR_SIMPLE << 5 | SIMPLE_NULL),
+    }
+    Ok(())
+}
+
+/// Decode one [`Pmt`] from the beginning of `input`, advancing it past the encoded value.
+pub fn from_cbor_bytes(input: &mut &[u8]) -> Result<Pmt> {
+    if read_head(input, MAJOR_MAP)? != 1 {
+        return Err(malformed("expected a map with a single entry"));
+    }
+    let variant = read_text(input)?;
+    Ok(match variant.as_str() {
+        "Ok" => read_null(input, Pmt::Ok)?,
+        "InvalidValue" => read_null(input, Pmt::InvalidValue)?,
+        "Null" => read_null(input, Pmt::Null)?,
+        "Finished" => read_null(input, Pmt::Finished)?,
+        "Bool" => match take::<1>(input)?[0] {
+            b if b == MAJOR_SIMPLE << 5 | SIMPLE_FALSE => Pmt::Bool(false),
+            b if b == MAJOR_SIMPLE << 5 | SIMPLE_TRUE => Pmt::Bool(true),
+            _ => return Err(malformed("expected a boolean")),
+        },
+        "String" => Pmt::String(read_text(input)?),
+        "Usize" => Pmt::Usize(
+            usize::try_from(read_head(input, MAJOR_UNSIGNED)?)
+                .map_err(|_| malformed("usize out of range"))?,
+        ),
+        "U32" => Pmt::U32(
+            u32::try_from(read_head(input, MAJOR_UNSIGNED)?)
+                .map_err(|_| malformed("u32 out of range"))?,
+        ),
+        "U64" => Pmt::U64(read_head(input, MAJOR_UNSIGNED)?),
+        "F32" => {
+            if take::<1>(input)?[0] != MAJOR_SIMPLE << 5 | SIMPLE_F32 {
+                return Err(malformed("expected a single precision float"));
+            }
+            Pmt::F32(f32::from_be_bytes(take(input)?))
+        }
+        "F64" => {
+            if take::<1>(input)?[0] != MAJOR_SIMPLE << 5 | SIMPLE_F64 {
+                return Err(malformed("expected a double precision float"));
+            }
+            Pmt::F64(f64::from_be_bytes(take(input)?))
+        }
+        "VecF32" => Pmt::VecF32(
+            read_bytes(input, 4)?
+                .chunks_exact(4)
+                .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                .collect(),
+        ),
+        "VecU64" => Pmt::VecU64(
+            read_bytes(input, 8)?
+                .chunks_exact(8)
+                .map(|b| u64::from_le_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]))
+                .collect(),
+        ),
+        "VecCF32" => Pmt::VecCF32(
+            read_bytes(input, 8)?
+                .chunks_exact(8)
+                .map(|b| {
+                    Complex32::new(
+                        f32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+                        f32::from_le_bytes([b[4], b[5], b[6], b[7]]),
+                    )
+                })
+                .collect(),
+        ),
+        "Blob" => Pmt::Blob(read_bytes(input, 1)?.to_vec()),
+        "VecPmt" => {
+            let len = read_head(input, MAJOR_ARRAY)?;
+            // every item takes at least one byte
+            let mut v = Vec::with_capacity((len as usize).min(input.len()));
+            for _ in 0..len {
+                v.push(from_cbor_bytes(input)?);
+            }
+            Pmt::VecPmt(v)
+        }
+        "MapStrPmt" => {
+            let len = read_head(input, MAJOR_MAP)?;
+            let mut m = HashMap::new();
+            for _ in 0..len {
+                let key = read_text(input)?;
+                let value = from_cbor_bytes(input)?;
+                m.insert(key, value);
+            }
+            Pmt::MapStrPmt(m)
+        }
+        v => return Err(Error::Message(format!("unknown Pmt variant {}", v))),
+    })
+}
+
+fn malformed(msg: &str) -> Error {
+    Error::Message(format!("malformed Pmt CBOR: {}", msg))
+}
+
+fn write_head(major: u8, arg: u64, out: &mut Vec<u8>) {
+    let major = major << 5;
+    if arg < 24 {
+        out.push(major | arg as u8);
+    } else if let Ok(arg) = u8::try_from(arg) {
+        out.push(major | 24);
+        out.push(arg);
+    } else if let Ok(arg) = u16::try_from(arg) {
+        out.push(major | 25);
+        out.extend_from_slice(&arg.to_be_bytes());
+    } else if let Ok(arg) = u32::try_from(arg) {
+        out.push(major | 26);
+        out.extend_from_slice(&arg.to_be_bytes());
+    } else {
+        out.push(major | 27);
+        out.extend_from_slice(&arg.to_be_bytes());
+    }
+}
+
+fn write_text(s: &str, out: &mut Vec<u8>) {
+    write_head(MAJOR_TEXT, s.len() as u64, out);
+    out.extend_from_slice(s.as_bytes());
+}
+
+fn take<const N: usize>(input: &mut &[u8]) -> Result<[u8; N]> {
+    let head = take_slice(input, N)?;
+    let mut bytes = [0; N];
+    bytes.copy_from_slice(head);
+    Ok(bytes)
+}
+
+fn take_slice<'a>(input: &mut &'a [u8], n: usize) -> Result<&'a [u8]> {
+    if input.len() < n {
+        return Err(Error::Eof);
+    }
+    let (head, tail) = input.split_at(n);
+    *input = tail;
+    Ok(head)
+}
+
+/// Read the head of a data item of the `expected` major type, returning its argument.
+fn read_head(input: &mut &[u8], expected: u8) -> Result<u64> {
+    let initial = take::<1>(input)?[0];
+    if initial >> 5 != expected {
+        return Err(Error::Message(format!(
+            "malformed Pmt CBOR: expected major type {}, got {}",
+            expected,
+            initial >> 5
+        )));
+    }
+    match initial & 0x1f {
+        arg @ 0..=23 => Ok(arg as u64),
+        24 => Ok(take::<1>(input)?[0] as u64),
+        25 => Ok(u16::from_be_bytes(take(input)?) as u64),
+        26 => Ok(u32::from_be_bytes(take(input)?) as u64),
+        27 => Ok(u64::from_be_bytes(take(input)?)),
+        _ => Err(malformed("unsupported argument encoding")),
+    }
+}
+
+fn read_len(input: &mut &[u8], major: u8) -> Result<usize> {
+    usize::try_from(read_head(input, major)?).map_err(|_| Error::Eof)
+}
+
+fn read_text(input: &mut &[u8]) -> Result<String> {
+    let len = read_len(input, MAJOR_TEXT)?;
+    let bytes = take_slice(input, len)?;
+    String::from_utf8(bytes.to_vec()).map_err(|e| Error::Message(e.to_string()))
+}
+
+/// Read a byte string made of items of `size` bytes.
+fn read_bytes<'a>(input: &mut &'a [u8], size: usize) -> Result<&'a [u8]> {
+    let len = read_len(input, MAJOR_BYTES)?;
+    if len % size != 0 {
+        return Err(malformed("truncated vector"));
+    }
+    take_slice(input, len)
+}
+
+fn read_null(input: &mut &[u8], value: Pmt) -> Result<Pmt> {
+    if take::<1>(input)?[0] != MAJOR_SIMPLE << 5 | SIMPLE_NULL {
+        return Err(malformed("expected null"));
+    }
+    Ok(value)
+}
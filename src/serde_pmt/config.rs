@@ -0,0 +1,166 @@
+//! Typed configuration of blocks through message handlers.
+//!
+//! A block implementing [`Configurable`] declares its parameters as a serde struct and gets
+//! generic message handlers for them, instead of matching [`Pmt`] variants by hand:
+//!
+//! - [`set_config`] takes a complete configuration, or a [`Pmt::MapStrPmt`] with only the
+//!   fields to change,
+//! - [`set_config_field`] builds a handler changing a single field,
+//! - [`get_config`] returns the current configuration.
+//!
+//! Setters reply [`Pmt::Ok`] or a [`ConfigError`] converted with [`to_pmt`], which can be
+//! read back with [`from_pmt`](super::from_pmt).
+//!
+//! # Usage
+//! ```
+//! use fsdr_blocks::serde_pmt::config::{get_config, set_config, set_config_field, Configurable};
+//! use futuresdr::runtime::{Kernel, MessageIoBuilder};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct GainConfig {
+//!     gain: f32,
+//!     enabled: bool,
+//! }
+//!
+//! struct Gain {
+//!     gain: f32,
+//!     enabled: bool,
+//! }
+//!
+//! impl Kernel for Gain {}
+//!
+//! impl Configurable for Gain {
+//!     type Config = GainConfig;
+//!
+//!     fn config(&self) -> GainConfig {
+//!         GainConfig { gain: self.gain, enabled: self.enabled }
+//!     }
+//!
+//!     fn set_config(&mut self, config: GainConfig) -> Result<(), String> {
+//!         if config.gain < 0.0 {
+//!             return Err("gain must be positive".to_string());
+//!         }
+//!         self.gain = config.gain;
+//!         self.enabled = config.enabled;
+//!         Ok(())
+//!     }
+//! }
+//!
+//! let mio = MessageIoBuilder::<Gain>::new()
+//!     .add_input("config", set_config)
+//!     .add_input("get_config", get_config)
+//!     .add_input("gain", set_config_field("gain"))
+//!     .build();
+//! ```
+
+use super::{from_pmt, to_pmt};
+use futuresdr::log::warn;
+use futuresdr::runtime::BlockMeta;
+use futuresdr::runtime::MessageIo;
+use futuresdr::runtime::Pmt;
+use futuresdr::runtime::Result;
+use futuresdr::runtime::WorkIo;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+
+/// A block whose parameters are described by a serde struct.
+pub trait Configurable: Send + Sized + 'static {
+    type Config: Serialize + DeserializeOwned;
+
+    /// Current configuration
+    fn config(&self) -> Self::Config;
+
+    /// Apply a new configuration, or explain why it is rejected
+    fn set_config(&mut self, config: Self::Config) -> std::result::Result<(), String>;
+}
+
+/// Reply of the setters when a configuration cannot be applied
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ConfigError {
+    /// The message does not describe a configuration
+    Invalid(String),
+    /// The message sets a field that does not exist
+    UnknownField(String),
+    /// The block rejected the configuration
+    Rejected(String),
+}
+
+type HandlerFuture<'a> = Pin<Box<dyn Future<Output = Result<Pmt>> + Send + 'a>>;
+
+/// Apply a complete or partial configuration to `block`.
+///
+/// When both the configuration and `p` are maps, the fields of `p` replace those of the
+/// current configuration. Nested structs are replaced as a whole.
+pub fn update<K: Configurable>(block: &mut K, p: Pmt) -> std::result::Result<(), ConfigError> {
+    let config = match (to_pmt(&block.config()), p) {
+        (Ok(Pmt::MapStrPmt(mut current)), Pmt::MapStrPmt(changes)) => {
+            for (field, value) in changes {
+                match current.get_mut(&field) {
+                    Some(v) => *v = value,
+                    None => return Err(ConfigError::UnknownField(field)),
+                }
+            }
+            from_pmt(Pmt::MapStrPmt(current))
+        }
+        (_, p) => from_pmt(p),
+    }
+    .map_err(|e| ConfigError::Invalid(e.to_string()))?;
+    block.set_config(config).map_err(ConfigError::Rejected)
+}
+
+fn reply(result: std::result::Result<(), ConfigError>) -> Pmt {
+    match result {
+        Ok(()) => Pmt::Ok,
+        Err(e) => {
+            warn!("configuration not applied: {:?}", e);
+            to_pmt(&e).unwrap_or(Pmt::InvalidValue)
+        }
+    }
+}
+
+/// Message handler applying a complete or partial configuration, see [`update`].
+pub fn set_config<'a, K: Configurable>(
+    block: &'a mut K,
+    _io: &'a mut WorkIo,
+    _mio: &'a mut MessageIo<K>,
+    _meta: &'a mut BlockMeta,
+    p: Pmt,
+) -> HandlerFuture<'a> {
+    Box::pin(async move { Ok(reply(update(block, p))) })
+}
+
+/// Message handler returning the current configuration.
+pub fn get_config<'a, K: Configurable>(
+    block: &'a mut K,
+    _io: &'a mut WorkIo,
+    _mio: &'a mut MessageIo<K>,
+    _meta: &'a mut BlockMeta,
+    _p: Pmt,
+) -> HandlerFuture<'a> {
+    Box::pin(async move { Ok(to_pmt(&block.config())?) })
+}
+
+/// Build a message handler setting a single `field` of the configuration.
+pub fn set_config_field<K: Configurable>(
+    field: &'static str,
+) -> impl for<'a> Fn(
+    &'a mut K,
+    &'a mut WorkIo,
+    &'a mut MessageIo<K>,
+    &'a mut BlockMeta,
+    Pmt,
+) -> HandlerFuture<'a>
+       + Send
+       + Sync
+       + 'static {
+    move |block, _io, _mio, _meta, p| {
+        Box::pin(async move {
+            let mut changes = HashMap::new();
+            changes.insert(field.to_string(), p);
+            Ok(reply(update(block, Pmt::MapStrPmt(changes))))
+        })
+    }
+}
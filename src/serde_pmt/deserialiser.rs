@@ -3,7 +3,10 @@ use std::{borrow::Cow, collections::HashMap};
 use super::error::{Error, Result};
 use futuresdr::runtime::Pmt;
 use serde::{
-    de::{DeserializeSeed, Expected, MapAccess, Unexpected, Visitor},
+    de::{
+        DeserializeSeed, EnumAccess, Expected, MapAccess, SeqAccess, Unexpected, VariantAccess,
+        Visitor,
+    },
     forward_to_deserialize_any, Deserializer,
 };
 
@@ -25,17 +28,96 @@ impl PmtDist {
     }
 
     #[cold]
-    fn unexpected(&self) -> Unexpected {
+    fn invalid_value<E>(&self, exp: &dyn Expected) -> E
+    where
+        E: serde::de::Error,
+    {
+        serde::de::Error::invalid_value(self.unexpected(), exp)
+    }
+
+    #[cold]
+    fn unexpected(&self) -> Unexpected<'_> {
         match &self.0 {
             Pmt::Null => Unexpected::Unit,
             Pmt::Bool(b) => Unexpected::Bool(*b),
-            //Pmt::U32(n) => n.unexpected(),
+            Pmt::U32(n) => Unexpected::Unsigned(*n as u64),
+            Pmt::U64(n) => Unexpected::Unsigned(*n),
+            Pmt::Usize(n) => Unexpected::Unsigned(*n as u64),
+            Pmt::F32(n) => Unexpected::Float(*n as f64),
+            Pmt::F64(n) => Unexpected::Float(*n),
             Pmt::String(s) => Unexpected::Str(s),
-            Pmt::VecF32(_) => Unexpected::Seq,
+            Pmt::Blob(b) => Unexpected::Bytes(b),
+            Pmt::VecF32(_) | Pmt::VecU64(_) | Pmt::VecCF32(_) | Pmt::VecPmt(_) => Unexpected::Seq,
             Pmt::MapStrPmt(_) => Unexpected::Map,
-            _ => Unexpected::Unit, //TODO
+            Pmt::Ok => Unexpected::Other("Pmt::Ok"),
+            Pmt::InvalidValue => Unexpected::Other("Pmt::InvalidValue"),
+            Pmt::Finished => Unexpected::Other("Pmt::Finished"),
+            _ => Unexpected::Other("opaque Pmt"),
         }
     }
+
+    /// Coerce any numeric variant into an integer, failing if it does not fit in `T`
+    /// or has a fractional part.
+    fn integer<T>(&self, exp: &dyn Expected) -> Result<T>
+    where
+        T: TryFrom<u64> + TryFrom<i64>,
+    {
+        let v = match &self.0 {
+            Pmt::U32(v) => <T as TryFrom<u64>>::try_from(*v as u64).ok(),
+            Pmt::U64(v) => <T as TryFrom<u64>>::try_from(*v).ok(),
+            Pmt::Usize(v) => <T as TryFrom<u64>>::try_from(*v as u64).ok(),
+            Pmt::F32(v) => float_to_integer(*v as f64),
+            Pmt::F64(v) => float_to_integer(*v),
+            _ => return Err(self.invalid_type(exp)),
+        };
+        v.ok_or_else(|| self.invalid_value(exp))
+    }
+
+    /// Coerce any numeric variant into a float.
+    fn float(&self, exp: &dyn Expected) -> Result<f64> {
+        match &self.0 {
+            Pmt::U32(v) => Ok(*v as f64),
+            Pmt::U64(v) => Ok(*v as f64),
+            Pmt::Usize(v) => Ok(*v as f64),
+            Pmt::F32(v) => Ok(*v as f64),
+            Pmt::F64(v) => Ok(*v),
+            _ => Err(self.invalid_type(exp)),
+        }
+    }
+}
+
+fn float_to_integer<T>(v: f64) -> Option<T>
+where
+    T: TryFrom<u64> + TryFrom<i64>,
+{
+    if !v.is_finite() || v.fract() != 0.0 {
+        None
+    } else if v < 0.0 {
+        // i64::MIN is exactly representable, larger magnitudes do not fit
+        if v >= i64::MIN as f64 {
+            <T as TryFrom<i64>>::try_from(v as i64).ok()
+        } else {
+            None
+        }
+    } else if v < u64::MAX as f64 {
+        <T as TryFrom<u64>>::try_from(v as u64).ok()
+    } else {
+        None
+    }
+}
+
+macro_rules! deserialize_integer {
+    ($($method:ident => $visit:ident: $ty:ty),* $(,)?) => {
+        $(
+            fn $method<V>(self, visitor: V) -> std::prelude::v1::Result<V::Value, Self::Error>
+            where
+                V: serde::de::Visitor<'de>,
+            {
+                let v: $ty = self.integer(&visitor)?;
+                visitor.$visit(v)
+            }
+        )*
+    };
 }
 
 impl<'de> serde::Deserializer<'de> for PmtDist {
@@ -54,7 +136,9 @@ impl<'de> serde::Deserializer<'de> for PmtDist {
             Pmt::Null => visitor.visit_unit(),
             Pmt::String(v) => visitor.visit_string(v),
             Pmt::Usize(v) => visitor.visit_u64(v as u64),
-            _ => Err(self::Error::Message("Not yet implemented".to_string())),
+            Pmt::Blob(v) => visitor.visit_byte_buf(v),
+            Pmt::MapStrPmt(v) => visit_object(v, visitor),
+            _ => self.deserialize_seq(visitor),
         }
     }
 
@@ -68,94 +152,60 @@ impl<'de> serde::Deserializer<'de> for PmtDist {
         }
     }
 
-    fn deserialize_i8<V>(self, _visitor: V) -> std::prelude::v1::Result<V::Value, Self::Error>
-    where
-        V: serde::de::Visitor<'de>,
-    {
-        todo!()
-    }
-
-    fn deserialize_i16<V>(self, _visitor: V) -> std::prelude::v1::Result<V::Value, Self::Error>
-    where
-        V: serde::de::Visitor<'de>,
-    {
-        todo!()
-    }
-
-    fn deserialize_i32<V>(self, _visitor: V) -> std::prelude::v1::Result<V::Value, Self::Error>
-    where
-        V: serde::de::Visitor<'de>,
-    {
-        todo!()
-    }
-
-    fn deserialize_i64<V>(self, _visitor: V) -> std::prelude::v1::Result<V::Value, Self::Error>
-    where
-        V: serde::de::Visitor<'de>,
-    {
-        todo!()
-    }
-
-    fn deserialize_u8<V>(self, _visitor: V) -> std::prelude::v1::Result<V::Value, Self::Error>
-    where
-        V: serde::de::Visitor<'de>,
-    {
-        todo!()
-    }
-
-    fn deserialize_u16<V>(self, _visitor: V) -> std::prelude::v1::Result<V::Value, Self::Error>
-    where
-        V: serde::de::Visitor<'de>,
-    {
-        todo!()
+    deserialize_integer! {
+        deserialize_i8 => visit_i8: i8,
+        deserialize_i16 => visit_i16: i16,
+        deserialize_i32 => visit_i32: i32,
+        deserialize_i64 => visit_i64: i64,
+        deserialize_i128 => visit_i128: i128,
+        deserialize_u8 => visit_u8: u8,
+        deserialize_u16 => visit_u16: u16,
+        deserialize_u32 => visit_u32: u32,
+        deserialize_u64 => visit_u64: u64,
+        deserialize_u128 => visit_u128: u128,
     }
 
-    fn deserialize_u32<V>(self, visitor: V) -> std::prelude::v1::Result<V::Value, Self::Error>
+    fn deserialize_f32<V>(self, visitor: V) -> std::prelude::v1::Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        match self.0 {
-            Pmt::U32(v) => visitor.visit_u32(v),
-            _ => Err(self.invalid_type(&visitor)),
+        if let Pmt::F32(v) = self.0 {
+            return visitor.visit_f32(v);
         }
-    }
-
-    fn deserialize_u64<V>(self, visitor: V) -> std::prelude::v1::Result<V::Value, Self::Error>
-    where
-        V: serde::de::Visitor<'de>,
-    {
-        match self.0 {
-            Pmt::U64(v) => visitor.visit_u64(v),
-            _ => Err(self.invalid_type(&visitor)),
+        let v = self.float(&visitor)?;
+        if v.is_finite() && v.abs() > f32::MAX as f64 {
+            return Err(self.invalid_value(&visitor));
         }
+        visitor.visit_f32(v as f32)
     }
 
-    fn deserialize_f32<V>(self, _visitor: V) -> std::prelude::v1::Result<V::Value, Self::Error>
-    where
-        V: serde::de::Visitor<'de>,
-    {
-        todo!()
-    }
-
-    fn deserialize_f64<V>(self, _visitor: V) -> std::prelude::v1::Result<V::Value, Self::Error>
+    fn deserialize_f64<V>(self, visitor: V) -> std::prelude::v1::Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        todo!()
+        let v = self.float(&visitor)?;
+        visitor.visit_f64(v)
     }
 
-    fn deserialize_char<V>(self, _visitor: V) -> std::prelude::v1::Result<V::Value, Self::Error>
+    fn deserialize_char<V>(self, visitor: V) -> std::prelude::v1::Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        todo!()
+        if let Pmt::String(s) = &self.0 {
+            let mut chars = s.chars();
+            return match (chars.next(), chars.next()) {
+                (Some(c), None) => visitor.visit_char(c),
+                _ => Err(self.invalid_value(&visitor)),
+            };
+        }
+        Err(self.invalid_type(&visitor))
     }
 
-    fn deserialize_str<V>(self, _visitor: V) -> std::prelude::v1::Result<V::Value, Self::Error>
+    fn deserialize_str<V>(self, visitor: V) -> std::prelude::v1::Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        todo!()
+        self.deserialize_string(visitor)
     }
 
     fn deserialize_string<V>(self, visitor: V) -> std::prelude::v1::Result<V::Value, Self::Error>
@@ -168,18 +218,29 @@ impl<'de> serde::Deserializer<'de> for PmtDist {
         }
     }
 
-    fn deserialize_bytes<V>(self, _visitor: V) -> std::prelude::v1::Result<V::Value, Self::Error>
+    fn deserialize_bytes<V>(self, visitor: V) -> std::prelude::v1::Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        todo!()
+        self.deserialize_byte_buf(visitor)
     }
 
-    fn deserialize_byte_buf<V>(self, _visitor: V) -> std::prelude::v1::Result<V::Value, Self::Error>
+    fn deserialize_byte_buf<V>(self, visitor: V) -> std::prelude::v1::Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        todo!()
+        match self.0 {
+            Pmt::Blob(v) => visitor.visit_byte_buf(v),
+            Pmt::String(v) => visitor.visit_byte_buf(v.into_bytes()),
+            Pmt::VecPmt(v) => {
+                let bytes = v
+                    .into_iter()
+                    .map(|b| PmtDist(b).integer::<u8>(&"a byte"))
+                    .collect::<Result<Vec<u8>>>()?;
+                visitor.visit_byte_buf(bytes)
+            }
+            _ => Err(self.invalid_type(&visitor)),
+        }
     }
 
     fn deserialize_option<V>(self, visitor: V) -> std::prelude::v1::Result<V::Value, Self::Error>
@@ -187,7 +248,7 @@ impl<'de> serde::Deserializer<'de> for PmtDist {
         V: serde::de::Visitor<'de>,
     {
         match self.0 {
-            Pmt::Null => visitor.visit_unit(),
+            Pmt::Null => visitor.visit_none(),
             _ => visitor.visit_some(self),
         }
     }
@@ -205,53 +266,64 @@ impl<'de> serde::Deserializer<'de> for PmtDist {
     fn deserialize_unit_struct<V>(
         self,
         _name: &'static str,
-        _visitor: V,
+        visitor: V,
     ) -> std::prelude::v1::Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        todo!()
+        self.deserialize_unit(visitor)
     }
 
     fn deserialize_newtype_struct<V>(
         self,
         _name: &'static str,
-        _visitor: V,
+        visitor: V,
     ) -> std::prelude::v1::Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        todo!()
+        visitor.visit_newtype_struct(self)
     }
 
-    fn deserialize_seq<V>(self, _visitor: V) -> std::prelude::v1::Result<V::Value, Self::Error>
+    fn deserialize_seq<V>(self, visitor: V) -> std::prelude::v1::Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        todo!()
+        match self.0 {
+            Pmt::VecPmt(v) => visit_array(v.into_iter(), visitor),
+            Pmt::VecF32(v) => visit_array(v.into_iter().map(Pmt::F32), visitor),
+            Pmt::VecU64(v) => visit_array(v.into_iter().map(Pmt::U64), visitor),
+            Pmt::VecCF32(v) => visit_array(
+                v.into_iter()
+                    .map(|c| Pmt::VecPmt(vec![Pmt::F32(c.re), Pmt::F32(c.im)])),
+                visitor,
+            ),
+            Pmt::Blob(v) => visit_array(v.into_iter().map(|b| Pmt::U32(b as u32)), visitor),
+            _ => Err(self.invalid_type(&visitor)),
+        }
     }
 
     fn deserialize_tuple<V>(
         self,
         _len: usize,
-        _visitor: V,
+        visitor: V,
     ) -> std::prelude::v1::Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        todo!()
+        self.deserialize_seq(visitor)
     }
 
     fn deserialize_tuple_struct<V>(
         self,
         _name: &'static str,
         _len: usize,
-        _visitor: V,
+        visitor: V,
     ) -> std::prelude::v1::Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        todo!()
+        self.deserialize_seq(visitor)
     }
 
     fn deserialize_map<V>(self, visitor: V) -> std::prelude::v1::Result<V::Value, Self::Error>
@@ -268,44 +340,197 @@ impl<'de> serde::Deserializer<'de> for PmtDist {
         self,
         _name: &'static str,
         _fields: &'static [&'static str],
-        _visitor: V,
+        visitor: V,
     ) -> std::prelude::v1::Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        todo!()
+        match self.0 {
+            Pmt::MapStrPmt(v) => visit_object(v, visitor),
+            Pmt::VecPmt(v) => visit_array(v.into_iter(), visitor),
+            _ => Err(self.invalid_type(&visitor)),
+        }
     }
 
     fn deserialize_enum<V>(
         self,
         _name: &'static str,
         _variants: &'static [&'static str],
-        _visitor: V,
+        visitor: V,
     ) -> std::prelude::v1::Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        todo!()
+        match self.0 {
+            Pmt::String(variant) => visitor.visit_enum(EnumDeserializer {
+                variant,
+                value: None,
+            }),
+            Pmt::MapStrPmt(v) => {
+                let mut iter = v.into_iter();
+                match (iter.next(), iter.next()) {
+                    (Some((variant, value)), None) => visitor.visit_enum(EnumDeserializer {
+                        variant,
+                        value: Some(value),
+                    }),
+                    _ => Err(serde::de::Error::invalid_value(
+                        Unexpected::Map,
+                        &"map with a single key",
+                    )),
+                }
+            }
+            _ => Err(self.invalid_type(&visitor)),
+        }
     }
 
     fn deserialize_identifier<V>(
         self,
-        _visitor: V,
+        visitor: V,
     ) -> std::prelude::v1::Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        todo!()
+        match self.0 {
+            Pmt::String(v) => visitor.visit_string(v),
+            Pmt::U32(_) | Pmt::U64(_) | Pmt::Usize(_) => self.deserialize_u64(visitor),
+            _ => Err(self.invalid_type(&visitor)),
+        }
     }
 
     fn deserialize_ignored_any<V>(
         self,
-        _visitor: V,
+        visitor: V,
     ) -> std::prelude::v1::Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        todo!()
+        drop(self);
+        visitor.visit_unit()
+    }
+}
+
+fn visit_array<'de, I, V>(array: I, visitor: V) -> Result<V::Value>
+where
+    I: ExactSizeIterator<Item = Pmt>,
+    V: Visitor<'de>,
+{
+    let len = array.len();
+    let mut deserializer = PmtSeqDeserializer { iter: array };
+    let seq = visitor.visit_seq(&mut deserializer)?;
+    let remaining = deserializer.iter.len();
+    if remaining == 0 {
+        Ok(seq)
+    } else {
+        Err(serde::de::Error::invalid_length(
+            len,
+            &"fewer elements in array",
+        ))
+    }
+}
+
+struct PmtSeqDeserializer<I> {
+    iter: I,
+}
+
+impl<'de, I> SeqAccess<'de> for PmtSeqDeserializer<I>
+where
+    I: ExactSizeIterator<Item = Pmt>,
+{
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> core::result::Result<Option<T::Value>, Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self.iter.next() {
+            Some(value) => seed.deserialize(PmtDist(value)).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+struct EnumDeserializer {
+    variant: String,
+    value: Option<Pmt>,
+}
+
+impl<'de> EnumAccess<'de> for EnumDeserializer {
+    type Error = Error;
+    type Variant = VariantDeserializer;
+
+    fn variant_seed<V>(
+        self,
+        seed: V,
+    ) -> core::result::Result<(V::Value, VariantDeserializer), Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let variant = seed.deserialize(MapKeyDeserializer {
+            key: Cow::Owned(self.variant),
+        })?;
+        Ok((variant, VariantDeserializer { value: self.value }))
+    }
+}
+
+struct VariantDeserializer {
+    value: Option<Pmt>,
+}
+
+impl<'de> VariantAccess<'de> for VariantDeserializer {
+    type Error = Error;
+
+    fn unit_variant(self) -> core::result::Result<(), Error> {
+        match self.value {
+            None | Some(Pmt::Null) => Ok(()),
+            Some(value) => Err(PmtDist(value).invalid_type(&"unit variant")),
+        }
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> core::result::Result<T::Value, Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self.value {
+            Some(value) => seed.deserialize(PmtDist(value)),
+            None => Err(serde::de::Error::invalid_type(
+                Unexpected::UnitVariant,
+                &"newtype variant",
+            )),
+        }
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> core::result::Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Some(value) => PmtDist(value).deserialize_seq(visitor),
+            None => Err(serde::de::Error::invalid_type(
+                Unexpected::UnitVariant,
+                &"tuple variant",
+            )),
+        }
+    }
+
+    fn struct_variant<V>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> core::result::Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Some(value) => PmtDist(value).deserialize_struct("", fields, visitor),
+            None => Err(serde::de::Error::invalid_type(
+                Unexpected::UnitVariant,
+                &"struct variant",
+            )),
+        }
     }
 }
 
@@ -379,6 +604,25 @@ impl<'de> MapAccess<'de> for PmtMapDeserializer {
     }
 }
 
+macro_rules! deserialize_parsed_key {
+    ($($method:ident => $visit:ident: $ty:ty),* $(,)?) => {
+        $(
+            fn $method<V>(self, visitor: V) -> core::result::Result<V::Value, Error>
+            where
+                V: Visitor<'de>,
+            {
+                match self.key.parse::<$ty>() {
+                    Ok(v) => visitor.$visit(v),
+                    Err(_) => Err(serde::de::Error::invalid_value(
+                        Unexpected::Str(&self.key),
+                        &visitor,
+                    )),
+                }
+            }
+        )*
+    };
+}
+
 struct MapKeyDeserializer<'de> {
     key: Cow<'de, str>,
 }
@@ -393,9 +637,41 @@ impl<'de> serde::Deserializer<'de> for MapKeyDeserializer<'de> {
         BorrowedCowStrDeserializer::new(self.key).deserialize_any(visitor)
     }
 
+    // keys of maps with non-string keys are serialized with `to_string()`
+    deserialize_parsed_key! {
+        deserialize_bool => visit_bool: bool,
+        deserialize_i8 => visit_i8: i8,
+        deserialize_i16 => visit_i16: i16,
+        deserialize_i32 => visit_i32: i32,
+        deserialize_i64 => visit_i64: i64,
+        deserialize_u8 => visit_u8: u8,
+        deserialize_u16 => visit_u16: u16,
+        deserialize_u32 => visit_u32: u32,
+        deserialize_u64 => visit_u64: u64,
+        deserialize_f32 => visit_f32: f32,
+        deserialize_f64 => visit_f64: f64,
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> core::result::Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> core::result::Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
     forward_to_deserialize_any! {
-        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
-        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        i128 u128 char str string bytes byte_buf unit unit_struct seq tuple
         tuple_struct map struct identifier ignored_any enum
     }
 }
@@ -0,0 +1,273 @@
+//! Binary serialization of [`Pmt`] compatible with GNU Radio's `pmt::serialize` and
+//! `pmt::deserialize`, as used by gr-zeromq tag headers.
+//!
+//! Only the subset of PMT types having an equivalent [`Pmt`] variant is supported:
+//!
+//! | [`Pmt`]                         | GNU Radio PMT                 |
+//! |---------------------------------|-------------------------------|
+//! | `Null`                          | `PMT_NIL`                     |
+//! | `Bool`                          | `PMT_T`, `PMT_F`              |
+//! | `String`                        | symbol                        |
+//! | `U32`                           | integer                       |
+//! | `U64`, `Usize`                  | `uint64`                      |
+//! | `F32`, `F64`                    | real (double)                 |
+//! | `VecPmt`                        | vector (tuples when decoding) |
+//! | `MapStrPmt`                     | dict                          |
+//! | `VecF32`, `VecCF32`, `VecU64`, `Blob` | `f32vector`, `c32vector`, `u64vector`, `u8vector` |
+//!
+//! Negative integers are decoded as [`Pmt::F64`]. Like GNU Radio does, the elements of
+//! `f32vector` and `c32vector` are serialized as doubles, i.e., one and two `f64`
+//! respectively.
+
+use super::error::{Error, Result};
+use futuresdr::num_complex::Complex32;
+use futuresdr::runtime::Pmt;
+use std::collections::HashMap;
+
+const PST_TRUE: u8 = 0x00;
+const PST_FALSE: u8 = 0x01;
+const PST_SYMBOL: u8 = 0x02;
+const PST_INT32: u8 = 0x03;
+const PST_DOUBLE: u8 = 0x04;
+const PST_NULL: u8 = 0x06;
+const PST_PAIR: u8 = 0x07;
+const PST_VECTOR: u8 = 0x08;
+const PST_DICT: u8 = 0x09;
+const PST_UNIFORM_VECTOR: u8 = 0x0a;
+const PST_UINT64: u8 = 0x0b;
+const PST_TUPLE: u8 = 0x0c;
+const PST_INT64: u8 = 0x0d;
+
+const UVI_U8: u8 = 0x00;
+const UVI_U64: u8 = 0x06;
+const UVI_F32: u8 = 0x08;
+const UVI_C32: u8 = 0x0a;
+
+/// Append the GNU Radio serialization of `value` to `out`.
+pub fn to_gr_bytes(value: &Pmt, out: &mut Vec<u8>) -> Result<()> {
+    match value {
+        Pmt::Null => out.push(PST_NULL),
+        Pmt::Bool(true) => out.push(PST_TRUE),
+        Pmt::Bool(false) => out.push(PST_FALSE),
+        Pmt::String(s) => write_symbol(s, out)?,
+        Pmt::U32(v) => match i32::try_from(*v) {
+            Ok(v) => {
+                out.push(PST_INT32);
+                out.extend_from_slice(&v.to_be_bytes());
+            }
+            Err(_) => {
+                out.push(PST_INT64);
+                out.extend_from_slice(&(*v as i64).to_be_bytes());
+            }
+        },
+        Pmt::U64(v) => {
+            out.push(PST_UINT64);
+            out.extend_from_slice(&v.to_be_bytes());
+        }
+        Pmt::Usize(v) => {
+            out.push(PST_UINT64);
+            out.extend_from_slice(&(*v as u64).to_be_bytes());
+        }
+        Pmt::F32(v) => {
+            out.push(PST_DOUBLE);
+            out.extend_from_slice(&(*v as f64).to_be_bytes());
+        }
+        Pmt::F64(v) => {
+            out.push(PST_DOUBLE);
+            out.extend_from_slice(&v.to_be_bytes());
+        }
+        Pmt::VecPmt(v) => {
+            out.push(PST_VECTOR);
+            write_len(v.len(), out)?;
+            for p in v {
+                to_gr_bytes(p, out)?;
+            }
+        }
+        Pmt::MapStrPmt(m) => {
+            // dicts are nil-terminated lists of (key . value) pairs
+            for (k, v) in m {
+                out.push(PST_DICT);
+                out.push(PST_PAIR);
+                write_symbol(k, out)?;
+                to_gr_bytes(v, out)?;
+            }
+            out.push(PST_NULL);
+        }
+        Pmt::Blob(v) => {
+            write_uniform_header(UVI_U8, v.len(), out)?;
+            out.extend_from_slice(v);
+        }
+        Pmt::VecU64(v) => {
+            write_uniform_header(UVI_U64, v.len(), out)?;
+            v.iter()
+                .for_each(|x| out.extend_from_slice(&x.to_be_bytes()));
+        }
+        Pmt::VecF32(v) => {
+            write_uniform_header(UVI_F32, v.len(), out)?;
+            v.iter()
+                .for_each(|x| out.extend_from_slice(&(*x as f64).to_be_bytes()));
+        }
+        Pmt::VecCF32(v) => {
+            write_uniform_header(UVI_C32, v.len(), out)?;
+            v.iter().for_each(|x| {
+                out.extend_from_slice(&(x.re as f64).to_be_bytes());
+                out.extend_from_slice(&(x.im as f64).to_be_bytes());
+            });
+        }
+        p => {
+            return Err(Error::Message(format!(
+                "{:?} has no GNU Radio PMT equivalent",
+                p
+            )))
+        }
+    }
+    Ok(())
+}
+
+/// Deserialize one PMT from the beginning of `input`, advancing it past the PMT.
+pub fn from_gr_bytes(input: &mut &[u8]) -> Result<Pmt> {
+    let tag = take::<1>(input)?[0];
+    Ok(match tag {
+        PST_NULL => Pmt::Null,
+        PST_TRUE => Pmt::Bool(true),
+        PST_FALSE => Pmt::Bool(false),
+        PST_SYMBOL => Pmt::String(read_symbol(input)?),
+        PST_INT32 => {
+            let v = i32::from_be_bytes(take(input)?);
+            u32::try_from(v).map_or(Pmt::F64(v as f64), Pmt::U32)
+        }
+        PST_INT64 => {
+            let v = i64::from_be_bytes(take(input)?);
+            u64::try_from(v).map_or(Pmt::F64(v as f64), Pmt::U64)
+        }
+        PST_UINT64 => Pmt::U64(u64::from_be_bytes(take(input)?)),
+        PST_DOUBLE => Pmt::F64(f64::from_be_bytes(take(input)?)),
+        PST_VECTOR | PST_TUPLE => {
+            let len = u32::from_be_bytes(take(input)?) as usize;
+            let mut v = Vec::with_capacity(len.min(input.len()));
+            for _ in 0..len {
+                v.push(from_gr_bytes(input)?);
+            }
+            Pmt::VecPmt(v)
+        }
+        PST_PAIR => {
+            let car = from_gr_bytes(input)?;
+            let cdr = from_gr_bytes(input)?;
+            Pmt::VecPmt(vec![car, cdr])
+        }
+        PST_DICT => {
+            let mut m = HashMap::new();
+            read_dict(input, &mut m)?;
+            Pmt::MapStrPmt(m)
+        }
+        PST_UNIFORM_VECTOR => read_uniform(input)?,
+        t => return Err(Error::Message(format!("unsupported PMT type 0x{:02x}", t))),
+    })
+}
+
+fn take<const N: usize>(input: &mut &[u8]) -> Result<[u8; N]> {
+    if input.len() < N {
+        return Err(Error::Eof);
+    }
+    let (head, tail) = input.split_at(N);
+    *input = tail;
+    Ok(head.try_into().unwrap())
+}
+
+fn take_slice<'a>(input: &mut &'a [u8], n: usize) -> Result<&'a [u8]> {
+    if input.len() < n {
+        return Err(Error::Eof);
+    }
+    let (head, tail) = input.split_at(n);
+    *input = tail;
+    Ok(head)
+}
+
+fn write_len(len: usize, out: &mut Vec<u8>) -> Result<()> {
+    let len = u32::try_from(len).map_err(|_| Error::Message("PMT too long".to_string()))?;
+    out.extend_from_slice(&len.to_be_bytes());
+    Ok(())
+}
+
+fn write_symbol(s: &str, out: &mut Vec<u8>) -> Result<()> {
+    let len = u16::try_from(s.len()).map_err(|_| Error::Message("symbol too long".to_string()))?;
+    out.push(PST_SYMBOL);
+    out.extend_from_slice(&len.to_be_bytes());
+    out.extend_from_slice(s.as_bytes());
+    Ok(())
+}
+
+fn read_symbol(input: &mut &[u8]) -> Result<String> {
+    let len = u16::from_be_bytes(take(input)?) as usize;
+    let bytes = take_slice(input, len)?;
+    String::from_utf8(bytes.to_vec()).map_err(|e| Error::Message(e.to_string()))
+}
+
+fn write_uniform_header(uvi: u8, len: usize, out: &mut Vec<u8>) -> Result<()> {
+    out.push(PST_UNIFORM_VECTOR);
+    out.push(uvi);
+    write_len(len, out)?;
+    // one byte of padding
+    out.push(1);
+    out.push(0);
+    Ok(())
+}
+
+fn read_uniform(input: &mut &[u8]) -> Result<Pmt> {
+    let uvi = take::<1>(input)?[0];
+    let len = u32::from_be_bytes(take(input)?) as usize;
+    let npad = take::<1>(input)?[0] as usize;
+    take_slice(input, npad)?;
+    Ok(match uvi {
+        UVI_U8 => Pmt::Blob(take_slice(input, len)?.to_vec()),
+        UVI_U64 => Pmt::VecU64(
+            take_slice(input, len.saturating_mul(8))?
+                .chunks_exact(8)
+                .map(|b| u64::from_be_bytes(b.try_into().unwrap()))
+                .collect(),
+        ),
+        UVI_F32 => Pmt::VecF32(
+            take_slice(input, len.saturating_mul(8))?
+                .chunks_exact(8)
+                .map(|b| f64::from_be_bytes(b.try_into().unwrap()) as f32)
+                .collect(),
+        ),
+        UVI_C32 => Pmt::VecCF32(
+            take_slice(input, len.saturating_mul(16))?
+                .chunks_exact(16)
+                .map(|b| {
+                    Complex32::new(
+                        f64::from_be_bytes(b[..8].try_into().unwrap()) as f32,
+                        f64::from_be_bytes(b[8..].try_into().unwrap()) as f32,
+                    )
+                })
+                .collect(),
+        ),
+        t => {
+            return Err(Error::Message(format!(
+                "unsupported PMT uniform vector type 0x{:02x}",
+                t
+            )))
+        }
+    })
+}
+
+/// Read the remaining `(key . value)` pairs of a dict, after its first `PST_DICT`.
+fn read_dict(input: &mut &[u8], m: &mut HashMap<String, Pmt>) -> Result<()> {
+    loop {
+        if take::<1>(input)?[0] != PST_PAIR {
+            return Err(Error::Message("malformed PMT dict".to_string()));
+        }
+        let key = match from_gr_bytes(input)? {
+            Pmt::String(k) => k,
+            _ => return Err(Error::KeyMustBeAString),
+        };
+        let value = from_gr_bytes(input)?;
+        m.insert(key, value);
+        match take::<1>(input)?[0] {
+            PST_DICT => continue,
+            PST_NULL => return Ok(()),
+            _ => return Err(Error::Message("malformed PMT dict".to_string())),
+        }
+    }
+}
@@ -0,0 +1,219 @@
+//! Canonical JSON representation of [`Pmt`], preserving the variant of every value.
+//!
+//! Each [`Pmt`] is a JSON object with a single member, named after the variant:
+//!
+//! | [`Pmt`]        | JSON                                  |
+//! |----------------|---------------------------------------|
+//! | `Ok`           | `{"Ok": null}`                        |
+//! | `InvalidValue` | `{"InvalidValue": null}`              |
+//! | `Null`         | `{"Null": null}`                      |
+//! | `Finished`     | `{"Finished": null}`                  |
+//! | `Bool`         | `{"Bool": true}`                      |
+//! | `String`       | `{"String": "a string"}`              |
+//! | `Usize`        | `{"Usize": 42}`                       |
+//! | `U32`          | `{"U32": 42}`                         |
+//! | `U64`          | `{"U64": 42}`                         |
+//! | `F32`          | `{"F32": 0.5}`                        |
+//! | `F64`          | `{"F64": 0.5}`                        |
+//! | `VecF32`       | `{"VecF32": [0.5, 1.0]}`              |
+//! | `VecU64`       | `{"VecU64": [1, 2]}`                  |
+//! | `VecCF32`      | `{"VecCF32": [[0.5, -1.0]]}`          |
+//! | `Blob`         | `{"Blob": "00ff"}` (lowercase hex)    |
+//! | `VecPmt`       | `{"VecPmt": [{"U32": 1}]}`            |
+//! | `MapStrPmt`    | `{"MapStrPmt": {"key": {"U32": 1}}}`  |
+//!
+//! `F32` values are written as their exact `f64` widening, so that they read back
+//! bit-identical. Non-finite floats are written as the strings `"NaN"`, `"inf"` and `"-inf"`.
+//! Map members are sorted by key, so that equal values always have the same encoding.
+//! `Pmt::Any` cannot be encoded.
+
+use super::error::{Error, Result};
+use futuresdr::num_complex::Complex32;
+use futuresdr::runtime::Pmt;
+use serde_json::{Map, Number, Value};
+use std::collections::HashMap;
+
+/// Encode `value` as a JSON value.
+pub fn to_json(value: &Pmt) -> Result<Value> {
+    let (variant, content) = match value {
+        Pmt::Ok => ("Ok", Value::Null),
+        Pmt::InvalidValue => ("InvalidValue", Value::Null),
+        Pmt::Null => ("Null", Value::Null),
+        Pmt::Finished => ("Finished", Value::Null),
+        Pmt::Bool(v) => ("Bool", Value::Bool(*v)),
+        Pmt::String(v) => ("String", Value::String(v.clone())),
+        Pmt::Usize(v) => ("Usize", Value::from(*v as u64)),
+        Pmt::U32(v) => ("U32", Value::from(*v)),
+        Pmt::U64(v) => ("U64", Value::from(*v)),
+        Pmt::F32(v) => ("F32", float_to_json(*v as f64)),
+        Pmt::F64(v) => ("F64", float_to_json(*v)),
+        Pmt::VecF32(v) => (
+            "VecF32",
+            Value::Array(v.iter().map(|x| float_to_json(*x as f64)).collect()),
+        ),
+        Pmt::VecU64(v) => (
+            "VecU64",
+            Value::Array(v.iter().map(|x| Value::from(*x)).collect()),
+        ),
+        Pmt::VecCF32(v) => (
+            "VecCF32",
+            Value::Array(
+                v.iter()
+                    .map(|c| {
+                        Value::Array(vec![float_to_json(c.re as f64), float_to_json(c.im as f64)])
+                    })
+                    .collect(),
+            ),
+        ),
+        Pmt::Blob(v) => (
+            "Blob",
+            Value::String(v.iter().map(|b| format!("{:02x}", b)).collect()),
+        ),
+        Pmt::VecPmt(v) => (
+            "VecPmt",
+            Value::Array(v.iter().map(to_json).collect::<Result<Vec<Value>>>()?),
+        ),
+        Pmt::MapStrPmt(m) => {
+            let mut map = Map::new();
+            for (k, v) in m {
+                map.insert(k.clone(), to_json(v)?);
+            }
+            ("MapStrPmt", Value::Object(map))
+        }
+        p => {
+            return Err(Error::Message(format!(
+                "{:?} has no JSON representation",
+                p
+            )))
+        }
+    };
+    let mut object = Map::new();
+    object.insert(variant.to_string(), content);
+    Ok(Value::Object(object))
+}
+
+/// Encode `value` as a JSON string.
+pub fn to_json_string(value: &Pmt) -> Result<String> {
+    Ok(to_json(value)?.to_string())
+}
+
+/// Decode a [`Pmt`] from a JSON value written by [`to_json`].
+pub fn from_json(value: &Value) -> Result<Pmt> {
+    let (variant, content) = match value {
+        Value::Object(o) if o.len() == 1 => match o.iter().next() {
+            Some(member) => member,
+            None => return Err(malformed(value)),
+        },
+        _ => return Err(malformed(value)),
+    };
+    Ok(match variant.as_str() {
+        "Ok" => Pmt::Ok,
+        "InvalidValue" => Pmt::InvalidValue,
+        "Null" => Pmt::Null,
+        "Finished" => Pmt::Finished,
+        "Bool" => Pmt::Bool(content.as_bool().ok_or_else(|| malformed(value))?),
+        "String" => Pmt::String(
+            content
+                .as_str()
+                .ok_or_else(|| malformed(value))?
+                .to_string(),
+        ),
+        "Usize" => {
+            Pmt::Usize(usize::try_from(unsigned_from_json(content)?).map_err(|_| malformed(value))?)
+        }
+        "U32" => {
+            Pmt::U32(u32::try_from(unsigned_from_json(content)?).map_err(|_| malformed(value))?)
+        }
+        "U64" => Pmt::U64(unsigned_from_json(content)?),
+        "F32" => Pmt::F32(float_from_json(content)? as f32),
+        "F64" => Pmt::F64(float_from_json(content)?),
+        "VecF32" => Pmt::VecF32(
+            array_from_json(content)?
+                .iter()
+                .map(|x| float_from_json(x).map(|x| x as f32))
+                .collect::<Result<Vec<f32>>>()?,
+        ),
+        "VecU64" => Pmt::VecU64(
+            array_from_json(content)?
+                .iter()
+                .map(unsigned_from_json)
+                .collect::<Result<Vec<u64>>>()?,
+        ),
+        "VecCF32" => Pmt::VecCF32(
+            array_from_json(content)?
+                .iter()
+                .map(|c| match array_from_json(c)?.as_slice() {
+                    [re, im] => Ok(Complex32::new(
+                        float_from_json(re)? as f32,
+                        float_from_json(im)? as f32,
+                    )),
+                    _ => Err(malformed(c)),
+                })
+                .collect::<Result<Vec<Complex32>>>()?,
+        ),
+        "Blob" => Pmt::Blob(hex_from_json(content)?),
+        "VecPmt" => Pmt::VecPmt(
+            array_from_json(content)?
+                .iter()
+                .map(from_json)
+                .collect::<Result<Vec<Pmt>>>()?,
+        ),
+        "MapStrPmt" => match content {
+            Value::Object(o) => Pmt::MapStrPmt(
+                o.iter()
+                    .map(|(k, v)| Ok((k.clone(), from_json(v)?)))
+                    .collect::<Result<HashMap<String, Pmt>>>()?,
+            ),
+            _ => return Err(malformed(value)),
+        },
+        _ => return Err(malformed(value)),
+    })
+}
+
+/// Decode a [`Pmt`] from a JSON string written by [`to_json_string`].
+pub fn from_json_str(s: &str) -> Result<Pmt> {
+    let value: Value = serde_json::from_str(s).map_err(|e| Error::Message(e.to_string()))?;
+    from_json(&value)
+}
+
+fn malformed(value: &Value) -> Error {
+    Error::Message(format!("malformed Pmt JSON: {}", value))
+}
+
+fn float_to_json(v: f64) -> Value {
+    match Number::from_f64(v) {
+        Some(n) => Value::Number(n),
+        None if v.is_nan() => Value::String("NaN".to_string()),
+        None if v > 0.0 => Value::String("inf".to_string()),
+        None => Value::String("-inf".to_string()),
+    }
+}
+
+fn float_from_json(value: &Value) -> Result<f64> {
+    match value {
+        Value::Number(n) => n.as_f64().ok_or_else(|| malformed(value)),
+        Value::String(s) if s == "NaN" => Ok(f64::NAN),
+        Value::String(s) if s == "inf" => Ok(f64::INFINITY),
+        Value::String(s) if s == "-inf" => Ok(f64::NEG_INFINITY),
+        _ => Err(malformed(value)),
+    }
+}
+
+fn unsigned_from_json(value: &Value) -> Result<u64> {
+    value.as_u64().ok_or_else(|| malformed(value))
+}
+
+fn array_from_json(value: &Value) -> Result<&Vec<Value>> {
+    value.as_array().ok_or_else(|| malformed(value))
+}
+
+fn hex_from_json(value: &Value) -> Result<Vec<u8>> {
+    let s = value.as_str().ok_or_else(|| malformed(value))?;
+    if s.len() % 2 != 0 || !s.is_ascii() {
+        return Err(malformed(value));
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).map_err(|_| malformed(value)))
+        .collect()
+}
@@ -1,4 +1,39 @@
+//! Conversion of Rust values from and to [`Pmt`] with serde.
+//!
+//! [`to_pmt`] uses the following mapping, which [`from_pmt`] reverses:
+//!
+//! | Rust                            | [`Pmt`]                                      |
+//! |---------------------------------|----------------------------------------------|
+//! | `bool`                          | `Bool`                                       |
+//! | `u8`, `u16`, `u32`              | `U32`                                        |
+//! | `u64`, `usize`                  | `U64`                                        |
+//! | `i8`, `i16`, `i32`              | `U32` if positive or zero, `F64` if negative |
+//! | `i64`                           | `U64` if positive or zero, `F64` if negative |
+//! | `f32`, `f64`                    | `F32`, `F64`                                 |
+//! | `char`, `str`, `String`         | `String`                                     |
+//! | bytes                           | `Blob`                                       |
+//! | `None`, `()`, unit structs      | `Null`                                       |
+//! | `Some(v)`, newtype structs      | the value itself                             |
+//! | sequences, tuples, tuple structs | `VecF32`, `VecU64` or `VecCF32` when all items are `f32`, `u64` or `Complex32`, `VecPmt` otherwise |
+//! | maps, structs                   | `MapStrPmt`, with keys converted to strings  |
+//! | unit variants                   | `String` holding the variant name            |
+//! | other enum variants             | `MapStrPmt` with a single entry, from the variant name to its content |
+//!
+//! The variant of a signed integer thus depends on its sign, e.g., `vec![1i32, -1]`
+//! becomes `VecPmt(vec![U32(1), F64(-1.0)])`. Negative `i64` that `f64` cannot represent
+//! exactly, such as -2<sup>53</sup> - 1, and 128-bit integers not fitting in 64 bits cannot
+//! be converted.
+//!
+//! When reading back, numbers are coerced between the numeric variants, failing
+//! if the value does not fit in the target type.
+//!
+//! To store or exchange [`Pmt`] values themselves, [`json`] and [`cbor`] provide
+//! lossless encodings preserving the variants.
+pub mod cbor;
+pub mod config;
 pub mod error;
+pub mod gr_pmt;
+pub mod json;
 
 mod serialiser;
 use futuresdr::runtime::Pmt;
@@ -1,4 +1,5 @@
 use core::fmt::Display;
+use futuresdr::num_complex::Complex32;
 use futuresdr::runtime::Pmt;
 use serde::{
     ser::{self, Impossible},
@@ -18,38 +19,49 @@ impl serde::Serializer for &mut Serializer {
 
     type Error = Error;
 
-    type SerializeSeq = Impossible<Pmt, Error>; // TODO
+    type SerializeSeq = SerializeVec;
 
-    type SerializeTuple = Impossible<Pmt, Error>; // TODO
+    type SerializeTuple = SerializeVec;
 
-    type SerializeTupleStruct = Impossible<Pmt, Error>; // TODO
+    type SerializeTupleStruct = SerializeVec;
 
-    type SerializeTupleVariant = Impossible<Pmt, Error>; // TODO
+    type SerializeTupleVariant = SerializeTupleVariant;
 
     type SerializeMap = SerializeMap;
 
     type SerializeStruct = SerializeMap;
 
-    type SerializeStructVariant = Impossible<Pmt, Error>; // TODO
+    type SerializeStructVariant = SerializeStructVariant;
 
     fn serialize_bool(self, v: bool) -> Result<Self::Ok> {
         Ok(Pmt::Bool(v))
     }
 
     fn serialize_i8(self, v: i8) -> Result<Self::Ok> {
-        self.serialize_f32(v as f32)
+        self.serialize_i32(v as i32)
     }
 
     fn serialize_i16(self, v: i16) -> Result<Self::Ok> {
-        self.serialize_f32(v as f32)
+        self.serialize_i32(v as i32)
     }
 
     fn serialize_i32(self, v: i32) -> Result<Self::Ok> {
-        self.serialize_f32(v as f32)
+        match u32::try_from(v) {
+            Ok(v) => self.serialize_u32(v),
+            Err(_) => self.serialize_f64(v as f64),
+        }
     }
 
     fn serialize_i64(self, v: i64) -> Result<Self::Ok> {
-        self.serialize_f32(v as f32)
+        match u64::try_from(v) {
+            Ok(v) => self.serialize_u64(v),
+            // below -2^53, only some negative values are exact as f64
+            Err(_) if v as f64 as i64 == v => self.serialize_f64(v as f64),
+            Err(_) => Err(ser::Error::custom(format!(
+                "{} cannot be represented exactly",
+                v
+            ))),
+        }
     }
 
     fn serialize_u8(self, v: u8) -> Result<Self::Ok> {
@@ -87,8 +99,7 @@ impl serde::Serializer for &mut Serializer {
     }
 
     fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok> {
-        let vec = v.iter().map(|&b| Pmt::U32(b.into())).collect();
-        Ok(Pmt::VecPmt(vec))
+        Ok(Pmt::Blob(v.to_vec()))
     }
 
     fn serialize_none(self) -> Result<Self::Ok> {
@@ -147,12 +158,11 @@ impl serde::Serializer for &mut Serializer {
 
     fn serialize_seq(
         self,
-        _len: Option<usize>,
+        len: Option<usize>,
     ) -> std::prelude::v1::Result<Self::SerializeSeq, Self::Error> {
-        // Ok(SerializeVec {
-        //     vec: Vec::with_capacity(len.unwrap_or(0)),
-        // })
-        todo!()
+        Ok(SerializeVec {
+            vec: Vec::with_capacity(len.unwrap_or(0)),
+        })
     }
 
     fn serialize_tuple(
@@ -174,14 +184,13 @@ impl serde::Serializer for &mut Serializer {
         self,
         _name: &'static str,
         _variant_index: u32,
-        _variant: &'static str,
-        _len: usize,
+        variant: &'static str,
+        len: usize,
     ) -> std::prelude::v1::Result<Self::SerializeTupleVariant, Self::Error> {
-        // Ok(SerializeTupleVariant {
-        //     name: String::from(variant),
-        //     vec: Vec::with_capacity(len),
-        // })
-        todo!()
+        Ok(SerializeTupleVariant {
+            name: String::from(variant),
+            vec: Vec::with_capacity(len),
+        })
     }
 
     fn serialize_map(
@@ -206,24 +215,27 @@ impl serde::Serializer for &mut Serializer {
         self,
         _name: &'static str,
         _variant_index: u32,
-        _variant: &'static str,
+        variant: &'static str,
         _len: usize,
     ) -> std::prelude::v1::Result<Self::SerializeStructVariant, Self::Error> {
-        // Ok(SerializeStructVariant {
-        //     name: String::from(variant),
-        //     map: Map::new(),
-        // })
-        todo!()
+        Ok(SerializeStructVariant {
+            name: String::from(variant),
+            map: HashMap::new(),
+        })
     }
 
     fn serialize_i128(self, v: i128) -> std::prelude::v1::Result<Self::Ok, Self::Error> {
-        let _ = v;
-        Err(ser::Error::custom("i128 is not supported"))
+        match i64::try_from(v) {
+            Ok(v) => self.serialize_i64(v),
+            Err(_) => Err(ser::Error::custom(format!("{} does not fit in 64 bits", v))),
+        }
     }
 
     fn serialize_u128(self, v: u128) -> std::prelude::v1::Result<Self::Ok, Self::Error> {
-        let _ = v;
-        Err(ser::Error::custom("u128 is not supported"))
+        match u64::try_from(v) {
+            Ok(v) => self.serialize_u64(v),
+            Err(_) => Err(ser::Error::custom(format!("{} does not fit in 64 bits", v))),
+        }
     }
 
     fn collect_str<T>(self, value: &T) -> Result<Pmt>
@@ -234,6 +246,147 @@ impl serde::Serializer for &mut Serializer {
     }
 }
 
+pub struct SerializeVec {
+    vec: Vec<Pmt>,
+}
+
+/// Use the specialized vector variants for homogeneous sequences of `f32`, `u64`
+/// and `Complex32` (serialized as `(re, im)` tuples).
+fn pack_seq(vec: Vec<Pmt>) -> Pmt {
+    if vec.is_empty() {
+        Pmt::VecPmt(vec)
+    } else if vec.iter().all(|p| matches!(p, Pmt::F32(_))) {
+        Pmt::VecF32(
+            vec.into_iter()
+                .filter_map(|p| match p {
+                    Pmt::F32(v) => Some(v),
+                    _ => None,
+                })
+                .collect(),
+        )
+    } else if vec.iter().all(|p| matches!(p, Pmt::U64(_))) {
+        Pmt::VecU64(
+            vec.into_iter()
+                .filter_map(|p| match p {
+                    Pmt::U64(v) => Some(v),
+                    _ => None,
+                })
+                .collect(),
+        )
+    } else if vec
+        .iter()
+        .all(|p| matches!(p, Pmt::VecF32(c) if c.len() == 2))
+    {
+        Pmt::VecCF32(
+            vec.into_iter()
+                .filter_map(|p| match p {
+                    Pmt::VecF32(c) => Some(Complex32::new(c[0], c[1])),
+                    _ => None,
+                })
+                .collect(),
+        )
+    } else {
+        Pmt::VecPmt(vec)
+    }
+}
+
+impl serde::ser::SerializeSeq for SerializeVec {
+    type Ok = Pmt;
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.vec.push(to_pmt(value)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Pmt> {
+        Ok(pack_seq(self.vec))
+    }
+}
+
+impl serde::ser::SerializeTuple for SerializeVec {
+    type Ok = Pmt;
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        serde::ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Pmt> {
+        serde::ser::SerializeSeq::end(self)
+    }
+}
+
+impl serde::ser::SerializeTupleStruct for SerializeVec {
+    type Ok = Pmt;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        serde::ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Pmt> {
+        serde::ser::SerializeSeq::end(self)
+    }
+}
+
+pub struct SerializeTupleVariant {
+    name: String,
+    vec: Vec<Pmt>,
+}
+
+impl serde::ser::SerializeTupleVariant for SerializeTupleVariant {
+    type Ok = Pmt;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.vec.push(to_pmt(value)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Pmt> {
+        let mut values = HashMap::<String, Pmt>::new();
+        values.insert(self.name, pack_seq(self.vec));
+        Ok(Pmt::MapStrPmt(values))
+    }
+}
+
+pub struct SerializeStructVariant {
+    name: String,
+    map: HashMap<String, Pmt>,
+}
+
+impl serde::ser::SerializeStructVariant for SerializeStructVariant {
+    type Ok = Pmt;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.map.insert(String::from(key), to_pmt(value)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Pmt> {
+        let mut values = HashMap::<String, Pmt>::new();
+        values.insert(self.name, Pmt::MapStrPmt(self.map));
+        Ok(Pmt::MapStrPmt(values))
+    }
+}
+
 pub struct SerializeMap {
     map: HashMap<String, Pmt>,
     next_key: Option<String>,
@@ -0,0 +1,360 @@
+//! ## Squelch blocks
+//!
+//! [`PowerSquelch`] only lets through the bursts of a stream whose power is above
+//! a threshold, and tags their boundaries.
+use futuresdr::num_complex::ComplexFloat;
+use futuresdr::runtime::Block;
+use futuresdr::runtime::BlockMeta;
+use futuresdr::runtime::BlockMetaBuilder;
+use futuresdr::runtime::Kernel;
+use futuresdr::runtime::MessageIo;
+use futuresdr::runtime::MessageIoBuilder;
+use futuresdr::runtime::Pmt;
+use futuresdr::runtime::Result;
+use futuresdr::runtime::StreamIo;
+use futuresdr::runtime::StreamIoBuilder;
+use futuresdr::runtime::Tag;
+use futuresdr::runtime::WorkIo;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::f32::consts::FRAC_PI_2;
+
+use crate::agc::{sample_power, PowerWindow};
+use crate::serde_pmt::config::{get_config, set_config, set_config_field, Configurable};
+
+/// Label of the tag on the first sample of a burst
+pub const BURST_START_LABEL: &str = "burst_start";
+/// Label of the tag on the last sample of a burst
+pub const BURST_END_LABEL: &str = "burst_end";
+
+/// Squelch letting through the bursts of a stream whose power is above a threshold.
+///
+/// The power is averaged over a window of samples, and compared to thresholds in dBFS,
+/// i.e., relative to a power of 1. A burst starts once the power reaches the `open`
+/// threshold, and ends with the sample where it drops below the `close` threshold,
+/// which can be set lower for hysteresis.
+/// Samples outside of bursts are zeroed, or dropped when gating.
+///
+/// The first and last `ramp` samples of a burst are faded in and out with a raised cosine.
+/// The burst then ends `ramp` samples after the power drops below the `close` threshold.
+///
+/// # Inputs
+///
+/// `in`: Samples, implementing [`ComplexFloat`]
+///
+/// # Outputs
+///
+/// `out`: Squelched samples, with the first and last samples of each burst tagged with
+/// a [`Tag::Data`] holding a [`Pmt::MapStrPmt`], in the SigMF annotation format:
+/// - `core:label`: [`BURST_START_LABEL`] or [`BURST_END_LABEL`]
+/// - `core:sample_start`: index of the first sample of the burst in the output
+/// - `core:sample_count`: number of samples of the burst, on its last sample only
+/// - `power_dbfs`: power at the start of the burst, on its first sample only
+///
+/// # Messages
+///
+/// - `open`, `close`: set the thresholds in dBFS with a number.
+/// - `gate`: drop samples outside of bursts with a [`Pmt::Bool`].
+/// - `config`: set any fields of the [`PowerSquelchConfig`] with a [`Pmt::MapStrPmt`].
+/// - `get_config`: get the current [`PowerSquelchConfig`].
+///
+/// See [`serde_pmt::config`](crate::serde_pmt::config) for the replies.
+///
+/// # Usage
+/// ```
+/// use fsdr_blocks::squelch::PowerSquelchBuilder;
+/// use futuresdr::num_complex::Complex32;
+///
+/// let squelch = PowerSquelchBuilder::<Complex32>::new(-40.0)
+///     .close(-45.0)
+///     .window(32)
+///     .ramp(16)
+///     .gate(true)
+///     .build();
+/// ```
+pub struct PowerSquelch<T> {
+    config: PowerSquelchConfig,
+    power: PowerWindow,
+    state: BurstState,
+    /// number of samples produced so far
+    produced: usize,
+    /// index of the first sample of the current burst
+    burst_start: usize,
+    _type: std::marker::PhantomData<T>,
+}
+
+/// Position in a burst
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BurstState {
+    Closed,
+    /// `k`-th sample of the fade in, with `k < ramp`
+    Opening(usize),
+    Open,
+    /// `k`-th sample of the fade out, with `k <= ramp`, starting at full gain with the
+    /// sample below the `close` threshold
+    Closing(usize),
+}
+
+/// Parameters of the [`PowerSquelch`] block, settable through its `config` message handler
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PowerSquelchConfig {
+    /// power in dBFS starting a burst
+    pub open: f32,
+    /// power in dBFS ending a burst, at most `open`
+    pub close: f32,
+    /// number of samples the power is averaged over
+    pub window: usize,
+    /// number of samples faded in and out at the start and end of bursts
+    pub ramp: usize,
+    /// drop samples outside of bursts, instead of zeroing them
+    pub gate: bool,
+}
+
+impl<T> PowerSquelch<T>
+where
+    T: Send + Sync + ComplexFloat + 'static,
+{
+    /// Create a squelch opening and closing at `threshold` dBFS
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(threshold: f32) -> Block {
+        PowerSquelchBuilder::<T>::new(threshold).build()
+    }
+
+    fn with_config(config: PowerSquelchConfig) -> Block {
+        if let Err(e) = check(&config) {
+            panic!("invalid squelch config: {e}");
+        }
+
+        Block::new(
+            BlockMetaBuilder::new("PowerSquelch").build(),
+            StreamIoBuilder::new()
+                .add_input::<T>("in")
+                .add_output::<T>("out")
+                .build(),
+            MessageIoBuilder::<Self>::new()
+                .add_input("open", set_config_field("open"))
+                .add_input("close", set_config_field("close"))
+                .add_input("gate", set_config_field("gate"))
+                .add_input("config", set_config)
+                .add_input("get_config", get_config)
+                .build(),
+            PowerSquelch {
+                power: PowerWindow::new(config.window),
+                config,
+                state: BurstState::Closed,
+                produced: 0,
+                burst_start: 0,
+                _type: std::marker::PhantomData,
+            },
+        )
+    }
+
+    /// Gain of the `k`-th sample of a fade out of `ramp` samples, from 1 for `k = 0`
+    fn fade_out(k: usize, ramp: usize) -> f32 {
+        (FRAC_PI_2 * k as f32 / (ramp + 1) as f32).cos().powi(2)
+    }
+
+    fn tag(&self, label: &str, entries: Vec<(&str, Pmt)>) -> Tag {
+        let mut map = HashMap::from([
+            ("core:label".to_string(), Pmt::String(label.to_string())),
+            (
+                "core:sample_start".to_string(),
+                Pmt::Usize(self.burst_start),
+            ),
+        ]);
+        map.extend(entries.into_iter().map(|(k, v)| (k.to_string(), v)));
+        Tag::Data(Pmt::MapStrPmt(map))
+    }
+}
+
+fn check(config: &PowerSquelchConfig) -> std::result::Result<(), String> {
+    if config.close > config.open {
+        return Err("close threshold must not be above the open threshold".to_string());
+    }
+    if config.window == 0 {
+        return Err("window must not be empty".to_string());
+    }
+    Ok(())
+}
+
+impl<T> Configurable for PowerSquelch<T>
+where
+    T: Send + Sync + ComplexFloat + 'static,
+{
+    type Config = PowerSquelchConfig;
+
+    fn config(&self) -> PowerSquelchConfig {
+        self.config.clone()
+    }
+
+    fn set_config(&mut self, config: PowerSquelchConfig) -> std::result::Result<(), String> {
+        check(&config)?;
+        if config.window != self.config.window {
+            self.power = PowerWindow::new(config.window);
+        }
+        self.config = config;
+        Ok(())
+    }
+}
+
+#[doc(hidden)]
+#[async_trait]
+impl<T> Kernel for PowerSquelch<T>
+where
+    T: Send + Sync + ComplexFloat + 'static,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        sio: &mut StreamIo,
+        _mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let i = sio.input(0).slice::<T>();
+        let o = sio.output(0).slice::<T>();
+
+        let open = 10f32.powf(self.config.open / 10.0);
+        let close = 10f32.powf(self.config.close / 10.0);
+        let ramp = self.config.ramp;
+
+        let mut consumed = 0;
+        let mut produced = 0;
+        while consumed < i.len() && produced < o.len() {
+            let input = i[consumed];
+            consumed += 1;
+            let power = self.power.push(sample_power(input));
+
+            let mut tags = vec![];
+            self.state = match self.state {
+                BurstState::Closed if power >= open => {
+                    self.burst_start = self.produced + produced;
+                    tags.push(self.tag(
+                        BURST_START_LABEL,
+                        vec![("power_dbfs", Pmt::F32(10.0 * power.log10()))],
+                    ));
+                    if ramp > 0 {
+                        BurstState::Opening(0)
+                    } else {
+                        BurstState::Open
+                    }
+                }
+                BurstState::Closed => {
+                    if self.config.gate {
+                        continue;
+                    }
+                    BurstState::Closed
+                }
+                // fade out from the gain of the previous sample
+                BurstState::Opening(k) if power < close => BurstState::Closing(ramp - k),
+                BurstState::Open if power < close => BurstState::Closing(0),
+                BurstState::Opening(k) if k + 1 < ramp => BurstState::Opening(k + 1),
+                BurstState::Opening(_) | BurstState::Open => BurstState::Open,
+                // fade in again, from the gain of the previous sample
+                BurstState::Closing(k) if power >= open && k > 1 => {
+                    BurstState::Opening(ramp + 1 - k)
+                }
+                BurstState::Closing(_) if power >= open => BurstState::Open,
+                BurstState::Closing(k) => BurstState::Closing(k + 1),
+            };
+
+            let gain = match self.state {
+                BurstState::Closed => 0.0,
+                BurstState::Opening(k) => Self::fade_out(ramp - k, ramp),
+                BurstState::Open => 1.0,
+                BurstState::Closing(k) => Self::fade_out(k, ramp),
+            };
+
+            if self.state == BurstState::Closing(ramp) {
+                let count = self.produced + produced + 1 - self.burst_start;
+                tags.push(self.tag(
+                    BURST_END_LABEL,
+                    vec![("core:sample_count", Pmt::Usize(count))],
+                ));
+                self.state = BurstState::Closed;
+            }
+
+            o[produced] = if gain == 0.0 {
+                T::zero()
+            } else {
+                input * T::from(gain).unwrap()
+            };
+            for tag in tags {
+                sio.output(0).add_tag(produced, tag);
+            }
+            produced += 1;
+        }
+
+        self.produced += produced;
+        sio.input(0).consume(consumed);
+        sio.output(0).produce(produced);
+
+        if sio.input(0).finished() && consumed == i.len() {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
+
+/// Builder for [`PowerSquelch`] block
+pub struct PowerSquelchBuilder<T>
+where
+    T: Send + Sync + ComplexFloat + 'static,
+{
+    config: PowerSquelchConfig,
+    _type: std::marker::PhantomData<T>,
+}
+
+impl<T> PowerSquelchBuilder<T>
+where
+    T: Send + Sync + ComplexFloat + 'static,
+{
+    /// Create builder for a squelch opening at `open` dBFS
+    ///
+    /// ## Defaults
+    /// - `close`: `open`, i.e., no hysteresis
+    /// - `window`: 64
+    /// - `ramp`: 0
+    /// - `gate`: false
+    pub fn new(open: f32) -> PowerSquelchBuilder<T> {
+        PowerSquelchBuilder {
+            config: PowerSquelchConfig {
+                open,
+                close: open,
+                window: 64,
+                ramp: 0,
+                gate: false,
+            },
+            _type: std::marker::PhantomData,
+        }
+    }
+
+    /// Power in dBFS below which bursts end
+    pub fn close(mut self, close: f32) -> PowerSquelchBuilder<T> {
+        self.config.close = close;
+        self
+    }
+
+    /// Number of samples the power is averaged over
+    pub fn window(mut self, window: usize) -> PowerSquelchBuilder<T> {
+        self.config.window = window;
+        self
+    }
+
+    /// Number of samples faded in and out at the start and end of bursts
+    pub fn ramp(mut self, ramp: usize) -> PowerSquelchBuilder<T> {
+        self.config.ramp = ramp;
+        self
+    }
+
+    /// Drop samples outside of bursts, instead of zeroing them
+    pub fn gate(mut self, gate: bool) -> PowerSquelchBuilder<T> {
+        self.config.gate = gate;
+        self
+    }
+
+    /// Create [`PowerSquelch`] block
+    pub fn build(self) -> Block {
+        PowerSquelch::<T>::with_config(self.config)
+    }
+}
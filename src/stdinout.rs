@@ -1,23 +1,195 @@
-//! ## Blocks related to stdin/stdout serialization
+//! ## Blocks related to raw byte-stream serialization
+//!
+//! Samples are exchanged as raw bytes with other processes, either through
+//! stdin/stdout, regular files, named pipes (FIFOs), Unix domain sockets or TCP.
+//!
+//! See [`crate::net`] for UDP and `rtl_tcp`.
 
 use core::marker::PhantomData;
-use futuresdr::blocks::Sink;
-use futuresdr::num_complex::Complex32;
+use futuresdr::log::{info, warn};
+use futuresdr::num_complex::Complex;
 use futuresdr::runtime::Block;
-use std::io::Write;
+use futuresdr::runtime::BlockMeta;
+use futuresdr::runtime::BlockMetaBuilder;
+use futuresdr::runtime::Kernel;
+use futuresdr::runtime::MessageIo;
+use futuresdr::runtime::MessageIoBuilder;
+use futuresdr::runtime::Result;
+use futuresdr::runtime::StreamIo;
+use futuresdr::runtime::StreamIoBuilder;
+use futuresdr::runtime::WorkIo;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, ErrorKind, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Default size in bytes of the buffer used to read or write the byte stream.
+pub const DEFAULT_BUFFER_SIZE: usize = 64 * 1024;
 
 enum StdDirection {
     In,
     Out,
 }
 
-enum BytesOrder {
+/// Order of the bytes of each item once serialized
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum BytesOrder {
+    /// Byte order of the host
+    #[default]
     Native,
     BigEndian,
     LittleEndian,
 }
 
-/// Build blocks to serialize/deserialized stream from stdin/stdout.
+/// Types that can be serialized into raw bytes with a given [`BytesOrder`].
+///
+/// Complex values are serialized as the real part followed by the imaginary part,
+/// i.e. the usual interleaved IQ layout.
+pub trait BytesOrdered: Copy + Send + Sync + 'static {
+    /// Number of bytes of one serialized item
+    const SIZE: usize;
+
+    /// Write the item into `dst`, which must be exactly [`Self::SIZE`] bytes long.
+    fn write_bytes(&self, order: BytesOrder, dst: &mut [u8]);
+
+    /// Read an item from `src`, which must be exactly [`Self::SIZE`] bytes long.
+    fn read_bytes(order: BytesOrder, src: &[u8]) -> Self;
+}
+
+macro_rules! impl_bytes_ordered {
+    ($($t:ty),*) => {
+        $(
+            impl BytesOrdered for $t {
+                const SIZE: usize = std::mem::size_of::<$t>();
+
+                #[inline(always)]
+                fn write_bytes(&self, order: BytesOrder, dst: &mut [u8]) {
+                    match order {
+                        BytesOrder::Native => dst.copy_from_slice(&self.to_ne_bytes()),
+                        BytesOrder::BigEndian => dst.copy_from_slice(&self.to_be_bytes()),
+                        BytesOrder::LittleEndian => dst.copy_from_slice(&self.to_le_bytes()),
+                    }
+                }
+
+                #[inline(always)]
+                fn read_bytes(order: BytesOrder, src: &[u8]) -> Self {
+                    let mut bytes = [0u8; std::mem::size_of::<$t>()];
+                    bytes.copy_from_slice(src);
+                    match order {
+                        BytesOrder::Native => <$t>::from_ne_bytes(bytes),
+                        BytesOrder::BigEndian => <$t>::from_be_bytes(bytes),
+                        BytesOrder::LittleEndian => <$t>::from_le_bytes(bytes),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_bytes_ordered!(u8, i8, u16, i16, u32, i32, u64, i64, f32, f64);
+
+impl<T: BytesOrdered> BytesOrdered for Complex<T> {
+    const SIZE: usize = 2 * T::SIZE;
+
+    #[inline(always)]
+    fn write_bytes(&self, order: BytesOrder, dst: &mut [u8]) {
+        let (re, im) = dst.split_at_mut(T::SIZE);
+        self.re.write_bytes(order, re);
+        self.im.write_bytes(order, im);
+    }
+
+    #[inline(always)]
+    fn read_bytes(order: BytesOrder, src: &[u8]) -> Self {
+        let (re, im) = src.split_at(T::SIZE);
+        Complex::new(T::read_bytes(order, re), T::read_bytes(order, im))
+    }
+}
+
+/// Where the raw bytes are read from or written to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ByteStreamEndpoint {
+    /// stdin for sources, stdout for sinks
+    Stdio,
+    /// Regular file, created or truncated by sinks
+    File(PathBuf),
+    /// Existing named pipe (see `mkfifo`).
+    /// Opening it blocks until the other end is opened too.
+    Fifo(PathBuf),
+    /// Unix domain stream socket, to which the block connects as a client
+    #[cfg(unix)]
+    UnixSocket(PathBuf),
+    /// TCP connection to a remote server, e.g. `"127.0.0.1:1234"`
+    /// (GNU Radio TCP Server Sink/Source)
+    TcpClient(String),
+    /// Listen on a local address and serve the first client that connects
+    /// (GNU Radio TCP Client Sink/Source)
+    TcpServer(String),
+}
+
+impl ByteStreamEndpoint {
+    fn is_socket(&self) -> bool {
+        #[cfg(unix)]
+        if let ByteStreamEndpoint::UnixSocket(_) = self {
+            return true;
+        }
+        matches!(
+            self,
+            ByteStreamEndpoint::TcpClient(_) | ByteStreamEndpoint::TcpServer(_)
+        )
+    }
+
+    fn accept_tcp(addr: &str) -> std::io::Result<TcpStream> {
+        let listener = TcpListener::bind(addr)?;
+        let (stream, peer) = listener.accept()?;
+        info!("accepted TCP connection from {}", peer);
+        Ok(stream)
+    }
+
+    fn open_reader(&self) -> std::io::Result<Box<dyn Read + Send>> {
+        Ok(match self {
+            ByteStreamEndpoint::Stdio => Box::new(std::io::stdin()),
+            ByteStreamEndpoint::File(path) | ByteStreamEndpoint::Fifo(path) => {
+                Box::new(File::open(path)?)
+            }
+            #[cfg(unix)]
+            ByteStreamEndpoint::UnixSocket(path) => {
+                Box::new(std::os::unix::net::UnixStream::connect(path)?)
+            }
+            ByteStreamEndpoint::TcpClient(addr) => Box::new(TcpStream::connect(addr)?),
+            ByteStreamEndpoint::TcpServer(addr) => Box::new(Self::accept_tcp(addr)?),
+        })
+    }
+
+    fn open_writer(&self) -> std::io::Result<Box<dyn Write + Send>> {
+        Ok(match self {
+            ByteStreamEndpoint::Stdio => Box::new(std::io::stdout()),
+            ByteStreamEndpoint::File(path) => Box::new(File::create(path)?),
+            ByteStreamEndpoint::Fifo(path) => Box::new(OpenOptions::new().write(true).open(path)?),
+            #[cfg(unix)]
+            ByteStreamEndpoint::UnixSocket(path) => {
+                Box::new(std::os::unix::net::UnixStream::connect(path)?)
+            }
+            ByteStreamEndpoint::TcpClient(addr) => Box::new(TcpStream::connect(addr)?),
+            ByteStreamEndpoint::TcpServer(addr) => Box::new(Self::accept_tcp(addr)?),
+        })
+    }
+}
+
+/// Errors meaning that the other end of the stream went away.
+fn is_disconnect(e: &std::io::Error) -> bool {
+    matches!(
+        e.kind(),
+        ErrorKind::BrokenPipe
+            | ErrorKind::ConnectionReset
+            | ErrorKind::ConnectionAborted
+            | ErrorKind::NotConnected
+            | ErrorKind::UnexpectedEof
+    )
+}
+
+/// Build blocks to serialize/deserialize streams from stdin/stdout, files, FIFOs,
+/// Unix sockets or TCP connections.
 /// It also takes care of endianness.
 ///
 /// # Usage
@@ -33,26 +205,63 @@ enum BytesOrder {
 /// # use fsdr_blocks::stdinout::StdInOutBuilder;
 /// let blk = StdInOutBuilder::<u8>::stdout().as_le().build();
 /// ```
+///
+/// Build a block that outputs a stream of `Complex32` as big endian IQ to stdout,
+/// writing by chunks of 1 MiB:
+/// ```
+/// # use fsdr_blocks::stdinout::StdInOutBuilder;
+/// # use futuresdr::num_complex::Complex32;
+/// let blk = StdInOutBuilder::<Complex32>::stdout()
+///     .as_be()
+///     .buffer_size(1024 * 1024)
+///     .build();
+/// ```
+///
+/// Build a block that reads little endian `i16` from a Unix socket,
+/// reconnecting every second when the peer goes away:
+/// ```
+/// # use fsdr_blocks::stdinout::{ByteStreamEndpoint, StdInOutBuilder};
+/// # use std::time::Duration;
+/// let blk = StdInOutBuilder::<i16>::read_from(ByteStreamEndpoint::UnixSocket("/tmp/iq.sock".into()))
+///     .as_le()
+///     .reconnect(Duration::from_secs(1))
+///     .build();
+/// ```
 pub struct StdInOutBuilder<A> {
     direction: StdDirection,
+    endpoint: ByteStreamEndpoint,
     marker_type: PhantomData<A>,
     bytes_order: BytesOrder,
+    buffer_size: usize,
+    reconnect: Option<Duration>,
 }
 
 impl<A> StdInOutBuilder<A> {
     pub fn stdin() -> StdInOutBuilder<A> {
+        StdInOutBuilder::read_from(ByteStreamEndpoint::Stdio)
+    }
+
+    pub fn stdout() -> StdInOutBuilder<A> {
+        StdInOutBuilder::write_to(ByteStreamEndpoint::Stdio)
+    }
+
+    /// Build a source reading samples from `endpoint`
+    pub fn read_from(endpoint: ByteStreamEndpoint) -> StdInOutBuilder<A> {
         StdInOutBuilder::<A> {
             marker_type: PhantomData,
             direction: StdDirection::In,
+            endpoint,
             bytes_order: BytesOrder::Native,
+            buffer_size: DEFAULT_BUFFER_SIZE,
+            reconnect: None,
         }
     }
 
-    pub fn stdout() -> StdInOutBuilder<A> {
+    /// Build a sink writing samples to `endpoint`
+    pub fn write_to(endpoint: ByteStreamEndpoint) -> StdInOutBuilder<A> {
         StdInOutBuilder::<A> {
-            marker_type: PhantomData,
             direction: StdDirection::Out,
-            bytes_order: BytesOrder::Native,
+            ..StdInOutBuilder::read_from(endpoint)
         }
     }
 
@@ -76,133 +285,337 @@ impl<A> StdInOutBuilder<A> {
             ..self
         }
     }
+
+    /// Size in bytes of the buffer accumulating samples before they are written,
+    /// or of the chunks read at once.
+    pub fn buffer_size(self, buffer_size: usize) -> StdInOutBuilder<A> {
+        StdInOutBuilder::<A> {
+            buffer_size,
+            ..self
+        }
+    }
+
+    /// For sockets (Unix or TCP) only: when the peer disconnects, or cannot be reached,
+    /// retry to connect after `delay` instead of finishing the block.
+    pub fn reconnect(self, delay: Duration) -> StdInOutBuilder<A> {
+        StdInOutBuilder::<A> {
+            reconnect: Some(delay),
+            ..self
+        }
+    }
 }
 
-impl StdInOutBuilder<u8> {
+impl<A: BytesOrdered> StdInOutBuilder<A> {
     pub fn build(self) -> Block {
         match self.direction {
-            StdDirection::Out => {
-                let mut stdout = std::io::stdout();
-                Sink::new(move |f: &i16| {
-                    stdout
-                        .write_all(&f.to_ne_bytes())
-                        .expect("cannot write to stdout");
-                    stdout.flush().expect("flush error on stdout");
-                })
-                .into()
+            StdDirection::In => ByteStreamSource::<A>::new(
+                self.endpoint,
+                self.bytes_order,
+                self.buffer_size,
+                self.reconnect,
+            ),
+            StdDirection::Out => ByteStreamSink::<A>::new(
+                self.endpoint,
+                self.bytes_order,
+                self.buffer_size,
+                self.reconnect,
+            ),
+        }
+    }
+}
+
+/// Read samples serialized with the given [`BytesOrder`] from a [`ByteStreamEndpoint`].
+///
+/// The endpoint is opened when the flowgraph starts.
+/// The block finishes at the end of the stream, unless it is a socket configured
+/// to reconnect, in which case it waits for the peer to come back.
+///
+/// # Outputs
+///
+/// `out`: deserialized samples
+///
+/// # Usage
+/// ```
+/// use fsdr_blocks::stdinout::{ByteStreamEndpoint, ByteStreamSource, BytesOrder};
+///
+/// let blk = ByteStreamSource::<f32>::new(
+///     ByteStreamEndpoint::Fifo("/tmp/iq.fifo".into()),
+///     BytesOrder::LittleEndian,
+///     4096,
+///     None,
+/// );
+/// ```
+pub struct ByteStreamSource<T: BytesOrdered> {
+    endpoint: ByteStreamEndpoint,
+    reader: Option<Box<dyn Read + Send>>,
+    bytes_order: BytesOrder,
+    reconnect: Option<Duration>,
+    bytes: Vec<u8>,
+    filled: usize,
+    _type: PhantomData<T>,
+}
+
+impl<T: BytesOrdered> ByteStreamSource<T> {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(
+        endpoint: ByteStreamEndpoint,
+        bytes_order: BytesOrder,
+        buffer_size: usize,
+        reconnect: Option<Duration>,
+    ) -> Block {
+        let reconnect = reconnect.filter(|_| endpoint.is_socket());
+        Block::new(
+            BlockMetaBuilder::new("ByteStreamSource").blocking().build(),
+            StreamIoBuilder::new().add_output::<T>("out").build(),
+            MessageIoBuilder::<Self>::new().build(),
+            ByteStreamSource::<T> {
+                endpoint,
+                reader: None,
+                bytes_order,
+                reconnect,
+                bytes: vec![0; buffer_size.max(T::SIZE)],
+                filled: 0,
+                _type: PhantomData,
+            },
+        )
+    }
+
+    /// Returns `true` if the endpoint is (again) open.
+    fn try_open(&mut self, io: &mut WorkIo) -> Result<bool> {
+        match self.endpoint.open_reader() {
+            Ok(r) => {
+                info!("ByteStreamSource: connected to {:?}", self.endpoint);
+                self.reader = Some(r);
+                Ok(true)
             }
-            _ => todo!("stdin not yet implemented"),
+            Err(e) => match self.reconnect {
+                Some(delay) => {
+                    warn!("ByteStreamSource: cannot open {:?}: {}", self.endpoint, e);
+                    std::thread::sleep(delay);
+                    io.call_again = true;
+                    Ok(false)
+                }
+                None => Err(e.into()),
+            },
         }
     }
 }
 
-impl StdInOutBuilder<i16> {
-    pub fn build(self) -> Block {
-        match self.direction {
-            StdDirection::Out => {
-                let mut stdout = std::io::stdout();
-                match self.bytes_order {
-                    BytesOrder::Native => Sink::new(move |f: &i16| {
-                        stdout
-                            .write_all(&f.to_ne_bytes())
-                            .expect("cannot write to stdout");
-                        stdout.flush().expect("flush error on stdout");
-                    })
-                    .into(),
-                    BytesOrder::LittleEndian => Sink::new(move |f: &i16| {
-                        stdout
-                            .write_all(&f.to_le_bytes())
-                            .expect("cannot write to stdout");
-                        stdout.flush().expect("flush error on stdout");
-                    })
-                    .into(),
-                    BytesOrder::BigEndian => Sink::new(move |f: &i16| {
-                        stdout
-                            .write_all(&f.to_be_bytes())
-                            .expect("cannot write to stdout");
-                        stdout.flush().expect("flush error on stdout");
-                    })
-                    .into(),
+#[doc(hidden)]
+#[async_trait]
+impl<T: BytesOrdered> Kernel for ByteStreamSource<T> {
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        sio: &mut StreamIo,
+        _mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        if self.reader.is_none() && !self.try_open(io)? {
+            return Ok(());
+        }
+
+        let o = sio.output(0).slice::<T>();
+        if o.is_empty() {
+            return Ok(());
+        }
+
+        let n_items = o.len().min(self.bytes.len() / T::SIZE);
+        let reader = self.reader.as_mut().unwrap();
+        match reader.read(&mut self.bytes[self.filled..n_items * T::SIZE]) {
+            Ok(0) => {
+                self.reader = None;
+                self.filled = 0;
+                if self.reconnect.is_some() {
+                    warn!("ByteStreamSource: {:?} disconnected", self.endpoint);
+                    io.call_again = true;
+                } else {
+                    io.finished = true;
+                }
+            }
+            Ok(n) => {
+                self.filled += n;
+                let produced = self.filled / T::SIZE;
+                let bytes = &self.bytes[..produced * T::SIZE];
+                for (v, src) in o[..produced].iter_mut().zip(bytes.chunks_exact(T::SIZE)) {
+                    *v = T::read_bytes(self.bytes_order, src);
                 }
+                self.bytes.copy_within(produced * T::SIZE..self.filled, 0);
+                self.filled -= produced * T::SIZE;
+                sio.output(0).produce(produced);
+                io.call_again = true;
             }
-            _ => todo!("stdin not yet implemented"),
+            Err(e) if e.kind() == ErrorKind::Interrupted => io.call_again = true,
+            Err(e) if is_disconnect(&e) => {
+                self.reader = None;
+                self.filled = 0;
+                if self.reconnect.is_some() {
+                    warn!("ByteStreamSource: {:?} disconnected: {}", self.endpoint, e);
+                    io.call_again = true;
+                } else {
+                    io.finished = true;
+                }
+            }
+            Err(e) => return Err(e.into()),
         }
+
+        Ok(())
     }
 }
 
-impl StdInOutBuilder<f32> {
-    pub fn build(self) -> Block {
-        match self.direction {
-            StdDirection::Out => {
-                let mut stdout = std::io::stdout();
-                match self.bytes_order {
-                    BytesOrder::Native => Sink::new(move |f: &f32| {
-                        stdout
-                            .write_all(&f.to_ne_bytes())
-                            .expect("cannot write to stdout");
-                        stdout.flush().expect("flush error on stdout");
-                    })
-                    .into(),
-                    BytesOrder::LittleEndian => Sink::new(move |f: &f32| {
-                        stdout
-                            .write_all(&f.to_le_bytes())
-                            .expect("cannot write to stdout");
-                        stdout.flush().expect("flush error on stdout");
-                    })
-                    .into(),
-                    BytesOrder::BigEndian => Sink::new(move |f: &f32| {
-                        stdout
-                            .write_all(&f.to_be_bytes())
-                            .expect("cannot write to stdout");
-                        stdout.flush().expect("flush error on stdout");
-                    })
-                    .into(),
+/// Write samples to a [`ByteStreamEndpoint`] with the given [`BytesOrder`].
+///
+/// Whole input slices are serialized at once into a buffer of configurable size,
+/// that is only flushed when full or at the end of the stream.
+/// The block runs on its own thread, so that a slow reader on the other end of a pipe
+/// applies backpressure to the flowgraph instead of stalling the async executor.
+/// If the reader goes away (broken pipe), the block finishes gracefully, unless it is a
+/// socket configured to reconnect: samples are then held back until the peer is back,
+/// and those still buffered when the disconnection happened are lost.
+///
+/// # Inputs
+///
+/// `in`: samples written to the endpoint
+///
+/// # Usage
+/// ```
+/// use fsdr_blocks::stdinout::{ByteStreamEndpoint, ByteStreamSink, BytesOrder};
+///
+/// let blk = ByteStreamSink::<i16>::new(ByteStreamEndpoint::Stdio, BytesOrder::LittleEndian, 4096, None);
+/// ```
+pub struct ByteStreamSink<T: BytesOrdered> {
+    endpoint: ByteStreamEndpoint,
+    writer: Option<BufWriter<Box<dyn Write + Send>>>,
+    bytes_order: BytesOrder,
+    buffer_size: usize,
+    reconnect: Option<Duration>,
+    bytes: Vec<u8>,
+    _type: PhantomData<T>,
+}
+
+impl<T: BytesOrdered> ByteStreamSink<T> {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(
+        endpoint: ByteStreamEndpoint,
+        bytes_order: BytesOrder,
+        buffer_size: usize,
+        reconnect: Option<Duration>,
+    ) -> Block {
+        let reconnect = reconnect.filter(|_| endpoint.is_socket());
+        Block::new(
+            BlockMetaBuilder::new("ByteStreamSink").blocking().build(),
+            StreamIoBuilder::new().add_input::<T>("in").build(),
+            MessageIoBuilder::<Self>::new().build(),
+            ByteStreamSink::<T> {
+                endpoint,
+                writer: None,
+                bytes_order,
+                buffer_size,
+                reconnect,
+                bytes: Vec::new(),
+                _type: PhantomData,
+            },
+        )
+    }
+
+    /// Returns `true` if the endpoint is (again) open.
+    fn try_open(&mut self, io: &mut WorkIo) -> Result<bool> {
+        match self.endpoint.open_writer() {
+            Ok(w) => {
+                info!("ByteStreamSink: connected to {:?}", self.endpoint);
+                self.writer = Some(BufWriter::with_capacity(self.buffer_size, w));
+                Ok(true)
+            }
+            Err(e) => match self.reconnect {
+                Some(delay) => {
+                    warn!("ByteStreamSink: cannot open {:?}: {}", self.endpoint, e);
+                    std::thread::sleep(delay);
+                    io.call_again = true;
+                    Ok(false)
+                }
+                None => Err(e.into()),
+            },
+        }
+    }
+
+    /// Handle the peer going away, either by finishing or waiting for a reconnection.
+    fn handle_write_result(&mut self, res: std::io::Result<()>, io: &mut WorkIo) -> Result<()> {
+        match res {
+            Err(e) if is_disconnect(&e) => {
+                // discard buffered data instead of flushing it again on drop
+                if let Some(w) = self.writer.take() {
+                    let _ = w.into_parts();
+                }
+                if self.reconnect.is_some() {
+                    warn!("ByteStreamSink: {:?} disconnected: {}", self.endpoint, e);
+                    io.call_again = true;
+                } else {
+                    io.finished = true;
                 }
+                Ok(())
             }
-            _ => todo!("stdin not yet implemented"),
+            res => Ok(res?),
         }
     }
 }
 
-impl StdInOutBuilder<Complex32> {
-    pub fn build(self) -> Block {
-        match self.direction {
-            StdDirection::Out => {
-                let mut stdout = std::io::stdout();
-                match self.bytes_order {
-                    BytesOrder::Native => Sink::new(move |f: &Complex32| {
-                        stdout
-                            .write_all(&f.re.to_ne_bytes())
-                            .expect("cannot write to stdout");
-                        stdout
-                            .write_all(&f.im.to_ne_bytes())
-                            .expect("cannot write to stdout");
-                        stdout.flush().expect("flush error on stdout");
-                    })
-                    .into(),
-                    BytesOrder::LittleEndian => Sink::new(move |f: &Complex32| {
-                        stdout
-                            .write_all(&f.re.to_le_bytes())
-                            .expect("cannot write to stdout");
-                        stdout
-                            .write_all(&f.im.to_le_bytes())
-                            .expect("cannot write to stdout");
-                        stdout.flush().expect("flush error on stdout");
-                    })
-                    .into(),
-                    BytesOrder::BigEndian => Sink::new(move |f: &Complex32| {
-                        stdout
-                            .write_all(&f.re.to_be_bytes())
-                            .expect("cannot write to stdout");
-                        stdout
-                            .write_all(&f.im.to_be_bytes())
-                            .expect("cannot write to stdout");
-                        stdout.flush().expect("flush error on stdout");
-                    })
-                    .into(),
-                }
+#[doc(hidden)]
+#[async_trait]
+impl<T: BytesOrdered> Kernel for ByteStreamSink<T> {
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        sio: &mut StreamIo,
+        _mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let i = sio.input(0).slice::<T>();
+
+        if i.is_empty() && sio.input(0).finished() {
+            if let Some(w) = self.writer.as_mut() {
+                let res = w.flush();
+                self.handle_write_result(res, io)?;
             }
-            _ => todo!("stdin not yet implemented"),
+            io.finished = true;
+            return Ok(());
+        }
+
+        if self.writer.is_none() && !self.try_open(io)? {
+            return Ok(());
+        }
+
+        if !i.is_empty() {
+            self.bytes.resize(i.len() * T::SIZE, 0);
+            for (v, dst) in i.iter().zip(self.bytes.chunks_exact_mut(T::SIZE)) {
+                v.write_bytes(self.bytes_order, dst);
+            }
+            let res = self.writer.as_mut().unwrap().write_all(&self.bytes);
+            sio.input(0).consume(i.len());
+            self.handle_write_result(res, io)?;
+        }
+
+        if sio.input(0).finished() {
+            io.call_again = true;
+        }
+
+        Ok(())
+    }
+
+    async fn deinit(
+        &mut self,
+        _sio: &mut StreamIo,
+        _mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        if let Some(w) = self.writer.as_mut() {
+            let res = w.flush();
+            let mut io = WorkIo {
+                call_again: false,
+                finished: false,
+                block_on: None,
+            };
+            self.handle_write_result(res, &mut io)?;
         }
+        Ok(())
     }
 }
@@ -1,4 +1,8 @@
 //! ## Blocks related to operation on stream themselves
 mod deinterleave;
+mod overflow_policy;
+mod tagged_batch;
 
 pub use deinterleave::Deinterleave;
+pub use overflow_policy::{OverflowPolicy, OVERFLOW_TAG_KEY};
+pub use tagged_batch::TaggedBatch;
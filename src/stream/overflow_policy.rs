@@ -0,0 +1,16 @@
+/// What a sink does with new samples when its downstream consumer (e.g. a bounded channel)
+/// is full.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum OverflowPolicy {
+    /// Wait for the consumer, applying backpressure to the flowgraph
+    Block,
+    /// Discard the new samples
+    #[default]
+    DropNewest,
+    /// Discard the oldest pending samples to make room for the new ones
+    DropOldest,
+}
+
+/// Key of the `Tag::NamedUsize` marking the first sample following dropped samples,
+/// with the number of samples dropped.
+pub const OVERFLOW_TAG_KEY: &str = "overflow";
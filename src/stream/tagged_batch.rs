@@ -0,0 +1,37 @@
+use futuresdr::runtime::ItemTag;
+
+/// A batch of samples along with their tags, to move a piece of stream
+/// out of a flowgraph, e.g. through a channel, without losing its tags.
+///
+/// The `index` of each tag is relative to the first sample of the batch.
+#[derive(Debug, Clone)]
+pub struct TaggedBatch<T> {
+    pub samples: Box<[T]>,
+    pub tags: Vec<ItemTag>,
+}
+
+impl<T> TaggedBatch<T> {
+    pub fn new(samples: Box<[T]>, tags: Vec<ItemTag>) -> TaggedBatch<T> {
+        TaggedBatch { samples, tags }
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+}
+
+impl<T> From<Box<[T]>> for TaggedBatch<T> {
+    fn from(samples: Box<[T]>) -> Self {
+        TaggedBatch::new(samples, Vec::new())
+    }
+}
+
+impl<T> From<Vec<T>> for TaggedBatch<T> {
+    fn from(samples: Vec<T>) -> Self {
+        TaggedBatch::new(samples.into_boxed_slice(), Vec::new())
+    }
+}
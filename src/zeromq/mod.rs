@@ -0,0 +1,271 @@
+//! ## Blocks related to ZeroMQ
+//!
+//! Samples are exchanged with other processes through ZeroMQ sockets, in the format used by
+//! GNU Radio's gr-zeromq blocks: each message carries raw samples, optionally preceded by a
+//! tag header, and for PUB sockets optionally preceded by a topic frame.
+//! Tags are serialized with [`crate::serde_pmt::gr_pmt`].
+mod zmq_sink;
+mod zmq_source;
+
+pub use zmq::SocketType;
+pub use zmq_sink::ZmqSink;
+pub use zmq_source::ZmqSource;
+
+use crate::serde_pmt::gr_pmt::{from_gr_bytes, to_gr_bytes};
+use crate::stdinout::{BytesOrder, BytesOrdered};
+use core::marker::PhantomData;
+use futuresdr::anyhow::{anyhow, bail};
+use futuresdr::log::debug;
+use futuresdr::runtime::{Block, Pmt, Result, Tag};
+use std::collections::HashMap;
+
+/// Default timeout in milliseconds when waiting on a socket, as in GNU Radio
+pub const DEFAULT_TIMEOUT: i64 = 100;
+
+const GR_HEADER_MAGIC: u16 = 0x5FF0;
+const GR_HEADER_VERSION: u8 = 0x01;
+
+#[derive(Clone)]
+pub(crate) struct ZmqConfig {
+    socket_type: SocketType,
+    address: String,
+    bind: bool,
+    bytes_order: BytesOrder,
+    pass_tags: bool,
+    topic: Option<String>,
+    timeout: i64,
+}
+
+impl ZmqConfig {
+    fn is_sink(&self) -> bool {
+        matches!(self.socket_type, SocketType::PUB | SocketType::PUSH)
+    }
+
+    fn socket(&self) -> Result<zmq::Socket> {
+        let socket = zmq::Context::new().socket(self.socket_type)?;
+        if self.socket_type == SocketType::SUB {
+            socket.set_subscribe(self.topic.as_deref().unwrap_or("").as_bytes())?;
+        }
+        if self.bind {
+            socket.bind(&self.address)?;
+        } else {
+            socket.connect(&self.address)?;
+        }
+        Ok(socket)
+    }
+}
+
+/// Build blocks exchanging samples through ZeroMQ sockets, compatible with gr-zeromq.
+/// It also takes care of endianness.
+///
+/// As in GNU Radio, sinks bind and sources connect by default.
+///
+/// # Usage
+///
+/// Publish `Complex32` samples along with their tags:
+/// ```
+/// # use fsdr_blocks::zeromq::ZmqBuilder;
+/// # use futuresdr::num_complex::Complex32;
+/// let blk = ZmqBuilder::<Complex32>::publisher("tcp://127.0.0.1:5555")
+///     .pass_tags(true)
+///     .build();
+/// ```
+///
+/// Receive `f32` samples from a GNU Radio PUSH sink:
+/// ```
+/// # use fsdr_blocks::zeromq::ZmqBuilder;
+/// let blk = ZmqBuilder::<f32>::puller("tcp://127.0.0.1:5556").build();
+/// ```
+pub struct ZmqBuilder<A> {
+    config: ZmqConfig,
+    marker_type: PhantomData<A>,
+}
+
+impl<A> ZmqBuilder<A> {
+    /// Build a sink or a source, depending on `socket_type`.
+    ///
+    /// Fails if `socket_type` is not one of `PUB`, `PUSH`, `SUB` or `PULL`.
+    pub fn new(socket_type: SocketType, address: &str) -> Result<ZmqBuilder<A>> {
+        match socket_type {
+            SocketType::PUB | SocketType::PUSH | SocketType::SUB | SocketType::PULL => {
+                Ok(ZmqBuilder::with_socket_type(socket_type, address))
+            }
+            t => bail!("unsupported ZeroMQ socket type {:?}", t),
+        }
+    }
+
+    fn with_socket_type(socket_type: SocketType, address: &str) -> ZmqBuilder<A> {
+        ZmqBuilder::<A> {
+            config: ZmqConfig {
+                socket_type,
+                address: address.to_string(),
+                bind: matches!(socket_type, SocketType::PUB | SocketType::PUSH),
+                bytes_order: BytesOrder::Native,
+                pass_tags: false,
+                topic: None,
+                timeout: DEFAULT_TIMEOUT,
+            },
+            marker_type: PhantomData,
+        }
+    }
+
+    /// Sink publishing samples to all subscribers
+    pub fn publisher(address: &str) -> ZmqBuilder<A> {
+        ZmqBuilder::with_socket_type(SocketType::PUB, address)
+    }
+
+    /// Sink pushing samples to one of the pullers
+    pub fn pusher(address: &str) -> ZmqBuilder<A> {
+        ZmqBuilder::with_socket_type(SocketType::PUSH, address)
+    }
+
+    /// Source subscribing to a publisher
+    pub fn subscriber(address: &str) -> ZmqBuilder<A> {
+        ZmqBuilder::with_socket_type(SocketType::SUB, address)
+    }
+
+    /// Source pulling samples from pushers
+    pub fn puller(address: &str) -> ZmqBuilder<A> {
+        ZmqBuilder::with_socket_type(SocketType::PULL, address)
+    }
+
+    /// Bind to the address instead of connecting to it, or the other way around.
+    pub fn bind(mut self, bind: bool) -> ZmqBuilder<A> {
+        self.config.bind = bind;
+        self
+    }
+
+    pub fn as_ne(mut self) -> ZmqBuilder<A> {
+        self.config.bytes_order = BytesOrder::Native;
+        self
+    }
+
+    pub fn as_le(mut self) -> ZmqBuilder<A> {
+        self.config.bytes_order = BytesOrder::LittleEndian;
+        self
+    }
+
+    pub fn as_be(mut self) -> ZmqBuilder<A> {
+        self.config.bytes_order = BytesOrder::BigEndian;
+        self
+    }
+
+    /// Send or expect a tag header before the samples of each message.
+    pub fn pass_tags(mut self, pass_tags: bool) -> ZmqBuilder<A> {
+        self.config.pass_tags = pass_tags;
+        self
+    }
+
+    /// For publishers, the topic frame preceding each message.
+    /// For subscribers, the topic filter.
+    pub fn topic(mut self, topic: &str) -> ZmqBuilder<A> {
+        self.config.topic = Some(topic.to_string());
+        self
+    }
+
+    /// Timeout in milliseconds when waiting on the socket
+    pub fn timeout(mut self, timeout: i64) -> ZmqBuilder<A> {
+        self.config.timeout = timeout;
+        self
+    }
+}
+
+impl<A: BytesOrdered> ZmqBuilder<A> {
+    pub fn build(self) -> Block {
+        if self.config.is_sink() {
+            ZmqSink::<A>::with_config(self.config)
+        } else {
+            ZmqSource::<A>::with_config(self.config)
+        }
+    }
+}
+
+/// Key and value of a tag, as seen by GNU Radio
+fn tag_to_pmt(tag: &Tag) -> Option<(String, Pmt)> {
+    match tag {
+        Tag::Id(id) => Some(("id".to_string(), Pmt::U64(*id))),
+        Tag::String(s) => Some(("string".to_string(), Pmt::String(s.clone()))),
+        Tag::Data(p) => Some(("data".to_string(), p.clone())),
+        Tag::NamedF32(k, v) => Some((k.clone(), Pmt::F32(*v))),
+        Tag::NamedUsize(k, v) => Some((k.clone(), Pmt::Usize(*v))),
+        _ => None,
+    }
+}
+
+fn pmt_to_tag(key: String, value: Pmt) -> Tag {
+    match (key.as_str(), value) {
+        ("id", Pmt::U64(id)) => Tag::Id(id),
+        ("string", Pmt::String(s)) => Tag::String(s),
+        ("data", p) => Tag::Data(p),
+        (_, Pmt::F32(v)) => Tag::NamedF32(key, v),
+        (_, Pmt::F64(v)) => Tag::NamedF32(key, v as f32),
+        (_, Pmt::U32(v)) => Tag::NamedUsize(key, v as usize),
+        (_, Pmt::U64(v)) => Tag::NamedUsize(key, v as usize),
+        (_, Pmt::Usize(v)) => Tag::NamedUsize(key, v),
+        (_, p) => Tag::Data(Pmt::MapStrPmt(HashMap::from([(key, p)]))),
+    }
+}
+
+/// Append a gr-zeromq tag header to `out`.
+/// `offset` is the absolute index of the first sample of the message,
+/// and tags are given with their absolute index.
+pub(crate) fn write_tag_header(offset: u64, tags: &[(u64, &Tag)], out: &mut Vec<u8>) -> Result<()> {
+    let tags: Vec<(u64, String, Pmt)> = tags
+        .iter()
+        .filter_map(|(o, t)| match tag_to_pmt(t) {
+            Some((k, v)) => Some((*o, k, v)),
+            None => {
+                debug!("ZeroMQ: cannot serialize tag {:?}", t);
+                None
+            }
+        })
+        .collect();
+
+    out.extend_from_slice(&GR_HEADER_MAGIC.to_le_bytes());
+    out.push(GR_HEADER_VERSION);
+    out.extend_from_slice(&offset.to_le_bytes());
+    out.extend_from_slice(&(tags.len() as u64).to_le_bytes());
+    for (o, k, v) in tags {
+        out.extend_from_slice(&o.to_le_bytes());
+        to_gr_bytes(&Pmt::String(k), out)?;
+        to_gr_bytes(&v, out)?;
+        // source id
+        to_gr_bytes(&Pmt::Bool(false), out)?;
+    }
+    Ok(())
+}
+
+/// Parse a gr-zeromq tag header at the beginning of `input`, advancing it to the samples.
+/// Returns the absolute index of the first sample and the tags with their absolute index.
+pub(crate) fn read_tag_header(input: &mut &[u8]) -> Result<(u64, Vec<(u64, Tag)>)> {
+    fn take_u64(input: &mut &[u8]) -> Result<u64> {
+        if input.len() < 8 {
+            bail!("truncated ZeroMQ tag header");
+        }
+        let (head, tail) = input.split_at(8);
+        *input = tail;
+        Ok(u64::from_le_bytes(head.try_into().unwrap()))
+    }
+
+    if input.len() < 3
+        || u16::from_le_bytes([input[0], input[1]]) != GR_HEADER_MAGIC
+        || input[2] != GR_HEADER_VERSION
+    {
+        bail!("invalid ZeroMQ tag header");
+    }
+    *input = &input[3..];
+    let offset = take_u64(input)?;
+    let ntags = take_u64(input)?;
+    let mut tags = Vec::new();
+    for _ in 0..ntags {
+        let o = take_u64(input)?;
+        let key = match from_gr_bytes(input).map_err(|e| anyhow!("{}", e))? {
+            Pmt::String(k) => k,
+            p => bail!("invalid tag key {:?}", p),
+        };
+        let value = from_gr_bytes(input).map_err(|e| anyhow!("{}", e))?;
+        let _srcid = from_gr_bytes(input).map_err(|e| anyhow!("{}", e))?;
+        tags.push((o, pmt_to_tag(key, value)));
+    }
+    Ok((offset, tags))
+}
@@ -0,0 +1,134 @@
+use super::{write_tag_header, ZmqBuilder, ZmqConfig};
+use crate::stdinout::BytesOrdered;
+use core::marker::PhantomData;
+use futuresdr::anyhow::bail;
+use futuresdr::runtime::Block;
+use futuresdr::runtime::BlockMeta;
+use futuresdr::runtime::BlockMetaBuilder;
+use futuresdr::runtime::Kernel;
+use futuresdr::runtime::MessageIo;
+use futuresdr::runtime::MessageIoBuilder;
+use futuresdr::runtime::Result;
+use futuresdr::runtime::StreamIo;
+use futuresdr::runtime::StreamIoBuilder;
+use futuresdr::runtime::WorkIo;
+
+/// Send samples through a ZeroMQ PUB or PUSH socket.
+///
+/// Each call sends all available samples in one message. With tags enabled, the message
+/// starts with a gr-zeromq tag header holding the tags of these samples.
+/// A PUSH socket without peer holds the samples back until one connects.
+/// The socket is opened when the flowgraph starts.
+///
+/// See [`ZmqBuilder`] for the available options.
+///
+/// # Inputs
+///
+/// `in`: samples to send
+///
+/// # Usage
+/// ```
+/// use fsdr_blocks::zeromq::{SocketType, ZmqSink};
+///
+/// let blk = ZmqSink::<u8>::new(SocketType::PUSH, "tcp://127.0.0.1:5557").unwrap();
+/// ```
+pub struct ZmqSink<T: BytesOrdered> {
+    config: ZmqConfig,
+    socket: Option<zmq::Socket>,
+    offset: u64,
+    message: Vec<u8>,
+    _type: PhantomData<T>,
+}
+
+impl<T: BytesOrdered> ZmqSink<T> {
+    /// Fails if `socket_type` is not PUB or PUSH.
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(socket_type: zmq::SocketType, address: &str) -> Result<Block> {
+        let builder = ZmqBuilder::<T>::new(socket_type, address)?;
+        if !builder.config.is_sink() {
+            bail!("{:?} is not a ZeroMQ sink socket type", socket_type);
+        }
+        Ok(builder.build())
+    }
+
+    pub(crate) fn with_config(config: ZmqConfig) -> Block {
+        Block::new(
+            BlockMetaBuilder::new("ZmqSink").blocking().build(),
+            StreamIoBuilder::new().add_input::<T>("in").build(),
+            MessageIoBuilder::<Self>::new().build(),
+            ZmqSink::<T> {
+                config,
+                socket: None,
+                offset: 0,
+                message: Vec::new(),
+                _type: PhantomData,
+            },
+        )
+    }
+}
+
+#[doc(hidden)]
+#[async_trait]
+impl<T: BytesOrdered> Kernel for ZmqSink<T> {
+    async fn init(
+        &mut self,
+        _sio: &mut StreamIo,
+        _mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        self.socket = Some(self.config.socket()?);
+        Ok(())
+    }
+
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        sio: &mut StreamIo,
+        _mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let i = sio.input(0).slice::<T>();
+
+        if !i.is_empty() {
+            let socket = self.socket.as_ref().unwrap();
+            if socket.poll(zmq::POLLOUT, self.config.timeout)? == 0 {
+                io.call_again = true;
+                return Ok(());
+            }
+
+            self.message.clear();
+            if self.config.pass_tags {
+                let tags: Vec<(u64, &_)> = sio
+                    .input(0)
+                    .tags()
+                    .iter()
+                    .filter(|t| t.index < i.len())
+                    .map(|t| (self.offset + t.index as u64, &t.tag))
+                    .collect();
+                write_tag_header(self.offset, &tags, &mut self.message)?;
+            }
+            let start = self.message.len();
+            self.message.resize(start + i.len() * T::SIZE, 0);
+            for (v, dst) in i
+                .iter()
+                .zip(self.message[start..].chunks_exact_mut(T::SIZE))
+            {
+                v.write_bytes(self.config.bytes_order, dst);
+            }
+
+            if let Some(topic) = self.config.topic.as_ref() {
+                socket.send(topic.as_bytes(), zmq::SNDMORE)?;
+            }
+            socket.send(&self.message, 0)?;
+
+            self.offset += i.len() as u64;
+            sio.input(0).consume(i.len());
+        }
+
+        if sio.input(0).finished() {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
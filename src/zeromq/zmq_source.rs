@@ -0,0 +1,159 @@
+use super::{read_tag_header, ZmqBuilder, ZmqConfig};
+use crate::stdinout::BytesOrdered;
+use core::marker::PhantomData;
+use futuresdr::anyhow::bail;
+use futuresdr::log::warn;
+use futuresdr::runtime::Block;
+use futuresdr::runtime::BlockMeta;
+use futuresdr::runtime::BlockMetaBuilder;
+use futuresdr::runtime::Kernel;
+use futuresdr::runtime::MessageIo;
+use futuresdr::runtime::MessageIoBuilder;
+use futuresdr::runtime::Result;
+use futuresdr::runtime::StreamIo;
+use futuresdr::runtime::StreamIoBuilder;
+use futuresdr::runtime::Tag;
+use futuresdr::runtime::WorkIo;
+
+/// Receive samples from a ZeroMQ SUB or PULL socket.
+///
+/// With tags enabled, each message is expected to start with a gr-zeromq tag header,
+/// whose tags are attached to the received samples.
+/// Topic frames preceding the samples are skipped.
+/// The socket is opened when the flowgraph starts and the block never finishes on its own.
+///
+/// See [`ZmqBuilder`] for the available options.
+///
+/// # Outputs
+///
+/// `out`: received samples
+///
+/// # Usage
+/// ```
+/// use fsdr_blocks::zeromq::{SocketType, ZmqSource};
+///
+/// let blk = ZmqSource::<u8>::new(SocketType::PULL, "tcp://127.0.0.1:5557").unwrap();
+/// ```
+pub struct ZmqSource<T: BytesOrdered> {
+    config: ZmqConfig,
+    socket: Option<zmq::Socket>,
+    pending: Vec<T>,
+    pending_tags: Vec<(usize, Tag)>,
+    pending_offset: usize,
+    _type: PhantomData<T>,
+}
+
+impl<T: BytesOrdered> ZmqSource<T> {
+    /// Fails if `socket_type` is not SUB or PULL.
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(socket_type: zmq::SocketType, address: &str) -> Result<Block> {
+        let builder = ZmqBuilder::<T>::new(socket_type, address)?;
+        if builder.config.is_sink() {
+            bail!("{:?} is not a ZeroMQ source socket type", socket_type);
+        }
+        Ok(builder.build())
+    }
+
+    pub(crate) fn with_config(config: ZmqConfig) -> Block {
+        Block::new(
+            BlockMetaBuilder::new("ZmqSource").blocking().build(),
+            StreamIoBuilder::new().add_output::<T>("out").build(),
+            MessageIoBuilder::<Self>::new().build(),
+            ZmqSource::<T> {
+                config,
+                socket: None,
+                pending: Vec::new(),
+                pending_tags: Vec::new(),
+                pending_offset: 0,
+                _type: PhantomData,
+            },
+        )
+    }
+
+    /// Wait for the next message and deserialize it into `pending`.
+    fn receive(&mut self) -> Result<()> {
+        let socket = self.socket.as_ref().unwrap();
+        if socket.poll(zmq::POLLIN, self.config.timeout)? == 0 {
+            return Ok(());
+        }
+        let frames = socket.recv_multipart(0)?;
+        let Some(frame) = frames.last() else {
+            return Ok(());
+        };
+
+        let mut data = frame.as_slice();
+        self.pending_tags.clear();
+        if self.config.pass_tags {
+            match read_tag_header(&mut data) {
+                Ok((offset, tags)) => {
+                    self.pending_tags = tags
+                        .into_iter()
+                        .filter_map(|(o, t)| o.checked_sub(offset).map(|index| (index as usize, t)))
+                        .collect();
+                }
+                Err(e) => {
+                    warn!("ZmqSource: dropping message: {}", e);
+                    return Ok(());
+                }
+            }
+        }
+
+        let trailing = data.len() % T::SIZE;
+        if trailing != 0 {
+            warn!("ZmqSource: dropping {} trailing bytes", trailing);
+        }
+        self.pending.clear();
+        self.pending.extend(
+            data.chunks_exact(T::SIZE)
+                .map(|b| T::read_bytes(self.config.bytes_order, b)),
+        );
+        self.pending_offset = 0;
+        Ok(())
+    }
+}
+
+#[doc(hidden)]
+#[async_trait]
+impl<T: BytesOrdered> Kernel for ZmqSource<T> {
+    async fn init(
+        &mut self,
+        _sio: &mut StreamIo,
+        _mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        self.socket = Some(self.config.socket()?);
+        Ok(())
+    }
+
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        sio: &mut StreamIo,
+        _mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        if self.pending_offset == self.pending.len() {
+            self.receive()?;
+        }
+
+        let o = sio.output(0).slice::<T>();
+        let pending = &self.pending[self.pending_offset..];
+        let n = o.len().min(pending.len());
+        if n > 0 {
+            o[..n].copy_from_slice(&pending[..n]);
+            let range = self.pending_offset..self.pending_offset + n;
+            for (index, tag) in self.pending_tags.iter() {
+                if range.contains(index) {
+                    sio.output(0)
+                        .add_tag(index - self.pending_offset, tag.clone());
+                }
+            }
+            self.pending_offset += n;
+            sio.output(0).produce(n);
+        }
+
+        // with a full output buffer, wait for downstream to consume
+        io.call_again = !o.is_empty();
+        Ok(())
+    }
+}
@@ -0,0 +1,365 @@
+use crate::message::MessageCapture;
+use fsdr_blocks::agc::{Agc, AgcBuilder, AgcConfig, AgcMode, AgcState, HardwareGain};
+use fsdr_blocks::serde_pmt::config::ConfigError;
+use fsdr_blocks::serde_pmt::{from_pmt, to_pmt};
+use futuresdr::async_io::block_on;
+use futuresdr::blocks::{NullSink, Source, VectorSink, VectorSinkBuilder, VectorSource};
+use futuresdr::macros::connect;
+use futuresdr::num_complex::Complex32;
+use futuresdr::runtime::{Block, Flowgraph, Pmt, Result, Runtime};
+use std::collections::HashMap;
+
+#[test]
+fn agc_config() -> Result<()> {
+    let mut fg = Flowgraph::new();
+    let src = Block::from_typed(Source::new(|| 0.5f32));
+    let agc = Agc::<f32>::new(0.0, 65536.0, 1.0, 0.01, 1.0, true, false);
+    let snk = NullSink::<f32>::new();
+    connect!(fg, src > agc > snk);
+
+    let rt = Runtime::new();
+    block_on(async move {
+        let (task, mut handle) = rt.start(fg).await;
+
+        let config = handle.callback(agc, "get_config", Pmt::Null).await?;
+        let config = from_pmt::<AgcConfig>(config)?;
+        assert_eq!(config.max_gain, 65536.0);
+        assert_eq!(config.gain, 1.0);
+        assert!(config.gain_lock);
+
+        // single fields, with integers coerced to floats
+        let ret = handle.callback(agc, "max_gain", Pmt::U32(10)).await?;
+        assert_eq!(ret, Pmt::Ok);
+        let ret = handle.callback(agc, "auto_lock", Pmt::Bool(true)).await?;
+        assert_eq!(ret, Pmt::Ok);
+
+        // partial updates of the whole config
+        let update = Pmt::MapStrPmt(HashMap::from([
+            ("gain".to_string(), Pmt::F32(2.0)),
+            ("reference_power".to_string(), Pmt::F64(0.5)),
+        ]));
+        let ret = handle.callback(agc, "config", update).await?;
+        assert_eq!(ret, Pmt::Ok);
+
+        let config = handle.callback(agc, "get_config", Pmt::Null).await?;
+        assert_eq!(
+            from_pmt::<AgcConfig>(config)?,
+            AgcConfig {
+                squelch: 0.0,
+                max_gain: 10.0,
+                gain: 2.0,
+                reference_power: 0.5,
+                adjustment_rate: 0.01,
+                attack: None,
+                decay: None,
+                sample_rate: 1.0,
+                window: 1,
+                report_interval: None,
+                mode: AgcMode::Feedback,
+                hardware_gain: None,
+                gain_lock: true,
+                auto_lock: true,
+            }
+        );
+
+        // errors leave the config untouched
+        let update = Pmt::MapStrPmt(HashMap::from([("foo".to_string(), Pmt::F32(2.0))]));
+        let ret = handle.callback(agc, "config", update).await?;
+        assert_eq!(
+            from_pmt::<ConfigError>(ret)?,
+            ConfigError::UnknownField("foo".to_string())
+        );
+
+        let ret = handle
+            .callback(agc, "gain_lock", Pmt::String("yes".to_string()))
+            .await?;
+        assert!(matches!(
+            from_pmt::<ConfigError>(ret)?,
+            ConfigError::Invalid(_)
+        ));
+
+        let ret = handle.callback(agc, "max_gain", Pmt::F32(-1.0)).await?;
+        assert!(matches!(
+            from_pmt::<ConfigError>(ret)?,
+            ConfigError::Rejected(_)
+        ));
+
+        let update = Pmt::MapStrPmt(HashMap::from([(
+            "mode".to_string(),
+            Pmt::String("DualLoop".to_string()),
+        )]));
+        let ret = handle.callback(agc, "config", update).await?;
+        assert!(matches!(
+            from_pmt::<ConfigError>(ret)?,
+            ConfigError::Rejected(_)
+        ));
+
+        // without a sample rate, the report interval is counted in samples
+        let update = Pmt::MapStrPmt(HashMap::from([(
+            "report_interval".to_string(),
+            Pmt::F32(0.1),
+        )]));
+        let ret = handle.callback(agc, "config", update).await?;
+        assert!(matches!(
+            from_pmt::<ConfigError>(ret)?,
+            ConfigError::Rejected(_)
+        ));
+
+        let config = handle.callback(agc, "get_config", Pmt::Null).await?;
+        let config = from_pmt::<AgcConfig>(config)?;
+        assert_eq!(config.max_gain, 10.0);
+        assert!(config.gain_lock);
+        assert_eq!(to_pmt(&config.squelch)?, Pmt::F32(0.0));
+
+        handle.terminate().await?;
+        task.await?;
+        Ok(())
+    })
+}
+
+fn run_agc<T: Copy + Send + Sync + 'static>(input: Vec<T>, agc: Block) -> Result<Vec<T>> {
+    let mut fg = Flowgraph::new();
+    let src = VectorSource::<T>::new(input);
+    let snk = VectorSinkBuilder::<T>::new().build();
+    connect!(fg, src > agc > snk);
+    fg = Runtime::new().run(fg)?;
+    let snk = fg.kernel::<VectorSink<T>>(snk).unwrap();
+    Ok(snk.items().clone())
+}
+
+#[test]
+fn agc_complex_power() -> Result<()> {
+    // the real part of this tone goes through zero, its magnitude is constant
+    let input: Vec<Complex32> = (0..20_000)
+        .map(|i| Complex32::from_polar(4.0, i as f32 * 0.1))
+        .collect();
+    let agc = AgcBuilder::<Complex32>::new()
+        .reference_power(1.0)
+        .adjustment_rate(0.01)
+        .window(16)
+        .build();
+
+    let output = run_agc(input, agc)?;
+    assert_eq!(output.len(), 20_000);
+    for y in &output[10_000..] {
+        assert!((y.norm_sqr() - 1.0).abs() < 0.01, "{:?}", y);
+    }
+    Ok(())
+}
+
+#[test]
+fn agc_real_power() -> Result<()> {
+    let input = vec![-0.5f32; 5_000];
+    let agc = AgcBuilder::<f32>::new()
+        .reference_power(4.0)
+        .adjustment_rate(0.01)
+        .build();
+
+    let output = run_agc(input, agc)?;
+    let last = output.last().unwrap();
+    assert!((last + 2.0).abs() < 0.01, "{}", last);
+    Ok(())
+}
+
+#[test]
+fn agc_attack_decay() -> Result<()> {
+    // 10 ms attack and 1 s decay at 1 kHz
+    let build = || {
+        AgcBuilder::<Complex32>::new()
+            .sample_rate(1000.0)
+            .attack(0.01)
+            .decay(1.0)
+            .build()
+    };
+
+    // a strong signal is leveled after a few attack time constants
+    let output = run_agc(vec![Complex32::new(0.0, 10.0); 100], build())?;
+    assert!((output[99].norm_sqr() - 1.0).abs() < 0.01);
+
+    // a weak signal is still attenuated after the same time
+    let mut input = vec![Complex32::new(0.0, 10.0); 100];
+    input.extend(vec![Complex32::new(0.0, 0.1); 100]);
+    let output = run_agc(input, build())?;
+    assert!(output[199].norm_sqr() < 0.05);
+    Ok(())
+}
+
+#[test]
+fn agc_telemetry() -> Result<()> {
+    let mut fg = Flowgraph::new();
+    let src = VectorSource::<Complex32>::new(vec![Complex32::new(0.0, 2.0); 1000]);
+    let agc = AgcBuilder::<Complex32>::new()
+        .sample_rate(1000.0)
+        .attack(0.01)
+        .gain_output(true)
+        .report_interval(0.1)
+        .build();
+    let snk = NullSink::<Complex32>::new();
+    let gain_snk = VectorSinkBuilder::<f32>::new().build();
+    let (mut capture, pipe) = MessageCapture::new();
+    connect!(fg,
+        src > agc > snk;
+        agc.gain > gain_snk;
+        agc.state | pipe;
+    );
+    fg = Runtime::new().run(fg)?;
+
+    let gains = fg
+        .kernel::<VectorSink<f32>>(gain_snk)
+        .unwrap()
+        .items()
+        .clone();
+    assert_eq!(gains.len(), 1000);
+    assert_eq!(gains[0], 1.0);
+    assert!((gains[999] - 0.5).abs() < 1e-3);
+    assert!(gains.windows(2).all(|w| w[1] <= w[0]));
+
+    let messages = capture.messages();
+    assert_eq!(messages.len(), 10);
+    let states = messages
+        .into_iter()
+        .map(from_pmt::<AgcState>)
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    assert_eq!(states[0].gain, gains[99]);
+    assert_eq!(states[0].input_power, 4.0);
+    assert_eq!(states[0].output_power, 4.0 * gains[99].powi(2));
+    assert!(!states[0].squelched);
+    assert!((states[9].output_power - 1.0).abs() < 1e-2);
+    Ok(())
+}
+
+#[test]
+fn agc_get_state() -> Result<()> {
+    let mut fg = Flowgraph::new();
+    let src = Block::from_typed(Source::new(|| Complex32::new(0.0, 0.001)));
+    let agc = AgcBuilder::<Complex32>::new().squelch(0.01).build();
+    let snk = NullSink::<Complex32>::new();
+    connect!(fg, src > agc > snk);
+
+    let rt = Runtime::new();
+    block_on(async move {
+        let (task, mut handle) = rt.start(fg).await;
+        let state = handle.callback(agc, "get_state", Pmt::Null).await?;
+        let state = from_pmt::<AgcState>(state)?;
+        assert!(state.squelched);
+        assert_eq!(state.gain, 1.0);
+        assert_eq!(state.output_power, 0.0);
+        handle.terminate().await?;
+        task.await?;
+        Ok(())
+    })
+}
+
+#[test]
+fn agc_feed_forward() -> Result<()> {
+    let mut input = vec![Complex32::new(0.01, 0.0); 200];
+    input.extend(vec![Complex32::new(0.0, 10.0); 200]);
+    let agc = AgcBuilder::<Complex32>::new()
+        .mode(AgcMode::FeedForward { lookahead: 16 })
+        .build();
+
+    let output = run_agc(input, agc)?;
+    assert_eq!(output.len(), 400);
+    // the burst is leveled from its first sample, without overshoot
+    assert!((output[200].norm_sqr() - 1.0).abs() < 1e-3);
+    assert!(output.iter().all(|y| y.norm_sqr() < 1.0 + 1e-3));
+    // samples before the burst are attenuated along with it
+    assert!(output[190].norm_sqr() < 1e-5);
+    // the end of the stream is drained from the delay line
+    assert!((output[399].norm_sqr() - 1.0).abs() < 1e-3);
+    Ok(())
+}
+
+#[test]
+fn agc_dual_loop() -> Result<()> {
+    let mut input = vec![Complex32::new(0.1, 0.0); 2000];
+    input.extend(vec![Complex32::new(10.0, 0.0); 100]);
+    let build = |mode| {
+        AgcBuilder::<Complex32>::new()
+            .mode(mode)
+            .sample_rate(1000.0)
+            .attack(0.0)
+            .decay(1.0)
+            .window(100)
+            .build()
+    };
+
+    // the averaged power of the feedback loop lags behind the burst
+    let output = run_agc(input.clone(), build(AgcMode::Feedback))?;
+    assert!(output[2001].norm_sqr() > 10.0);
+
+    // the fast loop levels it on the next sample
+    let output = run_agc(input, build(AgcMode::DualLoop))?;
+    assert!((output[2001].norm_sqr() - 1.0).abs() < 1e-3);
+    assert!(output[2001..].iter().all(|y| y.norm_sqr() < 1.0 + 1e-3));
+    Ok(())
+}
+
+fn run_hardware_agc(amplitude: f32, hardware_gain: HardwareGain) -> Result<(Vec<f32>, Vec<Pmt>)> {
+    let mut fg = Flowgraph::new();
+    let src = VectorSource::<Complex32>::new(vec![Complex32::new(amplitude, 0.0); 2000]);
+    let agc = AgcBuilder::<Complex32>::new()
+        .sample_rate(1000.0)
+        .attack(0.01)
+        .decay(1.0)
+        .gain_output(true)
+        .hardware_gain(hardware_gain)
+        .build();
+    let snk = NullSink::<Complex32>::new();
+    let gain_snk = VectorSinkBuilder::<f32>::new().build();
+    let (mut capture, pipe) = MessageCapture::new();
+    connect!(fg,
+        src > agc > snk;
+        agc.gain > gain_snk;
+        agc.hw_gain | pipe;
+    );
+    fg = Runtime::new().run(fg)?;
+
+    let gains = fg
+        .kernel::<VectorSink<f32>>(gain_snk)
+        .unwrap()
+        .items()
+        .clone();
+    Ok((gains, capture.messages()))
+}
+
+#[test]
+fn agc_hardware_gain_up() -> Result<()> {
+    let hardware_gain = HardwareGain {
+        latency: 5,
+        ..HardwareGain::new(0.0, 30.0, 6.0)
+    };
+    let (gains, commands) = run_hardware_agc(0.01, hardware_gain)?;
+    assert_eq!(
+        commands,
+        [0.0, 6.0, 12.0, 18.0, 24.0, 30.0].map(Pmt::F64).to_vec()
+    );
+
+    // each step is compensated by the digital gain, once it reaches the input
+    let steps: Vec<usize> = gains
+        .windows(2)
+        .enumerate()
+        .filter(|(_, w)| w[1] < w[0])
+        .map(|(k, _)| k)
+        .collect();
+    assert_eq!(steps.len(), 5);
+    for k in steps {
+        let ratio = gains[k + 1] / gains[k];
+        assert!(ratio > 10f32.powf(-6.0 / 20.0) && ratio < 0.55, "{}", ratio);
+        // the gain went over the hysteresis `latency` samples before
+        assert!(gains[k - 4] > 1.99);
+    }
+    Ok(())
+}
+
+#[test]
+fn agc_hardware_gain_down() -> Result<()> {
+    let hardware_gain = HardwareGain {
+        gain: 30.0,
+        hysteresis: 6.0,
+        ..HardwareGain::new(0.0, 30.0, 10.0)
+    };
+    let (_, commands) = run_hardware_agc(10.0, hardware_gain)?;
+    assert_eq!(commands, [30.0, 20.0, 10.0, 0.0].map(Pmt::F64).to_vec());
+    Ok(())
+}
@@ -0,0 +1,38 @@
+use fsdr_blocks::async_channel::{AsyncChannelSink, AsyncChannelSource, DEFAULT_LEN_TAG_KEY};
+use futuresdr::macros::connect;
+use futuresdr::runtime::Flowgraph;
+use futuresdr::runtime::Result;
+use futuresdr::runtime::Runtime;
+
+#[test]
+fn run_async_channel_framing_roundtrip() -> Result<()> {
+    tokio_test::block_on(async_channel_framing_roundtrip())
+}
+
+async fn async_channel_framing_roundtrip() -> Result<()> {
+    let mut fg = Flowgraph::new();
+    let (tx_in, rx_in) = async_channel::unbounded::<Box<[u8]>>();
+    let (tx_out, rx_out) = async_channel::unbounded::<Box<[u8]>>();
+
+    let src = AsyncChannelSource::<u8>::with_len_tag(rx_in, DEFAULT_LEN_TAG_KEY);
+    let snk = AsyncChannelSink::<u8>::with_len_tag(tx_out, DEFAULT_LEN_TAG_KEY);
+
+    connect!(fg, src > snk);
+
+    let packets: Vec<Vec<u8>> = vec![vec![1, 2, 3], vec![4], vec![], (0..200).collect()];
+    for p in packets.iter() {
+        tx_in.send(p.clone().into_boxed_slice()).await.unwrap();
+    }
+    tx_in.close();
+
+    Runtime::new().run(fg)?;
+
+    let mut received = vec![];
+    while let Ok(p) = rx_out.try_recv() {
+        received.push(p.to_vec());
+    }
+    let expected: Vec<Vec<u8>> = packets.into_iter().filter(|p| !p.is_empty()).collect();
+    assert_eq!(received, expected);
+
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use fsdr_blocks::async_channel::{AsyncChannelMessageSink, AsyncChannelMessageSource};
+use futuresdr::macros::connect;
+use futuresdr::runtime::Flowgraph;
+use futuresdr::runtime::Pmt;
+use futuresdr::runtime::Result;
+use futuresdr::runtime::Runtime;
+
+#[test]
+fn run_async_channel_message_roundtrip() -> Result<()> {
+    tokio_test::block_on(async_channel_message_roundtrip())
+}
+
+async fn async_channel_message_roundtrip() -> Result<()> {
+    let mut fg = Flowgraph::new();
+    let (cmd_tx, cmd_rx) = async_channel::unbounded::<Pmt>();
+    let (tlm_tx, tlm_rx) = async_channel::bounded::<Pmt>(1);
+
+    let src = AsyncChannelMessageSource::new(cmd_rx);
+    let snk = AsyncChannelMessageSink::new(tlm_tx);
+
+    connect!(fg, src | snk);
+
+    let rt = Runtime::new();
+    let (task, _handle) = rt.start(fg).await;
+
+    // exchange with the running flowgraph
+    for i in 0..10u32 {
+        cmd_tx.send(Pmt::U32(i)).await.unwrap();
+        assert_eq!(tlm_rx.recv().await.unwrap(), Pmt::U32(i));
+    }
+    cmd_tx.send(Pmt::String("done".to_string())).await.unwrap();
+    cmd_tx.close();
+    assert_eq!(
+        tlm_rx.recv().await.unwrap(),
+        Pmt::String("done".to_string())
+    );
+
+    task.await?;
+    Ok(())
+}
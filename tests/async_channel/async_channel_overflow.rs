@@ -0,0 +1,50 @@
+use fsdr_blocks::async_channel::AsyncChannelSinkBuilder;
+use fsdr_blocks::stream::OverflowPolicy;
+use futuresdr::blocks::VectorSource;
+use futuresdr::macros::connect;
+use futuresdr::runtime::Flowgraph;
+use futuresdr::runtime::Result;
+use futuresdr::runtime::Runtime;
+
+const N: usize = 100_000;
+
+#[test]
+fn async_channel_sink_drop_oldest() -> Result<()> {
+    let mut fg = Flowgraph::new();
+    let (tx, rx) = async_channel::bounded::<Box<[u32]>>(1);
+
+    let src = VectorSource::<u32>::new((0..N as u32).collect());
+    let snk = AsyncChannelSinkBuilder::new(tx)
+        .overflow(OverflowPolicy::DropOldest)
+        .build();
+
+    connect!(fg, src > snk);
+    Runtime::new().run(fg)?;
+
+    // only the most recent chunk is left
+    let last = rx.try_recv().unwrap();
+    assert_eq!(*last.last().unwrap(), N as u32 - 1);
+    assert!(rx.try_recv().is_err());
+
+    Ok(())
+}
+
+#[test]
+fn async_channel_sink_drop_newest() -> Result<()> {
+    let mut fg = Flowgraph::new();
+    let (tx, rx) = async_channel::bounded::<Box<[u32]>>(1);
+
+    let src = VectorSource::<u32>::new((0..N as u32).collect());
+    let snk = AsyncChannelSinkBuilder::new(tx)
+        .overflow(OverflowPolicy::DropNewest)
+        .build();
+
+    connect!(fg, src > snk);
+    Runtime::new().run(fg)?;
+
+    let first = rx.try_recv().unwrap();
+    assert_eq!(first[0], 0);
+    assert!(first.len() < N);
+
+    Ok(())
+}
@@ -1,2 +1,6 @@
+pub mod async_channel_framing;
+pub mod async_channel_message;
+pub mod async_channel_overflow;
 pub mod async_channel_sink;
 pub mod async_channel_source;
+pub mod stream_adapters;
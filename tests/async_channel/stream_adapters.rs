@@ -0,0 +1,56 @@
+use fsdr_blocks::async_channel::{SinkAdapter, StreamSource};
+use futuresdr::futures::channel::mpsc;
+use futuresdr::futures::stream;
+use futuresdr::futures::StreamExt;
+use futuresdr::macros::connect;
+use futuresdr::runtime::Flowgraph;
+use futuresdr::runtime::Result;
+use futuresdr::runtime::Runtime;
+
+#[test]
+fn stream_source_to_sink_adapter() -> Result<()> {
+    let mut fg = Flowgraph::new();
+    let (tx, rx) = mpsc::channel::<Vec<u32>>(1);
+
+    let src = StreamSource::<u32, _>::new(stream::iter(0..10_000u32));
+    let snk = SinkAdapter::<u32, _>::new(tx);
+    connect!(fg, src > snk);
+
+    let reader = std::thread::spawn(move || {
+        futuresdr::async_io::block_on(rx.flat_map(stream::iter).collect::<Vec<u32>>())
+    });
+    Runtime::new().run(fg)?;
+
+    // the output stream ends when the flowgraph terminates
+    assert_eq!(reader.join().unwrap(), (0..10_000u32).collect::<Vec<u32>>());
+
+    Ok(())
+}
+
+#[test]
+fn stream_source_waits_for_items() -> Result<()> {
+    let mut fg = Flowgraph::new();
+    let (item_tx, item_rx) = mpsc::unbounded::<f32>();
+    let (tx, rx) = mpsc::unbounded::<Vec<f32>>();
+
+    let src = StreamSource::<f32, _>::new(item_rx);
+    let snk = SinkAdapter::<f32, _>::new(tx);
+    connect!(fg, src > snk);
+
+    let writer = std::thread::spawn(move || {
+        for i in 0..100 {
+            item_tx.unbounded_send(i as f32).unwrap();
+            if i % 10 == 0 {
+                std::thread::sleep(std::time::Duration::from_millis(5));
+            }
+        }
+    });
+    Runtime::new().run(fg)?;
+    writer.join().unwrap();
+
+    let received: Vec<f32> =
+        futuresdr::async_io::block_on(rx.flat_map(stream::iter).collect::<Vec<f32>>());
+    assert_eq!(received, (0..100).map(|i| i as f32).collect::<Vec<f32>>());
+
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use fsdr_blocks::channel::{CrossbeamMessageSink, CrossbeamMessageSource};
+use futuresdr::macros::connect;
+use futuresdr::runtime::Flowgraph;
+use futuresdr::runtime::Pmt;
+use futuresdr::runtime::Result;
+use futuresdr::runtime::Runtime;
+use std::time::Duration;
+
+#[test]
+fn crossbeam_message_roundtrip() -> Result<()> {
+    let mut fg = Flowgraph::new();
+    let (cmd_tx, cmd_rx) = crossbeam_channel::unbounded::<Pmt>();
+    let (tlm_tx, tlm_rx) = crossbeam_channel::unbounded::<Pmt>();
+
+    let src = CrossbeamMessageSource::new(cmd_rx);
+    let snk = CrossbeamMessageSink::new(tlm_tx);
+
+    connect!(fg, src | snk);
+
+    let rt = Runtime::new();
+    let (task, _handle) = rt.start_sync(fg);
+
+    for i in 0..10u64 {
+        cmd_tx.send(Pmt::U64(i))?;
+        assert_eq!(tlm_rx.recv_timeout(Duration::from_secs(5))?, Pmt::U64(i));
+    }
+    drop(cmd_tx);
+
+    futuresdr::async_io::block_on(task)?;
+    Ok(())
+}
@@ -0,0 +1,104 @@
+use fsdr_blocks::channel::{
+    CrossbeamMessageSink, CrossbeamSinkBuilder, CrossbeamTaggedSinkBuilder,
+};
+use fsdr_blocks::stream::{OverflowPolicy, TaggedBatch, OVERFLOW_TAG_KEY};
+use futuresdr::blocks::VectorSource;
+use futuresdr::macros::connect;
+use futuresdr::runtime::Flowgraph;
+use futuresdr::runtime::Pmt;
+use futuresdr::runtime::Result;
+use futuresdr::runtime::Runtime;
+use futuresdr::runtime::Tag;
+
+const N: usize = 100_000;
+
+fn last_dropped(rx: &crossbeam_channel::Receiver<Pmt>) -> u64 {
+    match rx.try_iter().last() {
+        Some(Pmt::U64(n)) => n,
+        None => 0,
+        p => panic!("unexpected dropped count {:?}", p),
+    }
+}
+
+#[test]
+fn crossbeam_sink_drop_newest() -> Result<()> {
+    let mut fg = Flowgraph::new();
+    let (tx, rx) = crossbeam_channel::bounded::<Box<[f32]>>(1);
+    let (tlm_tx, tlm_rx) = crossbeam_channel::unbounded::<Pmt>();
+
+    let src = VectorSource::<f32>::new((0..N).map(|x| x as f32).collect());
+    let snk = CrossbeamSinkBuilder::new(tx)
+        .overflow(OverflowPolicy::DropNewest)
+        .build()?;
+    let tlm = CrossbeamMessageSink::new(tlm_tx);
+
+    connect!(fg, src > snk; snk.dropped | tlm);
+    Runtime::new().run(fg)?;
+
+    // the first chunk is kept, the following ones are dropped
+    let first = rx.try_recv()?;
+    assert_eq!(first[0], 0.0);
+    assert!(first.len() < N);
+    assert_eq!(last_dropped(&tlm_rx), (N - first.len()) as u64);
+
+    Ok(())
+}
+
+#[test]
+fn crossbeam_tagged_sink_drop_oldest() -> Result<()> {
+    let mut fg = Flowgraph::new();
+    let (tx, rx) = crossbeam_channel::bounded::<TaggedBatch<f32>>(1);
+    let (tlm_tx, tlm_rx) = crossbeam_channel::unbounded::<Pmt>();
+
+    let src = VectorSource::<f32>::new((0..N).map(|x| x as f32).collect());
+    let snk = CrossbeamTaggedSinkBuilder::new(tx)
+        .overflow(OverflowPolicy::DropOldest)
+        .receiver(rx.clone())
+        .build()?;
+    let tlm = CrossbeamMessageSink::new(tlm_tx);
+
+    connect!(fg, src > snk; snk.dropped | tlm);
+    Runtime::new().run(fg)?;
+
+    // the last chunk is kept, and marks the samples evicted before it
+    let last = rx.try_recv()?;
+    assert_eq!(*last.samples.last().unwrap(), (N - 1) as f32);
+    assert_eq!(last_dropped(&tlm_rx), (N - last.len()) as u64);
+    match &last.tags[0].tag {
+        Tag::NamedUsize(key, n) if key == OVERFLOW_TAG_KEY => {
+            assert_eq!(last.tags[0].index, 0);
+            assert!(*n > 0 && *n as f32 <= last.samples[0]);
+        }
+        t => panic!("expected an overflow tag, got {:?}", t),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn crossbeam_sink_block() -> Result<()> {
+    let mut fg = Flowgraph::new();
+    let (tx, rx) = crossbeam_channel::bounded::<Box<[f32]>>(1);
+
+    let src = VectorSource::<f32>::new((0..N).map(|x| x as f32).collect());
+    let snk = CrossbeamSinkBuilder::new(tx)
+        .overflow(OverflowPolicy::Block)
+        .build()?;
+
+    connect!(fg, src > snk);
+    let reader = std::thread::spawn(move || rx.iter().flat_map(|c| c.to_vec()).count());
+    Runtime::new().run(fg)?;
+
+    assert_eq!(reader.join().unwrap(), N);
+
+    Ok(())
+}
+
+#[test]
+fn crossbeam_sink_drop_oldest_needs_receiver() {
+    let (tx, _rx) = crossbeam_channel::bounded::<Box<[f32]>>(1);
+    let snk = CrossbeamSinkBuilder::new(tx)
+        .overflow(OverflowPolicy::DropOldest)
+        .build();
+    assert!(snk.is_err());
+}
@@ -0,0 +1,60 @@
+use fsdr_blocks::channel::{CrossbeamTaggedSink, CrossbeamTaggedSource};
+use fsdr_blocks::stream::TaggedBatch;
+use futuresdr::macros::connect;
+use futuresdr::runtime::Flowgraph;
+use futuresdr::runtime::ItemTag;
+use futuresdr::runtime::Result;
+use futuresdr::runtime::Runtime;
+use futuresdr::runtime::Tag;
+
+fn tag(index: usize, name: &str) -> ItemTag {
+    ItemTag {
+        index,
+        tag: Tag::NamedUsize(name.to_string(), index),
+    }
+}
+
+#[test]
+fn crossbeam_tags_across_flowgraphs() -> Result<()> {
+    let (tx_in, rx_in) = crossbeam_channel::unbounded::<TaggedBatch<u16>>();
+    let (tx_out, rx_out) = crossbeam_channel::unbounded::<TaggedBatch<u16>>();
+
+    let mut fg = Flowgraph::new();
+    let src = CrossbeamTaggedSource::<u16>::new(rx_in);
+    let snk = CrossbeamTaggedSink::<u16>::new(tx_out);
+    connect!(fg, src > snk);
+
+    tx_in.send(TaggedBatch::new(
+        (0..10).collect::<Vec<u16>>().into_boxed_slice(),
+        vec![tag(0, "burst_start"), tag(9, "burst_end")],
+    ))?;
+    tx_in.send(TaggedBatch::new(
+        (10..20).collect::<Vec<u16>>().into_boxed_slice(),
+        vec![tag(5, "annotation")],
+    ))?;
+    drop(tx_in);
+    Runtime::new().run(fg)?;
+
+    let mut samples = vec![];
+    let mut tags = vec![];
+    for batch in rx_out.try_iter() {
+        for t in batch.tags.iter() {
+            if let Tag::NamedUsize(name, _) = &t.tag {
+                tags.push((samples.len() + t.index, name.clone()));
+            }
+        }
+        samples.extend_from_slice(&batch.samples);
+    }
+
+    assert_eq!(samples, (0..20).collect::<Vec<u16>>());
+    assert_eq!(
+        tags,
+        vec![
+            (0, "burst_start".to_string()),
+            (9, "burst_end".to_string()),
+            (15, "annotation".to_string())
+        ]
+    );
+
+    Ok(())
+}
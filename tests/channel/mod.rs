@@ -1,2 +1,5 @@
+pub mod crossbeam_message;
+pub mod crossbeam_overflow;
 pub mod crossbeam_sink;
 pub mod crossbeam_source;
+pub mod crossbeam_tagged;
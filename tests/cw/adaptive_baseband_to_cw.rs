@@ -0,0 +1,84 @@
+use crate::message::MessageCapture;
+use fsdr_blocks::cw::adaptive_baseband_to_cw::{AdaptiveBaseBandToCW, AdaptiveBaseBandToCWBuilder};
+use fsdr_blocks::cw::cw_encoder::CWEncoderBuilder;
+use fsdr_blocks::cw::cw_to_char::CWToCharBuilder;
+use futuresdr::blocks::{VectorSink, VectorSinkBuilder, VectorSource};
+use futuresdr::macros::connect;
+use futuresdr::runtime::{Flowgraph, Pmt, Result, Runtime};
+
+use super::SAMPLE_RATE;
+
+fn encode(wpm: f32, text: &str) -> Result<Vec<f32>> {
+    let mut fg = Flowgraph::new();
+    let src = VectorSource::<char>::new(text.chars().collect());
+    let encoder = CWEncoderBuilder::new(SAMPLE_RATE)
+        .wpm(wpm)
+        .stream_input(true)
+        .build();
+    let snk = VectorSinkBuilder::<f32>::new().build();
+    connect!(fg, src > encoder > snk);
+    fg = Runtime::new().run(fg)?;
+
+    Ok(fg.kernel::<VectorSink<f32>>(snk).unwrap().items().clone())
+}
+
+/// Decoded text, final speed estimate, and speeds posted on the `wpm` port
+fn decode(bb: Vec<f32>) -> Result<(String, f32, Vec<f32>)> {
+    let mut fg = Flowgraph::new();
+    let src = VectorSource::<f32>::new(bb);
+    let decoder = AdaptiveBaseBandToCWBuilder::new(SAMPLE_RATE)
+        .wpm(25.0)
+        .build();
+    let cw_to_char = CWToCharBuilder::new().build();
+    let snk = VectorSinkBuilder::<char>::new().build();
+    let (mut capture, pipe) = MessageCapture::new();
+    connect!(fg,
+        src > decoder > cw_to_char > snk;
+        decoder.wpm | pipe;
+    );
+    fg = Runtime::new().run(fg)?;
+
+    let text = fg
+        .kernel::<VectorSink<char>>(snk)
+        .unwrap()
+        .items()
+        .iter()
+        .collect();
+    let wpm = fg.kernel::<AdaptiveBaseBandToCW>(decoder).unwrap().wpm();
+    let wpms = capture
+        .messages()
+        .into_iter()
+        .map(|p| match p {
+            Pmt::F32(wpm) => wpm,
+            p => panic!("unexpected wpm message {p:?}"),
+        })
+        .collect();
+    Ok((text, wpm, wpms))
+}
+
+#[test]
+fn adaptive_cw_unknown_speed() -> Result<()> {
+    let bb = encode(18.0, "CQ CQ DE DL1ABC K")?;
+    let (text, wpm, wpms) = decode(bb)?;
+
+    // including the last word, once the transmission ends
+    assert_eq!(text, "CQ CQ DE DL1ABC K ");
+    assert!((wpm - 18.0).abs() < 0.5);
+    assert!((wpms.last().unwrap() - 18.0).abs() < 0.5);
+
+    Ok(())
+}
+
+#[test]
+fn adaptive_cw_speed_change() -> Result<()> {
+    let mut bb = encode(15.0, "PARIS PARIS ")?;
+    bb.extend(encode(30.0, "PARIS PARIS PARIS PARIS")?);
+    let (text, wpm, wpms) = decode(bb)?;
+
+    assert!(text.starts_with("PARIS PARIS "));
+    assert!(text.ends_with(" PARIS PARIS "));
+    assert!((wpm - 30.0).abs() < 1.0);
+    assert!(wpms.iter().any(|wpm| (wpm - 15.0).abs() < 0.5));
+
+    Ok(())
+}
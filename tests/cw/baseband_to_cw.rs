@@ -1,10 +1,11 @@
+use crate::message::MessageCapture;
 use fsdr_blocks::cw::baseband_to_cw::BaseBandToCWBuilder;
 use fsdr_blocks::cw::shared::CWAlphabet::*;
 use fsdr_blocks::cw::shared::{char_to_baseband, CWAlphabet};
 use futuresdr::blocks::{VectorSink, VectorSinkBuilder, VectorSource};
 use futuresdr::macros::connect;
 use futuresdr::runtime::Result;
-use futuresdr::runtime::{Flowgraph, Runtime};
+use futuresdr::runtime::{Flowgraph, Pmt, Runtime};
 
 // cargo nextest run test_baseband_to_cw --no-capture
 #[test]
@@ -62,3 +63,66 @@ fn test_baseband_to_cw() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_baseband_to_cw_events() -> Result<()> {
+    let mut fg = Flowgraph::new();
+
+    let mut char_to_baseband_function = char_to_baseband(10);
+    let mut bb: Vec<f32> = "ET"
+        .chars()
+        .flat_map(|c| char_to_baseband_function(&c))
+        .collect();
+    // a mark too long for a dash
+    bb.extend([1.0; 50]);
+    bb.extend([0.0; 10]);
+
+    let vector_src = VectorSource::new(bb);
+    let baseband_to_cw = BaseBandToCWBuilder::new()
+        .accuracy(80)
+        .samples_per_dot(10)
+        .debug(true)
+        .build();
+    let vector_snk = VectorSinkBuilder::<CWAlphabet>::new().build();
+    let (mut capture, pipe) = MessageCapture::new();
+
+    connect!(fg,
+        vector_src > baseband_to_cw > vector_snk;
+        baseband_to_cw.events | pipe;
+    );
+
+    Runtime::new().run(fg)?;
+
+    let events: Vec<(String, usize, Pmt, f32)> = capture
+        .messages()
+        .into_iter()
+        .map(|p| match p {
+            Pmt::MapStrPmt(map) => match (
+                &map["event"],
+                &map["duration"],
+                &map["symbol"],
+                &map["confidence"],
+            ) {
+                (Pmt::String(e), Pmt::Usize(d), s, Pmt::F32(c)) => (e.clone(), *d, s.clone(), *c),
+                e => panic!("unexpected event {e:?}"),
+            },
+            p => panic!("unexpected message {p:?}"),
+        })
+        .collect();
+
+    let symbol = |s: &str| Pmt::String(s.to_string());
+    assert_eq!(events[0], ("mark".to_string(), 10, symbol("Dot"), 1.0));
+    assert_eq!(
+        events[1],
+        ("space".to_string(), 30, symbol("LetterSpace"), 1.0)
+    );
+    assert_eq!(events[2], ("mark".to_string(), 30, symbol("Dash"), 1.0));
+    assert_eq!(
+        events[3],
+        ("space".to_string(), 30, symbol("LetterSpace"), 1.0)
+    );
+    assert_eq!(events[4], ("mark".to_string(), 50, Pmt::Null, 0.0));
+    assert_eq!(events.len(), 5);
+
+    Ok(())
+}
@@ -0,0 +1,96 @@
+use fsdr_blocks::cw::code::CWCode;
+use fsdr_blocks::cw::shared::CWAlphabet::*;
+use fsdr_blocks::cw::shared::{get_alphabet, msg_to_cw};
+
+#[test]
+fn test_code_itu() {
+    let code = CWCode::itu();
+    // same as the default alphabet, for the characters it contains
+    let message = "CQ DE DL1ABC/P ?".chars().collect::<Vec<char>>();
+    assert_eq!(code.encode("cq de dl1abc/p ?"), msg_to_cw(&message));
+    assert_eq!(code.chars().len(), get_alphabet().len() + 5);
+
+    assert_eq!(
+        code.decode(&[Dash, Dot, Dot, Dot, Dash]),
+        Some("=".to_string())
+    );
+    assert_eq!(code.decode(&[Dot, Dot, Dash, Dash]), None);
+}
+
+#[test]
+fn test_code_prosigns() {
+    let code = CWCode::itu().with_prosigns();
+    // prosigns are a single character, and take precedence over the same characters
+    assert_eq!(
+        code.encode("<SK>"),
+        vec![Dot, Dot, Dot, Dash, Dot, Dash, LetterSpace]
+    );
+    assert_eq!(code.encode("<bt>"), code.encode("="));
+    assert_eq!(
+        code.decode(&[Dash, Dot, Dot, Dot, Dash]),
+        Some("<BT>".to_string())
+    );
+    // angle brackets are not in the code otherwise
+    assert_eq!(
+        code.encode("<X"),
+        vec![Unknown, Dash, Dot, Dot, Dash, LetterSpace]
+    );
+}
+
+#[test]
+fn test_code_alphabets() {
+    let international = CWCode::international();
+    assert_eq!(
+        international.encode("ü"),
+        vec![Dot, Dot, Dash, Dash, LetterSpace]
+    );
+    assert_eq!(international.decode(&[Dot, Dash]), Some("A".to_string()));
+
+    let cyrillic = CWCode::cyrillic();
+    assert_eq!(
+        cyrillic.encode("Щ"),
+        vec![Dash, Dash, Dot, Dash, LetterSpace]
+    );
+    assert_eq!(cyrillic.decode(&[Dot, Dash]), Some("А".to_string()));
+    assert_eq!(cyrillic.encode("Q"), vec![Unknown]);
+    assert_eq!(
+        cyrillic.decode(&[Dot, Dot, Dot, Dot, Dot]),
+        Some("5".to_string())
+    );
+
+    let greek = CWCode::greek();
+    assert_eq!(greek.encode("ω"), vec![Dot, Dash, Dash, LetterSpace]);
+    assert_eq!(
+        greek.decode(&[Dash, Dash, Dash, Dash]),
+        Some("Χ".to_string())
+    );
+
+    let wabun = CWCode::wabun();
+    assert_eq!(wabun.chars().len(), 55 + 11);
+    // ホレ starts a transmission in Wabun
+    assert_eq!(
+        wabun.encode("ホレ"),
+        vec![Dash, Dot, Dot, LetterSpace, Dash, Dash, Dash, LetterSpace]
+    );
+    assert_eq!(
+        wabun.decode(&[Dot, Dash, Dot, Dash]),
+        Some("ロ".to_string())
+    );
+}
+
+#[test]
+fn test_code_custom() {
+    let mut alphabet = get_alphabet();
+    alphabet.insert('!', vec![Dash, Dot, Dash, Dot, Dash, Dash]);
+    let mut code = CWCode::from(alphabet);
+    code.insert_prosign("<VE>", vec![Dot, Dot, Dot, Dash, Dot]);
+
+    assert_eq!(
+        code.encode("!"),
+        vec![Dash, Dot, Dash, Dot, Dash, Dash, LetterSpace]
+    );
+    assert_eq!(
+        code.decode(&[Dot, Dot, Dot, Dash, Dot]),
+        Some("<VE>".to_string())
+    );
+}
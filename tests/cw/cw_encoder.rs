@@ -0,0 +1,172 @@
+use fsdr_blocks::cw::code::CWCode;
+use fsdr_blocks::cw::cw_encoder::CWEncoderBuilder;
+use fsdr_blocks::cw::shared::CWAlphabet::*;
+use futuresdr::async_io::block_on;
+use futuresdr::blocks::{VectorSink, VectorSinkBuilder, VectorSource};
+use futuresdr::macros::connect;
+use futuresdr::runtime::{Flowgraph, Pmt, Result, Runtime};
+
+fn encode(builder: CWEncoderBuilder, text: &str) -> Result<Vec<f32>> {
+    let mut fg = Flowgraph::new();
+    let src = VectorSource::<char>::new(text.chars().collect());
+    let encoder = builder.stream_input(true).build();
+    let snk = VectorSinkBuilder::<f32>::new().build();
+    connect!(fg, src > encoder > snk);
+    fg = Runtime::new().run(fg)?;
+
+    Ok(fg.kernel::<VectorSink<f32>>(snk).unwrap().items().clone())
+}
+
+/// Lengths of the runs of samples above and below half the envelope
+fn runs(bb: &[f32]) -> Vec<(bool, usize)> {
+    let mut runs: Vec<(bool, usize)> = vec![];
+    for x in bb {
+        let on = *x > 0.5;
+        match runs.last_mut() {
+            Some((key, len)) if *key == on => *len += 1,
+            _ => runs.push((on, 1)),
+        }
+    }
+    runs
+}
+
+#[test]
+fn cw_encoder_paris() -> Result<()> {
+    // 12 wpm at 1 kHz: 100 samples per dot, PARIS and its word gap are 50 dots
+    let bb = encode(
+        CWEncoderBuilder::new(1000.0).wpm(12.0).rise_time(0.0),
+        "paris ",
+    )?;
+    assert_eq!(bb.len(), 5000);
+    assert!(bb.iter().all(|x| *x == 0.0 || *x == 1.0));
+
+    // P: dot dash dash dot, then A
+    assert_eq!(
+        &runs(&bb)[..10],
+        &[
+            (true, 100),
+            (false, 100),
+            (true, 300),
+            (false, 100),
+            (true, 300),
+            (false, 100),
+            (true, 100),
+            (false, 300),
+            (true, 100),
+            (false, 100),
+        ]
+    );
+    assert_eq!(runs(&bb).last(), Some(&(false, 700)));
+
+    Ok(())
+}
+
+#[test]
+fn cw_encoder_farnsworth() -> Result<()> {
+    // characters at 20 wpm, gaps stretched to 10 wpm overall: PARIS takes 6 seconds
+    let bb = encode(
+        CWEncoderBuilder::new(1000.0)
+            .wpm(20.0)
+            .farnsworth(10.0)
+            .rise_time(0.0),
+        "PARIS ",
+    )?;
+    assert!((bb.len() as f32 - 6000.0).abs() < 5.0);
+
+    let runs = runs(&bb);
+    // elements at 20 wpm
+    assert_eq!(runs[0], (true, 60));
+    assert_eq!(runs[1], (false, 60));
+    // letter gap of 3 stretched units
+    let unit = (60.0 * 20.0 - 37.2 * 10.0) / (10.0 * 20.0) / 19.0 * 1000.0;
+    assert!((runs[7].1 as f32 - 3.0 * unit).abs() < 2.0);
+
+    Ok(())
+}
+
+#[test]
+fn cw_encoder_shaping() -> Result<()> {
+    let bb = encode(
+        CWEncoderBuilder::new(8000.0).wpm(20.0).rise_time(0.005),
+        "E",
+    )?;
+
+    // 480 samples of key down, ramped over 40 samples
+    assert_eq!(bb.len(), 4 * 480);
+    assert!(bb[0] > 0.0 && bb[0] < 0.01);
+    assert!(bb[..40].windows(2).all(|w| w[0] < w[1]));
+    assert_eq!(bb[40], 1.0);
+    assert!(bb[480..520].windows(2).all(|w| w[0] > w[1]));
+    assert!(bb[520..].iter().all(|x| *x == 0.0));
+    // no clicks, and the dot still lasts 480 samples at half the envelope
+    assert!(bb.windows(2).all(|w| (w[1] - w[0]).abs() < 0.05));
+    assert_eq!(runs(&bb)[1], (true, 480));
+
+    Ok(())
+}
+
+#[test]
+fn cw_encoder_message_input() -> Result<()> {
+    let mut fg = Flowgraph::new();
+    let encoder = CWEncoderBuilder::new(1000.0).wpm(12.0).build();
+    let snk = VectorSinkBuilder::<f32>::new().build();
+    connect!(fg, encoder > snk);
+
+    let rt = Runtime::new();
+    let fg = block_on(async move {
+        let (task, mut handle) = rt.start(fg).await;
+        handle
+            .call(encoder, "in", Pmt::String("cq de".to_string()))
+            .await?;
+        handle
+            .call(encoder, "in", Pmt::String(" dl1abc".to_string()))
+            .await?;
+        handle.call(encoder, "in", Pmt::Finished).await?;
+        task.await
+    })?;
+
+    // messages are joined, and sent like a stream of characters
+    let bb = fg.kernel::<VectorSink<f32>>(snk).unwrap().items().clone();
+    assert_eq!(
+        bb,
+        encode(CWEncoderBuilder::new(1000.0).wpm(12.0), "CQ DE DL1ABC")?
+    );
+
+    Ok(())
+}
+
+#[test]
+fn cw_encoder_prosigns() -> Result<()> {
+    let mut fg = Flowgraph::new();
+    let encoder = CWEncoderBuilder::new(1000.0)
+        .wpm(12.0)
+        .code(CWCode::itu().with_prosigns())
+        .build();
+    let snk = VectorSinkBuilder::<f32>::new().build();
+    connect!(fg, encoder > snk);
+
+    let rt = Runtime::new();
+    let fg = block_on(async move {
+        let (task, mut handle) = rt.start(fg).await;
+        handle
+            .call(encoder, "in", Pmt::String("cq <s".to_string()))
+            .await?;
+        handle
+            .call(encoder, "in", Pmt::String("k> <".to_string()))
+            .await?;
+        handle.call(encoder, "in", Pmt::Finished).await?;
+        task.await
+    })?;
+
+    // a prosign split across messages is sent as a single character, and a lone
+    // angle bracket is skipped
+    let bb = fg.kernel::<VectorSink<f32>>(snk).unwrap().items().clone();
+    let mut code = CWCode::itu();
+    code.insert('%', vec![Dot, Dot, Dot, Dash, Dot, Dash]);
+    assert_eq!(
+        bb,
+        encode(CWEncoderBuilder::new(1000.0).wpm(12.0).code(code), "CQ % ")?
+    );
+
+    Ok(())
+}
@@ -1,11 +1,14 @@
+use crate::message::MessageCapture;
+use fsdr_blocks::cw::code::CWCode;
 use fsdr_blocks::cw::cw_to_char::CWToCharBuilder;
-use fsdr_blocks::cw::shared::{msg_to_cw, CWAlphabet};
+use fsdr_blocks::cw::shared::msg_to_cw;
+use fsdr_blocks::cw::shared::CWAlphabet::{self, *};
 use futuresdr::async_io::block_on;
 use futuresdr::blocks::{ChannelSource, VectorSink, VectorSinkBuilder, VectorSource};
 use futuresdr::futures::SinkExt;
 use futuresdr::macros::connect;
 use futuresdr::runtime::Result;
-use futuresdr::runtime::{Flowgraph, Runtime};
+use futuresdr::runtime::{Flowgraph, Pmt, Runtime};
 
 // cargo test --features="cw"
 // cargo nextest run test_cw_to_char_vector --no-capture --features="cw"
@@ -87,3 +90,42 @@ fn test_cw_to_char_channel() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_cw_to_char_words() -> Result<()> {
+    let code = CWCode::itu().with_prosigns();
+
+    let mut fg = Flowgraph::new();
+    let mut cw = code.encode("CQ DE DL1ABC <AR>  ");
+    // an unknown character in the last word, which has no word space
+    cw.extend([Dot, Dot, Dash, Dash, LetterSpace]);
+    cw.extend(code.encode("K"));
+    let vector_src = VectorSource::new(cw);
+    let cw_to_char = CWToCharBuilder::new().code(code).unknown('*').build();
+    let vector_snk = VectorSinkBuilder::<char>::new().build();
+    let (mut capture, pipe) = MessageCapture::new();
+
+    connect!(fg,
+        vector_src > cw_to_char > vector_snk;
+        cw_to_char.words | pipe;
+    );
+
+    fg = Runtime::new().run(fg)?;
+
+    let text: String = fg
+        .kernel::<VectorSink<char>>(vector_snk)
+        .unwrap()
+        .items()
+        .iter()
+        .collect();
+    assert_eq!(text, "CQ DE DL1ABC <AR>  *K");
+
+    assert_eq!(
+        capture.messages(),
+        ["CQ", "DE", "DL1ABC", "<AR>", "*K"]
+            .map(|w| Pmt::String(w.to_string()))
+            .to_vec()
+    );
+
+    Ok(())
+}
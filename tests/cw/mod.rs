@@ -1,3 +1,25 @@
+use futuresdr::num_complex::Complex32;
+use rand::rngs::StdRng;
+use rand::Rng;
+use std::f32::consts::PI;
+
+pub mod adaptive_baseband_to_cw;
 pub mod baseband_to_cw;
+pub mod code;
+pub mod cw_encoder;
 pub mod cw_to_char;
 pub mod shared;
+pub mod skimmer;
+pub mod tone_detector;
+
+const SAMPLE_RATE: f32 = 8000.0;
+
+/// Gaussian noise of standard deviation `sigma`
+fn noise(rng: &mut StdRng, sigma: f32) -> f32 {
+    let (u, v): (f32, f32) = (rng.gen_range(f32::EPSILON..1.0), rng.gen());
+    sigma * (-2.0 * u.ln()).sqrt() * (2.0 * PI * v).cos()
+}
+
+fn tone(n: usize, frequency: f32) -> Complex32 {
+    Complex32::from_polar(1.0, 2.0 * PI * frequency * n as f32 / SAMPLE_RATE)
+}
@@ -0,0 +1,112 @@
+use fsdr_blocks::cw::shared::char_to_baseband;
+use fsdr_blocks::cw::skimmer::{CWSkimmerBuilder, SkimmerSample};
+use futuresdr::blocks::VectorSource;
+use futuresdr::macros::connect;
+use futuresdr::num_complex::Complex32;
+use futuresdr::runtime::{Flowgraph, Pmt, Result, Runtime};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use super::{noise, tone, SAMPLE_RATE};
+use crate::message::MessageCapture;
+
+/// Keyed baseband of `text`, after `delay` seconds of silence
+fn keying(text: &str, samples_per_dot: usize, delay: f32) -> Vec<f32> {
+    let mut char_to_baseband_function = char_to_baseband(samples_per_dot);
+    let mut bb = vec![0.0; (delay * SAMPLE_RATE) as usize];
+    bb.extend(text.chars().flat_map(|c| char_to_baseband_function(&c)));
+    bb
+}
+
+/// Sum of the carriers, keyed by their text
+fn carriers(carriers: &[(f32, Vec<f32>)]) -> impl Iterator<Item = (usize, Complex32)> + '_ {
+    let len = carriers.iter().map(|(_, bb)| bb.len()).max().unwrap() + SAMPLE_RATE as usize;
+    (0..len).map(move |n| {
+        let signal = carriers
+            .iter()
+            .map(|(f, bb)| tone(n, *f) * bb.get(n).copied().unwrap_or(0.0))
+            .sum();
+        (n, signal)
+    })
+}
+
+/// Decoded words, in the order of the frequencies of their carriers
+fn skim<T>(skimmer: CWSkimmerBuilder<T>, input: Vec<T>, frequencies: &[f32]) -> Result<Vec<String>>
+where
+    T: SkimmerSample,
+{
+    let mut fg = Flowgraph::new();
+    let src = VectorSource::<T>::new(input);
+    let skimmer = skimmer.build();
+    let (mut capture, pipe) = MessageCapture::new();
+    connect!(fg,
+        src > skimmer;
+        skimmer.text | pipe;
+    );
+    Runtime::new().run(fg)?;
+
+    let mut texts = vec![String::new(); frequencies.len()];
+    for message in capture.messages() {
+        let Pmt::VecPmt(v) = message else {
+            panic!("unexpected message {message:?}");
+        };
+        let (Pmt::F32(frequency), Pmt::String(word)) = (&v[0], &v[1]) else {
+            panic!("unexpected message {v:?}");
+        };
+        let carrier = frequencies
+            .iter()
+            .position(|f| (f - frequency).abs() < 5.0)
+            .unwrap_or_else(|| panic!("no carrier at {frequency} Hz"));
+        if !texts[carrier].is_empty() {
+            texts[carrier].push(' ');
+        }
+        texts[carrier].push_str(word);
+    }
+    Ok(texts)
+}
+
+#[test]
+fn skimmer_audio() -> Result<()> {
+    let mut rng = StdRng::seed_from_u64(1);
+    let audio = carriers(&[
+        (600.0, keying("CQ DE DL1ABC", 480, 0.5)),
+        (1100.0, keying("TEST DE F4XYZ", 400, 1.3)),
+        (1750.0, keying("QRZ DE W1AW", 600, 0.2)),
+    ])
+    .map(|(_, x)| 0.5 * x.re + noise(&mut rng, 0.1))
+    .collect();
+
+    assert_eq!(
+        skim(
+            CWSkimmerBuilder::<f32>::new(SAMPLE_RATE),
+            audio,
+            &[600.0, 1100.0, 1750.0],
+        )?,
+        ["CQ DE DL1ABC", "TEST DE F4XYZ", "QRZ DE W1AW"]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn skimmer_baseband() -> Result<()> {
+    let mut rng = StdRng::seed_from_u64(2);
+    let baseband = carriers(&[
+        (-1500.0, keying("CQ TEST DL1ABC", 480, 0.7)),
+        (700.0, keying("5NN 599 K", 400, 0.1)),
+    ])
+    .map(|(_, x)| 0.5 * x + Complex32::new(noise(&mut rng, 0.1), noise(&mut rng, 0.1)))
+    .collect();
+
+    assert_eq!(
+        skim(
+            // not a power of 2
+            CWSkimmerBuilder::<Complex32>::new(SAMPLE_RATE).fft_size(1000),
+            baseband,
+            &[-1500.0, 700.0],
+        )?,
+        ["CQ TEST DL1ABC", "5NN 599 K"]
+    );
+
+    Ok(())
+}
@@ -0,0 +1,126 @@
+use fsdr_blocks::cw::adaptive_baseband_to_cw::AdaptiveBaseBandToCWBuilder;
+use fsdr_blocks::cw::cw_encoder::CWEncoderBuilder;
+use fsdr_blocks::cw::cw_to_char::CWToCharBuilder;
+use fsdr_blocks::cw::tone_detector::CWToneDetectorBuilder;
+use futuresdr::blocks::{VectorSink, VectorSinkBuilder, VectorSource};
+use futuresdr::macros::connect;
+use futuresdr::num_complex::Complex32;
+use futuresdr::runtime::{Flowgraph, Result, Runtime};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use super::{noise, tone, SAMPLE_RATE};
+
+/// Keyed baseband of `text` at 20 wpm, after half a second of silence
+fn keying(text: &str) -> Result<Vec<f32>> {
+    let mut fg = Flowgraph::new();
+    let src = VectorSource::<char>::new(text.chars().collect());
+    let encoder = CWEncoderBuilder::new(SAMPLE_RATE)
+        .wpm(20.0)
+        .stream_input(true)
+        .build();
+    let snk = VectorSinkBuilder::<f32>::new().build();
+    connect!(fg, src > encoder > snk);
+    fg = Runtime::new().run(fg)?;
+
+    let mut bb = vec![0.0; SAMPLE_RATE as usize / 2];
+    bb.extend(fg.kernel::<VectorSink<f32>>(snk).unwrap().items());
+    Ok(bb)
+}
+
+fn decode<T>(input: Vec<T>, frequency: f32) -> Result<String>
+where
+    T: Send + Sync + Copy + futuresdr::num_complex::ComplexFloat + 'static,
+{
+    let mut fg = Flowgraph::new();
+    let src = VectorSource::<T>::new(input);
+    let detector = CWToneDetectorBuilder::<T>::new(SAMPLE_RATE, frequency).build();
+    let decoder = AdaptiveBaseBandToCWBuilder::new(SAMPLE_RATE).build();
+    let cw_to_char = CWToCharBuilder::new().build();
+    let snk = VectorSinkBuilder::<char>::new().build();
+    connect!(fg, src > detector > decoder > cw_to_char > snk);
+    fg = Runtime::new().run(fg)?;
+
+    Ok(fg
+        .kernel::<VectorSink<char>>(snk)
+        .unwrap()
+        .items()
+        .iter()
+        .collect())
+}
+
+#[test]
+fn tone_detector_audio() -> Result<()> {
+    let mut rng = StdRng::seed_from_u64(1);
+    let audio = keying("CQ DE DL1ABC")?
+        .iter()
+        .enumerate()
+        .map(|(n, key)| key * tone(n, 700.0).re + noise(&mut rng, 0.3))
+        .collect();
+
+    assert_eq!(decode::<f32>(audio, 700.0)?, "CQ DE DL1ABC ");
+
+    Ok(())
+}
+
+#[test]
+fn tone_detector_baseband_selectivity() -> Result<()> {
+    let mut rng = StdRng::seed_from_u64(2);
+    let wanted = keying("TEST DE DL1ABC")?;
+    let other = keying("QRM QRM QRM QRM")?;
+    let len = wanted.len().max(other.len());
+    let baseband = (0..len)
+        .map(|n| {
+            let a = wanted.get(n).copied().unwrap_or(0.0);
+            let b = other.get(n).copied().unwrap_or(0.0);
+            tone(n, -500.0) * a
+                + tone(n, 400.0) * b
+                + Complex32::new(noise(&mut rng, 0.2), noise(&mut rng, 0.2))
+        })
+        .collect();
+
+    assert_eq!(decode::<Complex32>(baseband, -500.0)?, "TEST DE DL1ABC ");
+
+    Ok(())
+}
+
+#[test]
+fn tone_detector_snr() -> Result<()> {
+    let mut rng = StdRng::seed_from_u64(3);
+    // a second of noise, then a second of tone in noise
+    let audio = (0..2 * SAMPLE_RATE as usize)
+        .map(|n| {
+            let key = if n < SAMPLE_RATE as usize { 0.0 } else { 1.0 };
+            key * tone(n, 1000.0).re + noise(&mut rng, 0.5)
+        })
+        .collect();
+
+    let mut fg = Flowgraph::new();
+    let src = VectorSource::<f32>::new(audio);
+    let detector = CWToneDetectorBuilder::<f32>::new(SAMPLE_RATE, 1000.0)
+        .snr_output(true)
+        .build();
+    let keyed = VectorSinkBuilder::<f32>::new().build();
+    let snr = VectorSinkBuilder::<f32>::new().build();
+    connect!(fg,
+        src > detector > keyed;
+        detector.snr > snr;
+    );
+    fg = Runtime::new().run(fg)?;
+
+    let keyed = fg.kernel::<VectorSink<f32>>(keyed).unwrap().items().clone();
+    let snr = fg.kernel::<VectorSink<f32>>(snr).unwrap().items().clone();
+    assert_eq!(keyed.len(), snr.len());
+
+    // no false keying on noise, and the tone is keyed after the delay of the filter
+    assert!(keyed[..8000].iter().all(|k| *k == 0.0));
+    let start = keyed.iter().position(|k| *k == 1.0).unwrap();
+    assert!((8000 + 40..8000 + 160).contains(&start));
+    assert!(keyed[start..].iter().all(|k| *k == 1.0));
+
+    // tone amplitude 0.5, noise of about 0.5 / sqrt(2 * 160) in the filter
+    assert!(snr[7999].abs() < 3.0);
+    assert!((snr[15999] - 25.0).abs() < 4.0);
+
+    Ok(())
+}
@@ -0,0 +1,128 @@
+use fsdr_blocks::message::*;
+use futuresdr::async_io::block_on;
+use futuresdr::macros::connect;
+use futuresdr::runtime::Flowgraph;
+use futuresdr::runtime::Pmt;
+use futuresdr::runtime::Result;
+use futuresdr::runtime::Runtime;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("fsdr-blocks-{}-{}", std::process::id(), name))
+}
+
+fn messages() -> Vec<Pmt> {
+    vec![
+        Pmt::F32(0.5),
+        Pmt::Bool(true),
+        Pmt::String("gain".to_string()),
+        Pmt::VecCF32(vec![futuresdr::num_complex::Complex32::new(1.0, -1.0)]),
+        Pmt::MapStrPmt(HashMap::from([("max_gain".to_string(), Pmt::U32(10))])),
+    ]
+}
+
+#[test]
+fn record_messages() -> Result<()> {
+    let path = temp_path("recorded.jsonl");
+
+    let mut fg = Flowgraph::new();
+    let recorder = fg.add_block(MessageRecorder::new(&path));
+
+    let rt = Runtime::new();
+    block_on(async move {
+        let (task, mut handle) = rt.start(fg).await;
+        for p in messages() {
+            assert_eq!(handle.callback(recorder, "in", p).await?, Pmt::Ok);
+        }
+        handle.terminate().await?;
+        task.await?;
+        Result::<()>::Ok(())
+    })?;
+
+    let data = std::fs::read(&path)?;
+    assert!(std::str::from_utf8(&data)?.starts_with(r#"{"pmt":{"F32":0.5},"time_ns":"#));
+    let records = MessageRecord::decode_all(RecordFormat::JsonLines, &data)?;
+    assert_eq!(
+        records.iter().map(|r| r.pmt.clone()).collect::<Vec<_>>(),
+        messages()
+    );
+    assert!(records.windows(2).all(|w| w[0].time <= w[1].time));
+
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[test]
+fn replay_as_fast_as_possible() -> Result<()> {
+    let path = temp_path("replayed.cbor");
+    let records: Vec<MessageRecord> = messages()
+        .into_iter()
+        .enumerate()
+        .map(|(i, p)| MessageRecord::new(Duration::from_secs(3600 * i as u64), p))
+        .collect();
+
+    let mut fg = Flowgraph::new();
+    let replayer = MessageReplayerBuilder::from_records(records)
+        .timing(ReplayTiming::AsFastAsPossible)
+        .build();
+    let recorder = MessageRecorderBuilder::new(&path)
+        .format(RecordFormat::Cbor)
+        .build();
+    connect!(fg, replayer | recorder);
+
+    let t0 = Instant::now();
+    Runtime::new().run(fg)?;
+    assert!(t0.elapsed() < Duration::from_secs(60));
+
+    let records = MessageRecord::decode_all(RecordFormat::Cbor, &std::fs::read(&path)?)?;
+    assert_eq!(
+        records.into_iter().map(|r| r.pmt).collect::<Vec<_>>(),
+        messages()
+    );
+
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[test]
+fn replay_original_timing() -> Result<()> {
+    let original = temp_path("original.cbor");
+    let replayed = temp_path("replayed_timing.cbor");
+    let times = [0, 50, 100, 150];
+
+    let mut data = vec![];
+    for (i, t) in times.iter().enumerate() {
+        MessageRecord::new(Duration::from_millis(*t), Pmt::U64(i as u64))
+            .encode(RecordFormat::Cbor, &mut data)?;
+    }
+    std::fs::write(&original, data)?;
+
+    let mut fg = Flowgraph::new();
+    let replayer = MessageReplayerBuilder::new(&original)
+        .format(RecordFormat::Cbor)
+        .build();
+    let recorder = MessageRecorderBuilder::new(&replayed)
+        .format(RecordFormat::Cbor)
+        .build();
+    connect!(fg, replayer | recorder);
+    Runtime::new().run(fg)?;
+
+    let records = MessageRecord::decode_all(RecordFormat::Cbor, &std::fs::read(&replayed)?)?;
+    assert_eq!(records.len(), times.len());
+    for (i, r) in records.iter().enumerate() {
+        assert_eq!(r.pmt, Pmt::U64(i as u64));
+        if i > 0 {
+            assert!(r.time >= records[i - 1].time);
+            // never early, and only loosely bounded since a loaded machine delays messages
+            let delta = r.time - records[0].time;
+            assert!(delta >= Duration::from_millis(times[i] - 5));
+            assert!(delta < Duration::from_millis(times[i] + 1000));
+        }
+    }
+
+    std::fs::remove_file(&original)?;
+    std::fs::remove_file(&replayed)?;
+    Ok(())
+}
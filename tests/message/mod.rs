@@ -0,0 +1,24 @@
+use futuresdr::blocks::MessagePipe;
+use futuresdr::futures::channel::mpsc;
+use futuresdr::runtime::{Block, Pmt};
+
+pub mod message_recording;
+
+/// Messages posted to a port, captured in memory
+pub struct MessageCapture {
+    rx: mpsc::Receiver<Pmt>,
+}
+
+impl MessageCapture {
+    /// Create the capture, and the block to connect the port to
+    pub fn new() -> (MessageCapture, Block) {
+        // large enough for the messages of a test, which are only read after it ran
+        let (tx, rx) = mpsc::channel(1024);
+        (MessageCapture { rx }, MessagePipe::new(tx))
+    }
+
+    /// Messages received so far
+    pub fn messages(&mut self) -> Vec<Pmt> {
+        std::iter::from_fn(|| self.rx.try_recv().ok()).collect()
+    }
+}
@@ -0,0 +1,2 @@
+pub mod rtl_tcp_source;
+pub mod udp;
@@ -0,0 +1,66 @@
+use fsdr_blocks::net::RtlTcpSourceBuilder;
+use futuresdr::blocks::MessageSourceBuilder;
+use futuresdr::blocks::VectorSink;
+use futuresdr::blocks::VectorSinkBuilder;
+use futuresdr::macros::connect;
+use futuresdr::num_complex::Complex32;
+use futuresdr::runtime::Flowgraph;
+use futuresdr::runtime::Pmt;
+use futuresdr::runtime::Result;
+use futuresdr::runtime::Runtime;
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::time::Duration;
+
+#[test]
+fn rtl_tcp_loopback() -> Result<()> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let addr = listener.local_addr()?.to_string();
+
+    let server = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut header = b"RTL0".to_vec();
+        header.extend_from_slice(&5u32.to_be_bytes());
+        header.extend_from_slice(&29u32.to_be_bytes());
+        stream.write_all(&header).unwrap();
+
+        // frequency and sample rate from the builder, then frequency from the message
+        let mut commands = vec![];
+        for _ in 0..3 {
+            let mut cmd = [0u8; 5];
+            stream.read_exact(&mut cmd).unwrap();
+            commands.push((cmd[0], u32::from_be_bytes(cmd[1..].try_into().unwrap())));
+        }
+
+        let iq: Vec<u8> = (0..2000)
+            .map(|i| if i % 2 == 0 { 0 } else { 255 })
+            .collect();
+        stream.write_all(&iq).unwrap();
+        commands
+    });
+
+    let mut fg = Flowgraph::new();
+    let src = RtlTcpSourceBuilder::new(&addr)
+        .frequency(433.92e6)
+        .sample_rate(1.024e6)
+        .build();
+    let msg = MessageSourceBuilder::new(Pmt::F64(100e6), Duration::from_millis(10))
+        .n_messages(1)
+        .build();
+    let snk = VectorSinkBuilder::<Complex32>::new().build();
+    connect!(fg, src > snk; msg | src.freq);
+    fg = Runtime::new().run(fg)?;
+
+    let commands = server.join().unwrap();
+    assert_eq!(
+        commands,
+        vec![(0x01, 433_920_000), (0x02, 1_024_000), (0x01, 100_000_000)]
+    );
+
+    let snk = fg.kernel::<VectorSink<Complex32>>(snk).unwrap();
+    let items = snk.items();
+    assert_eq!(items.len(), 1000);
+    assert!(items.iter().all(|v| *v == Complex32::new(-1.0, 1.0)));
+
+    Ok(())
+}
@@ -0,0 +1,198 @@
+use fsdr_blocks::net::UdpBuilder;
+use futuresdr::blocks::Head;
+use futuresdr::blocks::VectorSink;
+use futuresdr::blocks::VectorSinkBuilder;
+use futuresdr::blocks::VectorSource;
+use futuresdr::macros::connect;
+use futuresdr::runtime::Flowgraph;
+use futuresdr::runtime::Result;
+use futuresdr::runtime::Runtime;
+use std::net::UdpSocket;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+fn free_port() -> u16 {
+    UdpSocket::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port()
+}
+
+#[test]
+fn udp_sink_sequence_header() -> Result<()> {
+    let receiver = UdpSocket::bind("127.0.0.1:0")?;
+    receiver.set_read_timeout(Some(Duration::from_secs(5)))?;
+    let addr = receiver.local_addr()?.to_string();
+
+    let mut fg = Flowgraph::new();
+    let src = VectorSource::<u32>::new((0..1000).collect());
+    let snk = UdpBuilder::<u32>::sink(&addr)
+        .as_be()
+        .payload_size(400)
+        .sequence_header(true)
+        .build();
+    connect!(fg, src > snk);
+    Runtime::new().run(fg)?;
+
+    let mut buf = [0u8; 2048];
+    for seq in 0..10u64 {
+        let n = receiver.recv(&mut buf)?;
+        assert_eq!(n, 8 + 400);
+        assert_eq!(u64::from_le_bytes(buf[..8].try_into().unwrap()), seq);
+        let first = u32::from_be_bytes(buf[8..12].try_into().unwrap());
+        assert_eq!(first, seq as u32 * 100);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn udp_source_sequence_header() -> Result<()> {
+    let addr = format!("127.0.0.1:{}", free_port());
+    let stop = Arc::new(AtomicBool::new(false));
+
+    let sender = {
+        let addr = addr.clone();
+        let stop = stop.clone();
+        std::thread::spawn(move || {
+            let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+            let mut seq = 0u64;
+            while !stop.load(Ordering::Relaxed) {
+                // simulate the loss of one datagram out of ten
+                if seq % 10 != 5 {
+                    let mut datagram = seq.to_le_bytes().to_vec();
+                    for _ in 0..4 {
+                        datagram.extend_from_slice(&(seq as u32).to_le_bytes());
+                    }
+                    let _ = socket.send_to(&datagram, &addr);
+                }
+                seq += 1;
+                std::thread::sleep(Duration::from_millis(1));
+            }
+        })
+    };
+
+    let mut fg = Flowgraph::new();
+    let src = UdpBuilder::<u32>::source(&addr)
+        .as_le()
+        .sequence_header(true)
+        .build();
+    let head = Head::<u32>::new(100);
+    let snk = VectorSinkBuilder::<u32>::new().build();
+    connect!(fg, src > head > snk);
+    fg = Runtime::new().run(fg)?;
+    stop.store(true, Ordering::Relaxed);
+    sender.join().unwrap();
+
+    let snk = fg.kernel::<VectorSink<u32>>(snk).unwrap();
+    let items = snk.items();
+    assert_eq!(items.len(), 100);
+    for datagram in items.chunks(4) {
+        assert!(datagram.iter().all(|v| *v == datagram[0]));
+        assert_ne!(datagram[0] % 10, 5);
+    }
+    assert!(items.windows(2).all(|w| w[0] <= w[1]));
+
+    Ok(())
+}
+
+#[test]
+fn udp_source_reordered_datagrams() -> Result<()> {
+    let addr = format!("127.0.0.1:{}", free_port());
+    let stop = Arc::new(AtomicBool::new(false));
+
+    let sender = {
+        let addr = addr.clone();
+        let stop = stop.clone();
+        std::thread::spawn(move || {
+            let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+            let mut base = 0u64;
+            while !stop.load(Ordering::Relaxed) {
+                // datagram 3 arrives after 4, and 6 is duplicated
+                for seq in [0, 1, 2, 4, 3, 5, 6, 6, 7, 8, 9].map(|i| base + i) {
+                    let mut datagram = seq.to_le_bytes().to_vec();
+                    for _ in 0..4 {
+                        datagram.extend_from_slice(&(seq as u32).to_le_bytes());
+                    }
+                    let _ = socket.send_to(&datagram, &addr);
+                    std::thread::sleep(Duration::from_millis(1));
+                }
+                base += 10;
+            }
+        })
+    };
+
+    let mut fg = Flowgraph::new();
+    let src = UdpBuilder::<u32>::source(&addr)
+        .as_le()
+        .sequence_header(true)
+        .build();
+    let head = Head::<u32>::new(100);
+    let snk = VectorSinkBuilder::<u32>::new().build();
+    connect!(fg, src > head > snk);
+    fg = Runtime::new().run(fg)?;
+    stop.store(true, Ordering::Relaxed);
+    sender.join().unwrap();
+
+    let snk = fg.kernel::<VectorSink<u32>>(snk).unwrap();
+    let items = snk.items();
+    assert_eq!(items.len(), 100);
+    let datagrams: Vec<u32> = items.chunks(4).map(|d| d[0]).collect();
+    // late and duplicate datagrams are dropped, the first one received aside
+    assert!(datagrams.windows(2).all(|w| w[0] < w[1]));
+    assert!(datagrams[1..].iter().all(|d| d % 10 != 3));
+
+    Ok(())
+}
+
+#[test]
+fn udp_source_sender_restart() -> Result<()> {
+    let addr = format!("127.0.0.1:{}", free_port());
+    let stop = Arc::new(AtomicBool::new(false));
+
+    let sender = {
+        let addr = addr.clone();
+        let stop = stop.clone();
+        std::thread::spawn(move || {
+            let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+            let mut run = 0u32;
+            while !stop.load(Ordering::Relaxed) {
+                // the sender restarts counting from 0 every ten datagrams
+                for seq in 0..10u64 {
+                    let mut datagram = seq.to_le_bytes().to_vec();
+                    for _ in 0..4 {
+                        datagram.extend_from_slice(&(run * 100 + seq as u32).to_le_bytes());
+                    }
+                    let _ = socket.send_to(&datagram, &addr);
+                    std::thread::sleep(Duration::from_millis(1));
+                }
+                run += 1;
+            }
+        })
+    };
+
+    let mut fg = Flowgraph::new();
+    let src = UdpBuilder::<u32>::source(&addr)
+        .as_le()
+        .sequence_header(true)
+        .build();
+    let head = Head::<u32>::new(100);
+    let snk = VectorSinkBuilder::<u32>::new().build();
+    connect!(fg, src > head > snk);
+    fg = Runtime::new().run(fg)?;
+    stop.store(true, Ordering::Relaxed);
+    sender.join().unwrap();
+
+    let snk = fg.kernel::<VectorSink<u32>>(snk).unwrap();
+    let items = snk.items();
+    assert_eq!(items.len(), 100);
+    let datagrams: Vec<u32> = items.chunks(4).map(|d| d[0]).collect();
+    // each datagram follows the previous one, or starts the next run of the sender
+    assert!(datagrams
+        .windows(2)
+        .all(|w| w[1] == w[0] + 1 || (w[1] % 100 == 0 && w[1] / 100 == w[0] / 100 + 1)));
+
+    Ok(())
+}
@@ -3,7 +3,11 @@ use std::collections::HashMap;
 use futuresdr::runtime::Pmt;
 use futuresdr::runtime::Result;
 
-use fsdr_blocks::serde_pmt::{from_pmt, to_pmt};
+use fsdr_blocks::serde_pmt::{cbor, from_pmt, json, to_pmt};
+use futuresdr::num_complex::Complex32;
+use quickcheck::{Arbitrary, Gen};
+use quickcheck_macros::quickcheck;
+use serde::{Deserialize, Serialize};
 
 #[test]
 fn test_pmt_uint32() -> Result<()> {
@@ -89,9 +93,31 @@ fn test_pmt_f64() -> Result<()> {
 
 #[test]
 fn test_pmt_i16() -> Result<()> {
-    assert_eq!(Pmt::F32(-3f32), to_pmt(&-3i16)?);
-    assert_eq!(Pmt::F32(5f32), to_pmt(&5i16)?);
-    assert_eq!(Pmt::F32(i16::MIN as f32), to_pmt(&i16::MIN)?);
+    assert_eq!(Pmt::F64(-3f64), to_pmt(&-3i16)?);
+    assert_eq!(Pmt::U32(5), to_pmt(&5i16)?);
+    assert_eq!(Pmt::F64(i16::MIN as f64), to_pmt(&i16::MIN)?);
+    Ok(())
+}
+
+#[test]
+fn test_pmt_integers() -> Result<()> {
+    assert_eq!(Pmt::U32(i32::MAX as u32), to_pmt(&i32::MAX)?);
+    assert_eq!(Pmt::F64(i32::MIN as f64), to_pmt(&i32::MIN)?);
+    assert_eq!(Pmt::U64(u64::MAX), to_pmt(&u64::MAX)?);
+    assert_eq!(Pmt::U64(i64::MAX as u64), to_pmt(&i64::MAX)?);
+    assert_eq!(Pmt::F64(-(1i64 << 53) as f64), to_pmt(&-(1i64 << 53))?);
+    assert_eq!(Pmt::F64(i64::MIN as f64), to_pmt(&i64::MIN)?);
+    assert!(to_pmt(&(-(1i64 << 53) - 1)).is_err());
+    assert_eq!(
+        Pmt::VecPmt(vec![Pmt::U32(1), Pmt::F64(-1.0)]),
+        to_pmt(&vec![1i32, -1])?
+    );
+    assert_eq!(
+        vec![1i32, -1],
+        from_pmt::<Vec<i32>>(to_pmt(&vec![1i32, -1])?)?
+    );
+    assert_eq!(Pmt::U64(7), to_pmt(&7u128)?);
+    assert!(to_pmt(&u128::MAX).is_err());
     Ok(())
 }
 
@@ -158,3 +184,498 @@ fn test_sigmf_annot_pmt() -> Result<()> {
     assert_eq!(annot, from_pmt(Pmt::MapStrPmt(value))?);
     Ok(())
 }
+
+#[test]
+fn test_integers_from_pmt() -> Result<()> {
+    assert_eq!(-3, from_pmt::<i16>(Pmt::F32(-3.0))?);
+    assert_eq!(200, from_pmt::<u8>(Pmt::U32(200))?);
+    assert_eq!(7, from_pmt::<i8>(Pmt::Usize(7))?);
+    assert_eq!(u64::MAX, from_pmt::<u64>(Pmt::U64(u64::MAX))?);
+    assert_eq!(i64::MIN, from_pmt::<i64>(Pmt::F64(i64::MIN as f64))?);
+    assert_eq!(42, from_pmt::<u32>(Pmt::U64(42))?);
+    assert_eq!(42, from_pmt::<u128>(Pmt::U32(42))?);
+
+    // overflows and fractional values are errors
+    assert!(from_pmt::<u8>(Pmt::U32(256)).is_err());
+    assert!(from_pmt::<i8>(Pmt::F32(-129.0)).is_err());
+    assert!(from_pmt::<u32>(Pmt::F64(-1.0)).is_err());
+    assert!(from_pmt::<u16>(Pmt::F32(1.5)).is_err());
+    assert!(from_pmt::<i32>(Pmt::F64(f64::NAN)).is_err());
+    assert!(from_pmt::<u32>(Pmt::String("1".to_string())).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_floats_from_pmt() -> Result<()> {
+    assert_eq!(1.5, from_pmt::<f32>(Pmt::F32(1.5))?);
+    assert_eq!(1.5, from_pmt::<f32>(Pmt::F64(1.5))?);
+    assert_eq!(3.0, from_pmt::<f64>(Pmt::U32(3))?);
+    assert_eq!(f32::INFINITY, from_pmt::<f32>(Pmt::F64(f64::INFINITY))?);
+    assert!(from_pmt::<f32>(Pmt::F64(f64::MAX)).is_err());
+    assert!(from_pmt::<f64>(Pmt::Bool(true)).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_char_from_pmt() -> Result<()> {
+    assert_eq!('a', from_pmt::<char>(Pmt::String("a".to_string()))?);
+    assert!(from_pmt::<char>(Pmt::String("ab".to_string())).is_err());
+    assert!(from_pmt::<char>(Pmt::String("".to_string())).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_seq_from_pmt() -> Result<()> {
+    let v: Vec<f32> = from_pmt(Pmt::VecF32(vec![1.0, 2.0]))?;
+    assert_eq!(vec![1.0, 2.0], v);
+    let v: Vec<u64> = from_pmt(Pmt::VecU64(vec![1, 2, 3]))?;
+    assert_eq!(vec![1, 2, 3], v);
+    let v: Vec<u8> = from_pmt(Pmt::Blob(vec![0, 255]))?;
+    assert_eq!(vec![0, 255], v);
+    let v: Vec<Complex32> = from_pmt(Pmt::VecCF32(vec![Complex32::new(1.0, -1.0)]))?;
+    assert_eq!(vec![Complex32::new(1.0, -1.0)], v);
+    let v: Vec<String> = from_pmt(Pmt::VecPmt(vec![
+        Pmt::String("a".to_string()),
+        Pmt::String("b".to_string()),
+    ]))?;
+    assert_eq!(vec!["a", "b"], v);
+    let v: (u32, String, Option<bool>) = from_pmt(Pmt::VecPmt(vec![
+        Pmt::U32(1),
+        Pmt::String("a".to_string()),
+        Pmt::Null,
+    ]))?;
+    assert_eq!((1, "a".to_string(), None), v);
+    let v: [f64; 2] = from_pmt(Pmt::VecF32(vec![0.5, 1.0]))?;
+    assert_eq!([0.5, 1.0], v);
+
+    assert!(from_pmt::<(u32, u32)>(Pmt::VecU64(vec![1, 2, 3])).is_err());
+    assert!(from_pmt::<Vec<u8>>(Pmt::VecU64(vec![256])).is_err());
+    assert!(from_pmt::<Vec<u32>>(Pmt::U32(1)).is_err());
+    Ok(())
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Unit;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Newtype(f64);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Tuple(u8, String);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Config {
+    name: String,
+    gain: Newtype,
+    taps: Vec<f32>,
+    mode: Mode,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Mode {
+    Off,
+    Fixed(f32),
+    Range(f32, f32),
+    Auto { attack: f32, decay: f32 },
+}
+
+#[test]
+fn test_structs_from_pmt() -> Result<()> {
+    assert_eq!(Unit, from_pmt(Pmt::Null)?);
+    assert_eq!(Newtype(2.0), from_pmt(Pmt::F64(2.0))?);
+    assert_eq!(
+        Tuple(3, "x".to_string()),
+        from_pmt(Pmt::VecPmt(vec![Pmt::U32(3), Pmt::String("x".to_string())]))?
+    );
+
+    let mut map = HashMap::new();
+    map.insert("name".to_string(), Pmt::String("agc".to_string()));
+    map.insert("gain".to_string(), Pmt::U32(3));
+    map.insert("taps".to_string(), Pmt::VecF32(vec![0.5, 0.5]));
+    map.insert("mode".to_string(), Pmt::String("Off".to_string()));
+    assert_eq!(
+        Config {
+            name: "agc".to_string(),
+            gain: Newtype(3.0),
+            taps: vec![0.5, 0.5],
+            mode: Mode::Off,
+        },
+        from_pmt(Pmt::MapStrPmt(map.clone()))?
+    );
+
+    map.remove("name");
+    assert!(from_pmt::<Config>(Pmt::MapStrPmt(map)).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_enums_from_pmt() -> Result<()> {
+    let variant = |name: &str, value: Pmt| {
+        let mut map = HashMap::new();
+        map.insert(name.to_string(), value);
+        Pmt::MapStrPmt(map)
+    };
+    assert_eq!(Mode::Off, from_pmt(Pmt::String("Off".to_string()))?);
+    assert_eq!(Mode::Fixed(1.0), from_pmt(variant("Fixed", Pmt::F32(1.0)))?);
+    assert_eq!(
+        Mode::Range(1.0, 2.0),
+        from_pmt(variant("Range", Pmt::VecF32(vec![1.0, 2.0])))?
+    );
+    let mut fields = HashMap::new();
+    fields.insert("attack".to_string(), Pmt::F32(0.1));
+    fields.insert("decay".to_string(), Pmt::F64(0.5));
+    assert_eq!(
+        Mode::Auto {
+            attack: 0.1,
+            decay: 0.5
+        },
+        from_pmt(variant("Auto", Pmt::MapStrPmt(fields)))?
+    );
+
+    assert!(from_pmt::<Mode>(Pmt::String("Manual".to_string())).is_err());
+    assert!(from_pmt::<Mode>(Pmt::String("Fixed".to_string())).is_err());
+    assert!(from_pmt::<Mode>(Pmt::U32(0)).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_maps_from_pmt() -> Result<()> {
+    let mut map = HashMap::new();
+    map.insert("1".to_string(), Pmt::F32(0.5));
+    map.insert("20".to_string(), Pmt::F32(2.0));
+    let v: HashMap<u32, f32> = from_pmt(Pmt::MapStrPmt(map.clone()))?;
+    assert_eq!(Some(&2.0), v.get(&20));
+
+    map.insert("x".to_string(), Pmt::F32(2.0));
+    assert!(from_pmt::<HashMap<u32, f32>>(Pmt::MapStrPmt(map)).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_bytes_from_pmt() -> Result<()> {
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Frame {
+        #[serde(with = "serde_bytes_compat")]
+        payload: Vec<u8>,
+    }
+
+    mod serde_bytes_compat {
+        use serde::de::{Deserializer, Visitor};
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<u8>, D::Error> {
+            struct Bytes;
+            impl<'de> Visitor<'de> for Bytes {
+                type Value = Vec<u8>;
+                fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                    f.write_str("bytes")
+                }
+                fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Vec<u8>, E> {
+                    Ok(v)
+                }
+            }
+            d.deserialize_byte_buf(Bytes)
+        }
+    }
+
+    let frame = |payload: Pmt| {
+        let mut map = HashMap::new();
+        map.insert("payload".to_string(), payload);
+        Pmt::MapStrPmt(map)
+    };
+    assert_eq!(
+        Frame {
+            payload: vec![1, 2]
+        },
+        from_pmt(frame(Pmt::Blob(vec![1, 2])))?
+    );
+    assert_eq!(
+        Frame {
+            payload: vec![1, 2]
+        },
+        from_pmt(frame(Pmt::VecPmt(vec![Pmt::U32(1), Pmt::U32(2)])))?
+    );
+    assert!(from_pmt::<Frame>(frame(Pmt::VecPmt(vec![Pmt::U32(300)]))).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_unsupported_pmt() {
+    assert!(from_pmt::<u32>(Pmt::Finished).is_err());
+    assert!(from_pmt::<String>(Pmt::Ok).is_err());
+    assert!(from_pmt::<serde_json::Value>(Pmt::InvalidValue).is_err());
+}
+
+#[test]
+fn test_pmt_seq() -> Result<()> {
+    assert_eq!(Pmt::VecF32(vec![1.0, 2.0]), to_pmt(&vec![1.0f32, 2.0])?);
+    assert_eq!(Pmt::VecU64(vec![1, 2]), to_pmt(&[1u64, 2])?);
+    assert_eq!(
+        Pmt::VecCF32(vec![Complex32::new(1.0, 2.0)]),
+        to_pmt(&vec![Complex32::new(1.0, 2.0)])?
+    );
+    assert_eq!(
+        Pmt::VecPmt(vec![Pmt::U32(1), Pmt::String("a".to_string())]),
+        to_pmt(&(1u8, "a"))?
+    );
+    assert_eq!(Pmt::VecPmt(vec![]), to_pmt(&Vec::<f32>::new())?);
+    assert_eq!(
+        Pmt::VecPmt(vec![Pmt::U32(3), Pmt::String("x".to_string())]),
+        to_pmt(&Tuple(3, "x".to_string()))?
+    );
+    Ok(())
+}
+
+#[test]
+fn test_pmt_enum() -> Result<()> {
+    let variant = |name: &str, value: Pmt| {
+        let mut map = HashMap::new();
+        map.insert(name.to_string(), value);
+        Pmt::MapStrPmt(map)
+    };
+    assert_eq!(Pmt::String("Off".to_string()), to_pmt(&Mode::Off)?);
+    assert_eq!(variant("Fixed", Pmt::F32(1.0)), to_pmt(&Mode::Fixed(1.0))?);
+    assert_eq!(
+        variant("Range", Pmt::VecF32(vec![1.0, 2.0])),
+        to_pmt(&Mode::Range(1.0, 2.0))?
+    );
+    let mut fields = HashMap::new();
+    fields.insert("attack".to_string(), Pmt::F32(0.1));
+    fields.insert("decay".to_string(), Pmt::F32(0.5));
+    assert_eq!(
+        variant("Auto", Pmt::MapStrPmt(fields)),
+        to_pmt(&Mode::Auto {
+            attack: 0.1,
+            decay: 0.5
+        })?
+    );
+    Ok(())
+}
+
+#[test]
+fn test_pmt_roundtrip() -> Result<()> {
+    let config = Config {
+        name: "agc".to_string(),
+        gain: Newtype(-1.5),
+        taps: vec![0.25; 4],
+        mode: Mode::Auto {
+            attack: 0.1,
+            decay: 0.5,
+        },
+    };
+    assert_eq!(config, from_pmt(to_pmt(&config)?)?);
+
+    let values: (i8, i64, u16, Option<char>, Vec<Complex32>, Unit) = (
+        -5,
+        -(1 << 40),
+        u16::MAX,
+        Some('z'),
+        vec![Complex32::new(0.0, -1.0)],
+        Unit,
+    );
+    assert_eq!(values, from_pmt(to_pmt(&values)?)?);
+
+    let mut map = HashMap::new();
+    map.insert(3u32, vec![Mode::Off, Mode::Range(0.0, 1.0)]);
+    assert_eq!(map, from_pmt(to_pmt(&map)?)?);
+    Ok(())
+}
+
+#[test]
+fn test_gr_pmt_roundtrip() -> Result<()> {
+    use fsdr_blocks::serde_pmt::gr_pmt::{from_gr_bytes, to_gr_bytes};
+
+    let mut map = HashMap::new();
+    map.insert("freq".to_string(), Pmt::F64(433.92e6));
+    map.insert("count".to_string(), Pmt::U64(u64::MAX));
+    let values = vec![
+        Pmt::Null,
+        Pmt::Bool(true),
+        Pmt::U32(7),
+        Pmt::U32(u32::MAX),
+        Pmt::String("packet_len".to_string()),
+        Pmt::VecPmt(vec![Pmt::U64(1), Pmt::F64(0.5)]),
+        Pmt::MapStrPmt(map),
+        Pmt::VecF32(vec![1.0, -1.0]),
+        Pmt::Blob(vec![1, 2, 3]),
+    ];
+    for v in values {
+        let mut bytes = vec![];
+        to_gr_bytes(&v, &mut bytes).unwrap();
+        let mut input = bytes.as_slice();
+        let decoded = from_gr_bytes(&mut input).unwrap();
+        assert!(input.is_empty());
+        match (&v, decoded) {
+            (Pmt::U32(a), Pmt::U64(b)) => assert_eq!(*a as u64, b),
+            (v, decoded) => assert_eq!(v, &decoded),
+        }
+    }
+
+    // GNU Radio's serialization of the symbol "ab" and of the double 1.0
+    let mut bytes = vec![];
+    to_gr_bytes(&Pmt::String("ab".to_string()), &mut bytes).unwrap();
+    to_gr_bytes(&Pmt::F32(1.0), &mut bytes).unwrap();
+    assert_eq!(
+        bytes,
+        vec![0x02, 0, 2, b'a', b'b', 0x04, 0x3f, 0xf0, 0, 0, 0, 0, 0, 0]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_gr_pmt_uniform_vectors() -> Result<()> {
+    use fsdr_blocks::serde_pmt::gr_pmt::{from_gr_bytes, to_gr_bytes};
+
+    // pmt::serialize_str(pmt::init_f32vector(2, [1.0, -2.5])) in GNU Radio:
+    // elements are written as doubles
+    let f32vector = vec![
+        0x0a, 0x08, 0, 0, 0, 2, 0x01, 0x00, //
+        0x3f, 0xf0, 0, 0, 0, 0, 0, 0, //
+        0xc0, 0x04, 0, 0, 0, 0, 0, 0,
+    ];
+    // pmt::serialize_str(pmt::init_c32vector(1, [0.5 - 1j])) in GNU Radio:
+    // real and imaginary parts are written as doubles
+    let c32vector = vec![
+        0x0a, 0x0a, 0, 0, 0, 1, 0x01, 0x00, //
+        0x3f, 0xe0, 0, 0, 0, 0, 0, 0, //
+        0xbf, 0xf0, 0, 0, 0, 0, 0, 0,
+    ];
+
+    for (pmt, bytes) in [
+        (Pmt::VecF32(vec![1.0, -2.5]), f32vector),
+        (Pmt::VecCF32(vec![Complex32::new(0.5, -1.0)]), c32vector),
+    ] {
+        let mut encoded = vec![];
+        to_gr_bytes(&pmt, &mut encoded).unwrap();
+        assert_eq!(encoded, bytes);
+        let mut input = bytes.as_slice();
+        assert_eq!(from_gr_bytes(&mut input).unwrap(), pmt);
+        assert!(input.is_empty());
+    }
+    Ok(())
+}
+
+#[derive(Clone, Debug)]
+struct ArbitraryPmt(Pmt);
+
+fn arbitrary_pmt(g: &mut Gen, depth: usize) -> Pmt {
+    let variants = if depth == 0 { 15 } else { 17 };
+    match u8::arbitrary(g) % variants {
+        0 => Pmt::Ok,
+        1 => Pmt::InvalidValue,
+        2 => Pmt::Null,
+        3 => Pmt::Finished,
+        4 => Pmt::Bool(bool::arbitrary(g)),
+        5 => Pmt::String(String::arbitrary(g)),
+        6 => Pmt::Usize(usize::arbitrary(g)),
+        7 => Pmt::U32(u32::arbitrary(g)),
+        8 => Pmt::U64(u64::arbitrary(g)),
+        9 => Pmt::F32(f32::arbitrary(g)),
+        10 => Pmt::F64(f64::arbitrary(g)),
+        11 => Pmt::VecF32(Vec::arbitrary(g)),
+        12 => Pmt::VecU64(Vec::arbitrary(g)),
+        13 => Pmt::VecCF32(
+            Vec::<(f32, f32)>::arbitrary(g)
+                .into_iter()
+                .map(|(re, im)| Complex32::new(re, im))
+                .collect(),
+        ),
+        14 => Pmt::Blob(Vec::arbitrary(g)),
+        15 => Pmt::VecPmt(
+            (0..usize::arbitrary(g) % 4)
+                .map(|_| arbitrary_pmt(g, depth - 1))
+                .collect(),
+        ),
+        _ => Pmt::MapStrPmt(
+            (0..usize::arbitrary(g) % 4)
+                .map(|_| (String::arbitrary(g), arbitrary_pmt(g, depth - 1)))
+                .collect(),
+        ),
+    }
+}
+
+impl Arbitrary for ArbitraryPmt {
+    fn arbitrary(g: &mut Gen) -> Self {
+        ArbitraryPmt(arbitrary_pmt(g, 2))
+    }
+}
+
+/// Canonical encoding, bit-exact for floats, to compare values including NaNs
+fn cbor(p: &Pmt) -> Vec<u8> {
+    let mut out = vec![];
+    cbor::to_cbor_bytes(p, &mut out).unwrap();
+    out
+}
+
+#[quickcheck]
+fn prop_cbor_roundtrip(p: ArbitraryPmt) -> bool {
+    let bytes = cbor(&p.0);
+    let mut input = &bytes[..];
+    let decoded = cbor::from_cbor_bytes(&mut input).unwrap();
+    input.is_empty() && cbor(&decoded) == bytes
+}
+
+#[quickcheck]
+fn prop_json_roundtrip(p: ArbitraryPmt) -> bool {
+    let s = json::to_json_string(&p.0).unwrap();
+    let decoded = json::from_json_str(&s).unwrap();
+    cbor(&decoded) == cbor(&p.0) && json::to_json_string(&decoded).unwrap() == s
+}
+
+#[quickcheck]
+fn prop_cbor_truncated(p: ArbitraryPmt) -> bool {
+    let bytes = cbor(&p.0);
+    (0..bytes.len()).all(|n| cbor::from_cbor_bytes(&mut &bytes[..n]).is_err())
+}
+
+#[test]
+fn test_json_format() -> Result<()> {
+    assert_eq!(r#"{"F32":0.5}"#, json::to_json_string(&Pmt::F32(0.5))?);
+    assert_eq!(
+        r#"{"F64":"NaN"}"#,
+        json::to_json_string(&Pmt::F64(f64::NAN))?
+    );
+    assert_eq!(
+        r#"{"Blob":"00ff"}"#,
+        json::to_json_string(&Pmt::Blob(vec![0, 255]))?
+    );
+    assert_eq!(
+        r#"{"VecCF32":[[1.0,-1.0]]}"#,
+        json::to_json_string(&Pmt::VecCF32(vec![Complex32::new(1.0, -1.0)]))?
+    );
+    let mut map = HashMap::new();
+    map.insert("b".to_string(), Pmt::U32(2));
+    map.insert("a".to_string(), Pmt::Null);
+    assert_eq!(
+        r#"{"MapStrPmt":{"a":{"Null":null},"b":{"U32":2}}}"#,
+        json::to_json_string(&Pmt::MapStrPmt(map))?
+    );
+
+    assert_eq!(Pmt::U64(7), json::from_json_str(r#"{"U64":7}"#)?);
+    assert!(json::from_json_str(r#"{"U32":4294967296}"#).is_err());
+    assert!(json::from_json_str(r#"{"U32":1,"U64":1}"#).is_err());
+    assert!(json::from_json_str(r#"{"Blob":"0"}"#).is_err());
+    assert!(json::from_json_str(r#"{"Tuple":[]}"#).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_cbor_format() -> Result<()> {
+    // {"U32": 500}
+    assert_eq!(
+        vec![0xa1, 0x63, b'U', b'3', b'2', 0x19, 0x01, 0xf4],
+        cbor(&Pmt::U32(500))
+    );
+    // {"VecF32": h'0000803f'}
+    assert_eq!(
+        vec![0xa1, 0x66, b'V', b'e', b'c', b'F', b'3', b'2', 0x44, 0, 0, 0x80, 0x3f],
+        cbor(&Pmt::VecF32(vec![1.0]))
+    );
+    assert!(
+        cbor::from_cbor_bytes(&mut &[0xa1, 0x63, b'U', b'3', b'2', 0x1a, 1, 0, 0, 0][..]).is_ok()
+    );
+    assert!(cbor::from_cbor_bytes(
+        &mut &[0xa1, 0x63, b'U', b'3', b'2', 0x1b, 1, 0, 0, 0, 0, 0, 0, 0][..]
+    )
+    .is_err());
+    Ok(())
+}
@@ -0,0 +1,149 @@
+use fsdr_blocks::sigmf::SigMFSink;
+use fsdr_blocks::squelch::{PowerSquelchBuilder, BURST_END_LABEL, BURST_START_LABEL};
+use futuresdr::blocks::{VectorSink, VectorSinkBuilder, VectorSource};
+use futuresdr::macros::connect;
+use futuresdr::runtime::{Flowgraph, Result, Runtime};
+use sigmf::{DatasetFormat, DescriptionBuilder};
+
+/// Samples with the given amplitudes, each repeated `len` times
+fn levels(segments: &[(f32, usize)]) -> Vec<f32> {
+    segments
+        .iter()
+        .flat_map(|&(amplitude, len)| vec![amplitude; len])
+        .collect()
+}
+
+fn db(dbfs: f32) -> f32 {
+    10f32.powf(dbfs / 20.0)
+}
+
+fn run_squelch(builder: PowerSquelchBuilder<f32>, input: Vec<f32>) -> Result<Vec<f32>> {
+    let mut fg = Flowgraph::new();
+    let src = VectorSource::<f32>::new(input);
+    let squelch = builder.build();
+    let snk = VectorSinkBuilder::<f32>::new().build();
+    connect!(fg, src > squelch > snk);
+    fg = Runtime::new().run(fg)?;
+
+    Ok(fg.kernel::<VectorSink<f32>>(snk).unwrap().items().clone())
+}
+
+#[test]
+fn squelch_burst_annotations() -> Result<()> {
+    let input = levels(&[(0.001, 200), (1.0, 300), (0.001, 200)]);
+
+    let mut fg = Flowgraph::new();
+    let src = VectorSource::<f32>::new(input.clone());
+    let squelch = PowerSquelchBuilder::<f32>::new(-20.0)
+        .close(-30.0)
+        .window(16)
+        .build();
+    let data_file = std::io::Cursor::new(vec![]);
+    let meta_file = std::io::Cursor::new(vec![]);
+    let desc = DescriptionBuilder::from(DatasetFormat::Rf32Le);
+    let snk = SigMFSink::<f32, _, _>::new(data_file, desc, meta_file);
+    connect!(fg, src > squelch > snk);
+    fg = Runtime::new().run(fg)?;
+
+    let snk = fg
+        .kernel::<SigMFSink<f32, std::io::Cursor<Vec<u8>>, std::io::Cursor<Vec<u8>>>>(snk)
+        .unwrap();
+    let desc = snk.description.build()?;
+    let annotations = desc.annotations()?;
+    assert_eq!(annotations.len(), 2);
+
+    // opens on the first loud sample, closes once the window only holds quiet ones
+    let start = &annotations[0];
+    assert_eq!(start.label.as_deref(), Some(BURST_START_LABEL));
+    assert_eq!(start.sample_start, Some(200));
+    let end = &annotations[1];
+    assert_eq!(end.label.as_deref(), Some(BURST_END_LABEL));
+    assert_eq!(end.sample_start, Some(200));
+    assert_eq!(end.sample_count, Some(316));
+
+    Ok(())
+}
+
+#[test]
+fn squelch_zeroes_outside_bursts() -> Result<()> {
+    let input = levels(&[(0.001, 200), (1.0, 300), (0.001, 200)]);
+    let output = run_squelch(PowerSquelchBuilder::new(-20.0).window(16), input.clone())?;
+
+    assert_eq!(output.len(), input.len());
+    assert!(output[..200].iter().all(|&x| x == 0.0));
+    assert_eq!(&output[200..500], &input[200..500]);
+    assert!(output[516..].iter().all(|&x| x == 0.0));
+
+    Ok(())
+}
+
+#[test]
+fn squelch_gate_ramp() -> Result<()> {
+    let input = levels(&[(0.001, 200), (1.0, 300), (0.001, 200)]);
+    let output = run_squelch(
+        PowerSquelchBuilder::new(-20.0)
+            .window(16)
+            .ramp(8)
+            .gate(true),
+        input,
+    )?;
+
+    // the burst, and the fade out past the sample closing it
+    assert_eq!(output.len(), 316 + 8);
+    assert!(output[..8].windows(2).all(|w| w[0] < w[1]));
+    assert!(output[..8].iter().all(|&x| 0.0 < x && x < 1.0));
+    assert!(output[8..300].iter().all(|&x| x == 1.0));
+    assert!(output[316..].windows(2).all(|w| w[0] > w[1]));
+
+    Ok(())
+}
+
+#[test]
+fn squelch_hysteresis() -> Result<()> {
+    let input = levels(&[
+        (db(-35.0), 100),
+        (db(-15.0), 100),
+        (db(-25.0), 100),
+        (db(-35.0), 100),
+    ]);
+
+    // the burst goes on while the power stays above the close threshold
+    let output = run_squelch(
+        PowerSquelchBuilder::new(-20.0)
+            .close(-30.0)
+            .window(1)
+            .gate(true),
+        input.clone(),
+    )?;
+    assert_eq!(output.len(), 201);
+
+    // without hysteresis, it ends as soon as the power drops below the open threshold
+    let output = run_squelch(PowerSquelchBuilder::new(-20.0).window(1).gate(true), input)?;
+    assert_eq!(output.len(), 101);
+
+    Ok(())
+}
+
+#[test]
+fn squelch_short_burst_ramp() -> Result<()> {
+    // the burst ends before its fade in completes
+    let input = levels(&[(db(-40.0), 100), (1.0, 3), (db(-40.0), 100)]);
+    let output = run_squelch(
+        PowerSquelchBuilder::new(-20.0).window(1).ramp(8).gate(true),
+        input.clone(),
+    )?;
+
+    // the fade out starts from the gain reached by the fade in, instead of full gain
+    let gains: Vec<f32> = output
+        .iter()
+        .zip(&input[100..])
+        .map(|(o, i)| o / i)
+        .collect();
+    assert_eq!(gains.len(), 6);
+    assert!(gains[..3].windows(2).all(|w| w[0] < w[1]));
+    assert!((gains[3] - gains[2]).abs() < 1e-4);
+    assert!(gains[3..].windows(2).all(|w| w[0] > w[1]));
+    assert!(gains.iter().all(|&g| g < 0.5));
+
+    Ok(())
+}
@@ -0,0 +1,144 @@
+use fsdr_blocks::stdinout::*;
+use futuresdr::blocks::VectorSink;
+use futuresdr::blocks::VectorSinkBuilder;
+use futuresdr::blocks::VectorSource;
+use futuresdr::macros::connect;
+use futuresdr::num_complex::Complex32;
+use futuresdr::runtime::Flowgraph;
+use futuresdr::runtime::Result;
+use futuresdr::runtime::Runtime;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("fsdr-blocks-{}-{}", std::process::id(), name))
+}
+
+#[test]
+fn file_roundtrip_complex_be() -> Result<()> {
+    let path = temp_path("roundtrip.cf32");
+    let orig: Vec<Complex32> = (0..10_000)
+        .map(|i| Complex32::new(i as f32, -(i as f32)))
+        .collect();
+
+    let mut fg = Flowgraph::new();
+    let src = VectorSource::<Complex32>::new(orig.clone());
+    let snk = StdInOutBuilder::<Complex32>::write_to(ByteStreamEndpoint::File(path.clone()))
+        .as_be()
+        .buffer_size(1000)
+        .build();
+    connect!(fg, src > snk);
+    Runtime::new().run(fg)?;
+
+    let bytes = std::fs::read(&path)?;
+    assert_eq!(bytes.len(), orig.len() * 8);
+    assert_eq!(&bytes[8..16], &[0x3f, 0x80, 0, 0, 0xbf, 0x80, 0, 0]);
+
+    let mut fg = Flowgraph::new();
+    let src = StdInOutBuilder::<Complex32>::read_from(ByteStreamEndpoint::File(path.clone()))
+        .as_be()
+        // not a multiple of the item size, to exercise partial reads
+        .buffer_size(1001)
+        .build();
+    let snk = VectorSinkBuilder::<Complex32>::new().build();
+    connect!(fg, src > snk);
+    fg = Runtime::new().run(fg)?;
+
+    let snk = fg.kernel::<VectorSink<Complex32>>(snk).unwrap();
+    assert_eq!(snk.items(), &orig);
+
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn unix_socket_sink() -> Result<()> {
+    use std::os::unix::net::UnixListener;
+
+    let path = temp_path("sink.sock");
+    let _ = std::fs::remove_file(&path);
+    let listener = UnixListener::bind(&path)?;
+    let reader = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut bytes = vec![];
+        stream.read_to_end(&mut bytes).unwrap();
+        bytes
+    });
+
+    let mut fg = Flowgraph::new();
+    let src = VectorSource::<u16>::new((0..1000).collect());
+    let snk = StdInOutBuilder::<u16>::write_to(ByteStreamEndpoint::UnixSocket(path.clone()))
+        .as_le()
+        .build();
+    connect!(fg, src > snk);
+    Runtime::new().run(fg)?;
+
+    let bytes = reader.join().unwrap();
+    let expected: Vec<u8> = (0..1000u16).flat_map(|v| v.to_le_bytes()).collect();
+    assert_eq!(bytes, expected);
+
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn unix_socket_source_reconnects() -> Result<()> {
+    use std::os::unix::net::UnixListener;
+    use std::time::Duration;
+
+    let path = temp_path("source.sock");
+    let _ = std::fs::remove_file(&path);
+    let listener = UnixListener::bind(&path)?;
+    let writer = std::thread::spawn(move || {
+        // first connection is dropped after a few samples, the second one carries the rest
+        for chunk in [0..100i32, 100..200i32] {
+            let (mut stream, _) = listener.accept().unwrap();
+            let bytes: Vec<u8> = chunk.flat_map(|v| v.to_be_bytes()).collect();
+            stream.write_all(&bytes).unwrap();
+        }
+    });
+
+    let mut fg = Flowgraph::new();
+    let src = StdInOutBuilder::<i32>::read_from(ByteStreamEndpoint::UnixSocket(path.clone()))
+        .as_be()
+        .reconnect(Duration::from_millis(10))
+        .build();
+    let head = futuresdr::blocks::Head::<i32>::new(200);
+    let snk = VectorSinkBuilder::<i32>::new().build();
+    connect!(fg, src > head > snk);
+    fg = Runtime::new().run(fg)?;
+    writer.join().unwrap();
+
+    let snk = fg.kernel::<VectorSink<i32>>(snk).unwrap();
+    assert_eq!(snk.items(), &(0..200).collect::<Vec<i32>>());
+
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[test]
+fn tcp_client_source() -> Result<()> {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+    let addr = listener.local_addr()?.to_string();
+    let server = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let bytes: Vec<u8> = (0..500).flat_map(|v: i16| v.to_le_bytes()).collect();
+        stream.write_all(&bytes).unwrap();
+    });
+
+    let mut fg = Flowgraph::new();
+    let src = StdInOutBuilder::<i16>::read_from(ByteStreamEndpoint::TcpClient(addr))
+        .as_le()
+        .build();
+    let snk = VectorSinkBuilder::<i16>::new().build();
+    connect!(fg, src > snk);
+    fg = Runtime::new().run(fg)?;
+    server.join().unwrap();
+
+    let snk = fg.kernel::<VectorSink<i16>>(snk).unwrap();
+    assert_eq!(snk.items(), &(0..500).collect::<Vec<i16>>());
+
+    Ok(())
+}
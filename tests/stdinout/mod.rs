@@ -0,0 +1,2 @@
+pub mod byte_stream;
+pub mod stdout_sink;
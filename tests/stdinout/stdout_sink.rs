@@ -0,0 +1,108 @@
+use fsdr_blocks::stdinout::*;
+use futuresdr::blocks::VectorSource;
+use futuresdr::macros::connect;
+use futuresdr::num_complex::Complex32;
+use futuresdr::runtime::Flowgraph;
+use futuresdr::runtime::Result;
+use futuresdr::runtime::Runtime;
+use std::process::Command;
+
+/// Set when the test binary is run as the child process of `stdout_sink_writes_stdout`
+const CHILD_ENV: &str = "FSDR_BLOCKS_STDOUT_CHILD";
+
+fn to_bytes<T: BytesOrdered>(v: T, order: BytesOrder) -> Vec<u8> {
+    let mut bytes = vec![0u8; T::SIZE];
+    v.write_bytes(order, &mut bytes);
+    bytes
+}
+
+#[test]
+fn bytes_order_primitives() {
+    assert_eq!(to_bytes(0x0102i16, BytesOrder::BigEndian), vec![1, 2]);
+    assert_eq!(to_bytes(0x0102i16, BytesOrder::LittleEndian), vec![2, 1]);
+    assert_eq!(
+        to_bytes(0x01020304u32, BytesOrder::Native),
+        0x01020304u32.to_ne_bytes().to_vec()
+    );
+    assert_eq!(
+        to_bytes(1.0f32, BytesOrder::BigEndian),
+        vec![0x3f, 0x80, 0, 0]
+    );
+}
+
+#[test]
+fn bytes_order_complex() {
+    assert_eq!(Complex32::SIZE, 8);
+    assert_eq!(
+        to_bytes(Complex32::new(1.0, -2.0), BytesOrder::BigEndian),
+        vec![0x3f, 0x80, 0, 0, 0xc0, 0, 0, 0]
+    );
+    assert_eq!(
+        to_bytes(Complex32::new(1.0, -2.0), BytesOrder::LittleEndian),
+        vec![0, 0, 0x80, 0x3f, 0, 0, 0, 0xc0]
+    );
+}
+
+#[test]
+fn file_sink_terminates() -> Result<()> {
+    let mut fg = Flowgraph::new();
+
+    // several times the buffer, written to the null device rather than the test output
+    let null = if cfg!(windows) { "NUL" } else { "/dev/null" };
+    let src = VectorSource::<i16>::new(vec![0; 10_000]);
+    let snk = StdInOutBuilder::<i16>::write_to(ByteStreamEndpoint::File(null.into()))
+        .as_le()
+        .buffer_size(1024)
+        .build();
+
+    connect!(fg, src > snk);
+    Runtime::new().run(fg)?;
+
+    Ok(())
+}
+
+/// Samples written to stdout by the child process, as little endian bytes
+fn stdout_samples() -> Vec<i16> {
+    (0..5000).collect()
+}
+
+#[test]
+fn stdout_sink_child() -> Result<()> {
+    if std::env::var_os(CHILD_ENV).is_none() {
+        return Ok(());
+    }
+
+    let mut fg = Flowgraph::new();
+    let src = VectorSource::<i16>::new(stdout_samples());
+    let snk = StdInOutBuilder::<i16>::stdout()
+        .as_le()
+        .buffer_size(1024)
+        .build();
+    connect!(fg, src > snk);
+    Runtime::new().run(fg)?;
+
+    Ok(())
+}
+
+#[test]
+fn stdout_sink_writes_stdout() -> Result<()> {
+    let output = Command::new(std::env::current_exe()?)
+        .args([
+            "--exact",
+            "stdinout::stdout_sink::stdout_sink_child",
+            "--nocapture",
+            "--quiet",
+        ])
+        .env(CHILD_ENV, "1")
+        .output()?;
+    assert!(output.status.success());
+
+    // the samples are surrounded by the report of the test harness
+    let expected: Vec<u8> = stdout_samples()
+        .iter()
+        .flat_map(|v| v.to_le_bytes())
+        .collect();
+    assert!(output.stdout.windows(expected.len()).any(|w| w == expected));
+
+    Ok(())
+}
@@ -4,8 +4,15 @@ mod async_channel;
 mod channel;
 #[cfg(feature = "cw")]
 mod cw;
+#[cfg(feature = "zeromq")]
+mod zeromq;
 
+mod agc;
 mod math;
+mod message;
+mod net;
 mod serde_pmt;
 mod sigmf;
+mod squelch;
+mod stdinout;
 mod stream;
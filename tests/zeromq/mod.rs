@@ -0,0 +1 @@
+pub mod zmq_blocks;
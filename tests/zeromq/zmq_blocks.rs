@@ -0,0 +1,148 @@
+use fsdr_blocks::zeromq::{SocketType, ZmqBuilder, ZmqSink, ZmqSource};
+use futuresdr::async_io::block_on;
+use futuresdr::blocks::VectorSource;
+use futuresdr::macros::connect;
+use futuresdr::runtime::Flowgraph;
+use futuresdr::runtime::Result;
+use futuresdr::runtime::Runtime;
+
+const MAGIC: [u8; 2] = 0x5FF0u16.to_le_bytes();
+
+fn header(offset: u64, tags: &[(u64, &str, f64)]) -> Vec<u8> {
+    let mut h = MAGIC.to_vec();
+    h.push(0x01);
+    h.extend_from_slice(&offset.to_le_bytes());
+    h.extend_from_slice(&(tags.len() as u64).to_le_bytes());
+    for (o, k, v) in tags {
+        h.extend_from_slice(&o.to_le_bytes());
+        // symbol key, double value, PMT_F source id
+        h.push(0x02);
+        h.extend_from_slice(&(k.len() as u16).to_be_bytes());
+        h.extend_from_slice(k.as_bytes());
+        h.push(0x04);
+        h.extend_from_slice(&v.to_be_bytes());
+        h.push(0x01);
+    }
+    h
+}
+
+/// Parse a message with a tag header of `f32` tags, as written by [`header`], into the
+/// offset of its first sample, its tags, and its little endian `f32` samples.
+fn parse(frame: &[u8]) -> (u64, Vec<(u64, String, f32)>, Vec<f32>) {
+    assert_eq!(&frame[..2], &MAGIC);
+    let offset = u64::from_le_bytes(frame[3..11].try_into().unwrap());
+    let ntags = u64::from_le_bytes(frame[11..19].try_into().unwrap());
+    let mut tags = vec![];
+    let mut rest = &frame[19..];
+    for _ in 0..ntags {
+        let o = u64::from_le_bytes(rest[..8].try_into().unwrap());
+        let key_len = u16::from_be_bytes(rest[9..11].try_into().unwrap()) as usize;
+        let key = String::from_utf8(rest[11..11 + key_len].to_vec()).unwrap();
+        let value = &rest[11 + key_len..];
+        assert_eq!(value[0], 0x04);
+        let v = f64::from_be_bytes(value[1..9].try_into().unwrap());
+        tags.push((o, key, v as f32));
+        rest = &value[10..];
+    }
+    let samples = rest
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes(b.try_into().unwrap()))
+        .collect();
+    (offset, tags, samples)
+}
+
+#[test]
+fn zmq_push_sink_topic() -> Result<()> {
+    let ctx = zmq::Context::new();
+    let pull = ctx.socket(zmq::PULL)?;
+    pull.set_rcvtimeo(5000)?;
+    pull.connect("tcp://127.0.0.1:45831")?;
+
+    let mut fg = Flowgraph::new();
+    let src = VectorSource::<u16>::new((0..100).collect());
+    let snk = ZmqBuilder::<u16>::pusher("tcp://127.0.0.1:45831")
+        .as_be()
+        .topic("iq")
+        .build();
+    connect!(fg, src > snk);
+    Runtime::new().run(fg)?;
+
+    let mut received = vec![];
+    while received.len() < 200 {
+        let frames = pull.recv_multipart(0)?;
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0], b"iq");
+        received.extend_from_slice(&frames[1]);
+    }
+    let expected: Vec<u8> = (0..100u16).flat_map(|v| v.to_be_bytes()).collect();
+    assert_eq!(received, expected);
+
+    Ok(())
+}
+
+#[test]
+fn zmq_tags_roundtrip() -> Result<()> {
+    let ctx = zmq::Context::new();
+    let push = ctx.socket(zmq::PUSH)?;
+    push.bind("tcp://127.0.0.1:45832")?;
+    let pull = ctx.socket(zmq::PULL)?;
+    pull.set_rcvtimeo(5000)?;
+    pull.bind("tcp://127.0.0.1:45833")?;
+
+    let mut fg = Flowgraph::new();
+    let src = ZmqBuilder::<f32>::puller("tcp://127.0.0.1:45832")
+        .as_le()
+        .pass_tags(true)
+        .build();
+    let snk = ZmqBuilder::<f32>::pusher("tcp://127.0.0.1:45833")
+        .bind(false)
+        .as_le()
+        .pass_tags(true)
+        .build();
+    connect!(fg, src > snk);
+
+    let rt = Runtime::new();
+    let received = block_on(async move {
+        let (task, mut handle) = rt.start(fg).await;
+
+        // samples 1000..1004, tagged on the third one
+        let mut msg = header(1000, &[(1002, "rx_freq", 433.92e6)]);
+        for v in [1.0f32, 2.0, 3.0, 4.0] {
+            msg.extend_from_slice(&v.to_le_bytes());
+        }
+        push.send(&msg, 0).unwrap();
+
+        let mut received = vec![];
+        let mut samples = 0;
+        while samples < 4 {
+            let frame = pull.recv_bytes(0).unwrap();
+            samples += parse(&frame).2.len();
+            received.push(frame);
+        }
+        handle.terminate().await.unwrap();
+        task.await.unwrap();
+        received
+    });
+
+    // the sink counts samples from 0, the tag lands on the third sample
+    let mut all_tags = vec![];
+    let mut all_samples = vec![];
+    for frame in received {
+        let (offset, tags, samples) = parse(&frame);
+        assert_eq!(offset as usize, all_samples.len());
+        all_tags.extend(tags);
+        all_samples.extend(samples);
+    }
+    assert_eq!(all_samples, vec![1.0, 2.0, 3.0, 4.0]);
+    assert_eq!(all_tags, vec![(2, "rx_freq".to_string(), 433.92e6f32)]);
+
+    Ok(())
+}
+
+#[test]
+fn zmq_unsupported_socket_types() {
+    assert!(ZmqBuilder::<u8>::new(SocketType::REQ, "tcp://127.0.0.1:45834").is_err());
+    assert!(ZmqSink::<u8>::new(SocketType::SUB, "tcp://127.0.0.1:45834").is_err());
+    assert!(ZmqSource::<u8>::new(SocketType::PUB, "tcp://127.0.0.1:45834").is_err());
+    assert!(ZmqSource::<u8>::new(SocketType::PULL, "tcp://127.0.0.1:45834").is_ok());
+}